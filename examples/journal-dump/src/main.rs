@@ -0,0 +1,40 @@
+use fjall::tools::journal;
+use std::path::PathBuf;
+
+fn main() -> fjall::Result<()> {
+    let mut args = std::env::args().skip(1);
+
+    let mut journal_path = None;
+    let mut db_path = None;
+    let mut should_truncate = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--db" => db_path = args.next().map(PathBuf::from),
+            "--truncate" => should_truncate = true,
+            _ => journal_path = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let Some(journal_path) = journal_path else {
+        eprintln!("usage: journal-dump <journal file> [--db <database folder>] [--truncate]");
+        std::process::exit(1);
+    };
+
+    let mut dump = journal::inspect(&journal_path)?;
+
+    if let Some(db_path) = db_path {
+        dump.resolve_keyspace_names(db_path)?;
+    }
+
+    print!("{dump}");
+
+    if should_truncate {
+        if let Some(offset) = dump.first_corrupt_offset {
+            journal::truncate(&journal_path, offset)?;
+            println!("truncated journal to {offset}B");
+        }
+    }
+
+    Ok(())
+}
@@ -39,9 +39,10 @@ pub fn handle_journal(
 
                 let keyspaces = keyspaces.read().expect("lock is poisoned");
 
+                // NOTE: Flushing keyspaces without journaling does not free up any journals
                 let mut keyspaces_with_seqno = keyspaces
                     .values()
-                    .filter(|x| x.tree.active_memtable().size() > 0)
+                    .filter(|x| x.config.journaling && x.tree.active_memtable().size() > 0)
                     .map(|x| (x.clone(), x.tree.get_highest_persisted_seqno()))
                     .collect::<Vec<_>>();
 
@@ -0,0 +1,510 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    checkpoint::{link_or_copy, write_synced, DatabaseFiles},
+    file::{fsync_directory, FJALL_MARKER, KEYSPACES_FOLDER, LOCK_FILE},
+    HashMap,
+};
+use lsm_tree::{
+    file::{BLOBS_FOLDER, TABLES_FOLDER},
+    SeqNo,
+};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+/// Identifies a backup inside a backup folder
+///
+/// Backup IDs start at 1 and increase with every backup.
+pub type BackupId = u64;
+
+/// Contains tables and blob files, which are shared between backups
+const FILES_FOLDER: &str = "files";
+
+/// Lists the files of a backup, it is written last, so only complete backups have a manifest
+const MANIFEST_FILE: &str = "manifest";
+
+/// A table or blob file stored in the backup folder
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct FileEntry {
+    /// Folder name of the keyspace
+    keyspace: String,
+
+    id: u64,
+    checksum: u128,
+}
+
+impl FileEntry {
+    /// Returns the path of the file inside the backup folder.
+    ///
+    /// IDs may be reused (e.g. after deleting a keyspace), so the checksum is part of the file name.
+    fn backup_path(&self, path: &Path, kind: &str) -> PathBuf {
+        path.join(FILES_FOLDER)
+            .join(&self.keyspace)
+            .join(kind)
+            .join(format!("{}-{:032x}", self.id, self.checksum))
+    }
+}
+
+/// Lists the files a backup consists of
+///
+/// Every backup lists all of its tables and blob files, including the ones
+/// that were already copied by previous backups.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Manifest {
+    /// Every batch below this seqno is contained in the backup
+    seqno: SeqNo,
+
+    /// The backup this backup was based on
+    parent: Option<BackupId>,
+
+    tables: Vec<FileEntry>,
+    blob_files: Vec<FileEntry>,
+}
+
+impl Manifest {
+    fn encode(&self) -> String {
+        use std::fmt::Write;
+
+        let mut s = format!("seqno {}\n", self.seqno);
+
+        if let Some(parent) = self.parent {
+            let _ = writeln!(s, "parent {parent}");
+        }
+
+        for (kind, entries) in [("table", &self.tables), ("blob", &self.blob_files)] {
+            for entry in entries {
+                let _ = writeln!(
+                    s,
+                    "{kind} {} {} {:032x}",
+                    entry.keyspace, entry.id, entry.checksum,
+                );
+            }
+        }
+
+        s
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        let mut manifest = Self::default();
+
+        for line in s.lines() {
+            let mut parts = line.split(' ');
+
+            match parts.next()? {
+                "seqno" => manifest.seqno = parts.next()?.parse().ok()?,
+                "parent" => manifest.parent = Some(parts.next()?.parse().ok()?),
+                kind @ ("table" | "blob") => {
+                    let entry = FileEntry {
+                        keyspace: parts.next()?.into(),
+                        id: parts.next()?.parse().ok()?,
+                        checksum: u128::from_str_radix(parts.next()?, 16).ok()?,
+                    };
+
+                    if kind == "table" {
+                        manifest.tables.push(entry);
+                    } else {
+                        manifest.blob_files.push(entry);
+                    }
+                }
+                _ => return None,
+            }
+        }
+
+        Some(manifest)
+    }
+
+    fn read(path: &Path, backup_id: BackupId) -> crate::Result<Self> {
+        let manifest_path = path.join(backup_id.to_string()).join(MANIFEST_FILE);
+
+        if !manifest_path.try_exists()? {
+            return Err(crate::Error::BackupNotFound(backup_id));
+        }
+
+        let bytes = std::fs::read_to_string(&manifest_path)?;
+
+        Self::decode(&bytes).ok_or_else(|| {
+            log::error!("Invalid backup manifest at {}", manifest_path.display());
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid backup manifest").into()
+        })
+    }
+}
+
+/// Lists all complete backups in the backup folder, in ascending order.
+pub fn list_backups(path: &Path) -> crate::Result<Vec<BackupId>> {
+    if !path.try_exists()? {
+        return Ok(vec![]);
+    }
+
+    let mut ids = vec![];
+
+    for dirent in std::fs::read_dir(path)? {
+        let dirent = dirent?;
+
+        let Some(id) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<BackupId>().ok())
+        else {
+            continue;
+        };
+
+        if dirent.path().join(MANIFEST_FILE).try_exists()? {
+            ids.push(id);
+        }
+    }
+
+    ids.sort_unstable();
+
+    Ok(ids)
+}
+
+/// Returns the ID for the next backup, skipping incomplete backups.
+fn next_backup_id(path: &Path) -> crate::Result<BackupId> {
+    let mut max_id = 0;
+
+    for dirent in std::fs::read_dir(path)? {
+        let dirent = dirent?;
+
+        if let Some(id) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<BackupId>().ok())
+        {
+            max_id = max_id.max(id);
+        }
+    }
+
+    Ok(max_id + 1)
+}
+
+/// Creates the folder of the next backup.
+///
+/// Backups may be created concurrently (e.g. by another process), so creating the folder
+/// is retried using the next ID if it already exists.
+fn create_backup_folder(path: &Path) -> crate::Result<(BackupId, PathBuf)> {
+    loop {
+        let backup_id = next_backup_id(path)?;
+        let backup_path = path.join(backup_id.to_string());
+
+        match std::fs::create_dir(&backup_path) {
+            Ok(()) => return Ok((backup_id, backup_path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                log::debug!("Backup {backup_id} was created concurrently, retrying");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Copies a file into the backup folder, unless it is already contained in the `parent` backup.
+///
+/// The file is copied using a temporary name, so files in the backup folder are always complete.
+fn backup_file(
+    src: &Path,
+    entry: &FileEntry,
+    path: &Path,
+    kind: &str,
+    parent: Option<&HashSet<&FileEntry>>,
+) -> crate::Result<bool> {
+    let dest = entry.backup_path(path, kind);
+
+    if parent.is_some_and(|files| files.contains(entry)) && dest.try_exists()? {
+        return Ok(false);
+    }
+
+    #[expect(clippy::expect_used, reason = "backup paths always have a parent")]
+    let folder = dest.parent().expect("should have parent");
+    std::fs::create_dir_all(folder)?;
+
+    let mut temp_path = dest.clone().into_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    if temp_path.try_exists()? {
+        std::fs::remove_file(&temp_path)?;
+    }
+
+    link_or_copy(src, &temp_path, false)?;
+    std::fs::rename(&temp_path, &dest)?;
+
+    fsync_directory(folder)?;
+
+    Ok(true)
+}
+
+/// Creates a backup in the backup folder at `path`.
+///
+/// If `since` is given, only tables and blob files that are not contained in that backup are copied.
+pub fn backup(
+    db: &crate::Database,
+    path: &Path,
+    since: Option<BackupId>,
+) -> crate::Result<BackupId> {
+    if db.config.read_only {
+        return Err(crate::Error::ReadOnly);
+    }
+
+    std::fs::create_dir_all(path)?;
+
+    let parent = since
+        .map(|backup_id| Manifest::read(path, backup_id))
+        .transpose()?;
+
+    let parent_files = parent.as_ref().map(|manifest| {
+        manifest
+            .tables
+            .iter()
+            .chain(&manifest.blob_files)
+            .collect::<HashSet<_>>()
+    });
+
+    let (backup_id, backup_path) = create_backup_folder(path)?;
+
+    log::info!(
+        "Creating backup {backup_id} at {} (since={since:?})",
+        path.display(),
+    );
+
+    let DatabaseFiles {
+        seqno,
+        meta_tree,
+        trees,
+    } = DatabaseFiles::capture(db, &backup_path)?;
+
+    let keyspaces_folder = backup_path.join(KEYSPACES_FOLDER);
+
+    // NOTE: The meta keyspace is small, so it is stored in every backup
+    meta_tree.write_to(&keyspaces_folder, false)?;
+
+    let mut manifest = Manifest {
+        seqno,
+        parent: since,
+        tables: vec![],
+        blob_files: vec![],
+    };
+
+    let mut copied_count = 0;
+
+    for tree in &trees {
+        tree.write_version(&keyspaces_folder.join(tree.folder_name()))?;
+
+        for table in tree.tables() {
+            let entry = FileEntry {
+                keyspace: tree.folder_name().into(),
+                id: table.id(),
+                checksum: table.checksum().into_u128(),
+            };
+
+            if backup_file(
+                &table.path,
+                &entry,
+                path,
+                TABLES_FOLDER,
+                parent_files.as_ref(),
+            )? {
+                copied_count += 1;
+            }
+
+            manifest.tables.push(entry);
+        }
+
+        for blob_file in tree.blob_files() {
+            let entry = FileEntry {
+                keyspace: tree.folder_name().into(),
+                id: blob_file.id(),
+                checksum: blob_file.checksum().into_u128(),
+            };
+
+            if backup_file(
+                blob_file.path(),
+                &entry,
+                path,
+                BLOBS_FOLDER,
+                parent_files.as_ref(),
+            )? {
+                copied_count += 1;
+            }
+
+            manifest.blob_files.push(entry);
+        }
+    }
+
+    write_synced(
+        &backup_path.join(FJALL_MARKER),
+        &std::fs::read(db.config.path.join(FJALL_MARKER))?,
+    )?;
+
+    fsync_directory(&keyspaces_folder)?;
+    fsync_directory(&backup_path)?;
+
+    // IMPORTANT: Lastly, write the manifest, which marks the backup as complete
+    write_synced(
+        &backup_path.join(MANIFEST_FILE),
+        manifest.encode().as_bytes(),
+    )?;
+    fsync_directory(&backup_path)?;
+    fsync_directory(path)?;
+
+    log::info!(
+        "Created backup {backup_id} with seqno={seqno}, copied {copied_count} of {} files",
+        manifest.tables.len() + manifest.blob_files.len(),
+    );
+
+    Ok(backup_id)
+}
+
+/// Restores the backup into a new database folder.
+pub fn restore(path: &Path, backup_id: BackupId, target: &Path) -> crate::Result<()> {
+    let manifest = Manifest::read(path, backup_id)?;
+    let backup_path = path.join(backup_id.to_string());
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::create_dir(target)?;
+
+    log::info!(
+        "Restoring backup {backup_id} from {} to {}",
+        path.display(),
+        target.display(),
+    );
+
+    // Journals
+    for dirent in std::fs::read_dir(&backup_path)? {
+        let dirent = dirent?;
+
+        if dirent
+            .path()
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jnl"))
+        {
+            link_or_copy(&dirent.path(), &target.join(dirent.file_name()), false)?;
+        }
+    }
+
+    let keyspaces_folder = target.join(KEYSPACES_FOLDER);
+
+    // NOTE: Copy the meta keyspace, and the version files of the other keyspaces
+    let mut tree_folders: HashMap<String, PathBuf> = HashMap::default();
+
+    for dirent in std::fs::read_dir(backup_path.join(KEYSPACES_FOLDER))? {
+        let dirent = dirent?;
+        let tree_folder = keyspaces_folder.join(dirent.file_name());
+
+        copy_dir(&dirent.path(), &tree_folder)?;
+
+        tree_folders.insert(dirent.file_name().to_string_lossy().into(), tree_folder);
+    }
+
+    for (kind, entries) in [
+        (TABLES_FOLDER, &manifest.tables),
+        (BLOBS_FOLDER, &manifest.blob_files),
+    ] {
+        for entry in entries {
+            let Some(tree_folder) = tree_folders.get(&entry.keyspace) else {
+                log::error!("Keyspace {} is missing in backup", entry.keyspace);
+                return Err(crate::Error::Unrecoverable);
+            };
+
+            let folder = tree_folder.join(kind);
+            std::fs::create_dir_all(&folder)?;
+
+            link_or_copy(
+                &entry.backup_path(path, kind),
+                &folder.join(entry.id.to_string()),
+                true,
+            )?;
+        }
+    }
+
+    for tree_folder in tree_folders.values() {
+        for kind in [TABLES_FOLDER, BLOBS_FOLDER] {
+            let folder = tree_folder.join(kind);
+
+            if folder.try_exists()? {
+                fsync_directory(&folder)?;
+            }
+        }
+        fsync_directory(tree_folder)?;
+    }
+
+    std::fs::File::create_new(target.join(LOCK_FILE))?.sync_all()?;
+
+    // NOTE: Lastly, write the version marker, so the database is only recovered once complete
+    write_synced(
+        &target.join(FJALL_MARKER),
+        &std::fs::read(backup_path.join(FJALL_MARKER))?,
+    )?;
+
+    // IMPORTANT: fsync folders on Unix
+    fsync_directory(&keyspaces_folder)?;
+    fsync_directory(target)?;
+
+    log::info!("Restored backup {backup_id} with seqno={}", manifest.seqno);
+
+    Ok(())
+}
+
+/// Recursively copies a folder.
+fn copy_dir(src: &Path, dest: &Path) -> crate::Result<()> {
+    std::fs::create_dir_all(dest)?;
+
+    for dirent in std::fs::read_dir(src)? {
+        let dirent = dirent?;
+        let dest = dest.join(dirent.file_name());
+
+        if dirent.file_type()?.is_dir() {
+            copy_dir(&dirent.path(), &dest)?;
+        } else {
+            link_or_copy(&dirent.path(), &dest, false)?;
+        }
+    }
+
+    fsync_directory(dest)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn backup_manifest_roundtrip() {
+        let manifest = Manifest {
+            seqno: 5,
+            parent: Some(2),
+            tables: vec![
+                FileEntry {
+                    keyspace: "1".into(),
+                    id: 0,
+                    checksum: u128::MAX,
+                },
+                FileEntry {
+                    keyspace: "2".into(),
+                    id: 7,
+                    checksum: 0,
+                },
+            ],
+            blob_files: vec![FileEntry {
+                keyspace: "2".into(),
+                id: 1,
+                checksum: 12_345,
+            }],
+        };
+
+        assert_eq!(
+            Some(&manifest),
+            Manifest::decode(&manifest.encode()).as_ref()
+        );
+
+        assert_eq!(None, Manifest::decode("seqno x"));
+        assert_eq!(None, Manifest::decode("table 1 2"));
+        assert_eq!(None, Manifest::decode("hello"));
+    }
+}
@@ -4,7 +4,10 @@
 
 pub mod item;
 
-use crate::{Database, Keyspace, PersistMode};
+use crate::{
+    change_feed::{ChangeBatch, ChangeItem},
+    Database, Keyspace, PersistMode,
+};
 use item::Item;
 use lsm_tree::{AbstractTree, UserKey, UserValue, ValueType};
 use std::collections::HashSet;
@@ -12,6 +15,10 @@ use std::collections::HashSet;
 /// An atomic write batch
 ///
 /// Allows atomically writing across keyspaces inside the [`Database`].
+///
+/// A batch can not write into both keyspaces with and without journaling
+/// (see `KeyspaceCreateOptions::journaling`), because only the journaled part
+/// would be recovered after a restart.
 pub struct WriteBatch {
     pub(crate) data: Vec<Item>,
     db: Database,
@@ -57,6 +64,18 @@ impl WriteBatch {
     }
 
     /// Sets the durability level.
+    ///
+    /// Concurrent commits using [`PersistMode::SyncData`] or [`PersistMode::SyncAll`]
+    /// share a single sync of the journal (group commit).
+    /// The batch only becomes visible to readers (and change feed subscribers)
+    /// once it is durable. If syncing fails, the database is poisoned,
+    /// and neither the batch nor any later write is published.
+    ///
+    /// Writes become visible in seqno order, so while a batch waits for its sync,
+    /// later writes, even those without durability, wait for it before they become visible.
+    ///
+    /// Flushes, compactions and ingestions can still advance the visible seqno
+    /// while a batch waits for its sync, making the batch visible slightly early.
     #[must_use]
     pub fn durability(mut self, mode: Option<PersistMode>) -> Self {
         self.durability = mode;
@@ -95,8 +114,10 @@ impl WriteBatch {
     ///
     /// # Errors
     ///
-    /// Will return `Err` if an IO error occurs.
+    /// Will return `Err` if an IO error occurs, or if the batch writes into both
+    /// keyspaces with and without journaling.
     #[allow(clippy::missing_panics_doc)]
+    #[expect(clippy::too_many_lines)]
     pub fn commit(mut self) -> crate::Result<()> {
         use std::sync::atomic::Ordering;
 
@@ -104,6 +125,22 @@ impl WriteBatch {
             return Ok(());
         }
 
+        if self.db.config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
+        // NOTE: Items of keyspaces without journaling go straight into the memtable,
+        // so a batch that mixes both would only be recovered partially
+        let journaled_count = self
+            .data
+            .iter()
+            .filter(|item| item.keyspace.config.journaling)
+            .count();
+
+        if journaled_count != 0 && journaled_count != self.data.len() {
+            return Err(crate::Error::MixedJournaling);
+        }
+
         log::trace!("batch: Acquiring journal writer");
         let mut journal_writer = self.db.journal.get_writer();
 
@@ -114,20 +151,44 @@ impl WriteBatch {
 
         let batch_seqno = self.db.supervisor.seqno.next();
 
-        let _ = journal_writer.write_batch(self.data.iter(), self.data.len(), batch_seqno);
+        if journaled_count > 0 {
+            // NOTE: The batch is encoded (compressed and encrypted) before anything is written,
+            // so if that fails, the journal is left untouched
+            journal_writer.encode_batch(self.data.iter(), journaled_count, batch_seqno)?;
 
-        if let Some(mode) = self.durability {
-            if let Err(e) = journal_writer.persist(mode) {
+            if let Err(e) = journal_writer.write_encoded() {
                 self.db.is_poisoned.store(true, Ordering::Release);
 
                 log::error!(
-                    "persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                    "write failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
                 );
 
                 return Err(crate::Error::Poisoned);
             }
         }
 
+        // NOTE: Syncing is deferred until the journal writer lock is released,
+        // so concurrent commits can share a single sync
+        let sync_ticket = match self.durability {
+            _ if journaled_count == 0 => Ok(None),
+            Some(PersistMode::Buffer) => journal_writer.persist(PersistMode::Buffer).map(|()| None),
+            Some(mode) => journal_writer.persist_deferred(mode).map(Some),
+            None => Ok(None),
+        };
+
+        let sync_ticket = match sync_ticket {
+            Ok(sync_ticket) => sync_ticket,
+            Err(e) => {
+                self.db.is_poisoned.store(true, Ordering::Release);
+
+                log::error!(
+                    "persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                );
+
+                return Err(crate::Error::Poisoned);
+            }
+        };
+
         // TODO: maybe we can use a stack alloc hashset/vec here, such as smallset
         #[expect(clippy::mutable_key_type)]
         let mut keyspaces_with_possible_stall = HashSet::new();
@@ -135,6 +196,25 @@ impl WriteBatch {
         #[expect(clippy::expect_used)]
         let keyspaces = self.db.keyspaces.read().expect("lock is poisoned");
 
+        let change = self
+            .db
+            .supervisor
+            .change_feed
+            .has_subscribers()
+            .then(|| ChangeBatch {
+                seqno: batch_seqno,
+                items: self
+                    .data
+                    .iter()
+                    .map(|item| ChangeItem {
+                        keyspace: item.keyspace.name.clone(),
+                        key: item.key.clone(),
+                        value: item.value.clone(),
+                        change_type: item.value_type.into(),
+                    })
+                    .collect(),
+            });
+
         let mut batch_size = 0u64;
 
         log::trace!("Applying batch (size={}) to memtable(s)", self.data.len());
@@ -154,7 +234,9 @@ impl WriteBatch {
             keyspaces_with_possible_stall.insert(item.keyspace.clone());
         }
 
-        self.db.supervisor.snapshot_tracker.publish(batch_seqno);
+        // NOTE: The batch is only published after it is persisted,
+        // and the publish queue keeps later writes from overtaking it
+        let publish_slot = self.db.supervisor.publish_queue.reserve();
 
         drop(journal_writer);
 
@@ -162,6 +244,26 @@ impl WriteBatch {
 
         drop(keyspaces);
 
+        if let Some(sync_ticket) = sync_ticket {
+            if let Err(e) = sync_ticket.wait() {
+                self.db.is_poisoned.store(true, Ordering::Release);
+
+                log::error!(
+                    "persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                );
+
+                return Err(crate::Error::Poisoned);
+            }
+        }
+
+        publish_slot.publish(|| {
+            self.db.supervisor.snapshot_tracker.publish(batch_seqno);
+
+            if let Some(change) = change {
+                self.db.supervisor.change_feed.publish(change);
+            }
+        });
+
         // IMPORTANT: Add batch size to current write buffer size
         // Otherwise write buffer growth is unbounded when using batches
         self.db.supervisor.write_buffer_size.allocate(batch_size);
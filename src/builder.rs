@@ -2,9 +2,12 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use crate::{tx::single_writer::Openable, Config};
-use lsm_tree::{Cache, CompressionType, DescriptorTable};
-use std::{marker::PhantomData, path::Path, sync::Arc};
+use crate::{
+    path::absolute_path, tx::single_writer::Openable, Config, EncryptionProvider, JournalArchiver,
+    JournalCompression, RecoveryMode,
+};
+use lsm_tree::{Cache, DescriptorTable, SeqNo};
+use std::{marker::PhantomData, path::Path, sync::Arc, time::Duration};
 
 /// Database builder
 pub struct Builder<O: Openable> {
@@ -35,10 +38,149 @@ impl<O: Openable> Builder<O> {
         O::open(self.inner)
     }
 
+    /// Sets the folder the journals are stored in.
+    ///
+    /// Can be used to put the journals on a different (e.g. faster) disk than the keyspaces.
+    ///
+    /// The folder is recorded when creating the database. Reopening the database using
+    /// a different journal folder fails with [`Error::JournalPathMismatch`](crate::Error::JournalPathMismatch).
+    ///
+    /// Creating a database fails if the journal folder already contains journals,
+    /// so the folder can not be shared by multiple databases.
+    ///
+    /// Default = same as the database folder
+    #[must_use]
+    pub fn journal_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.inner.journal_path = absolute_path(path.as_ref());
+        self
+    }
+
     /// Sets the compression type to use for large values that are written into the journal file.
+    ///
+    /// Accepts a [`CompressionType`](crate::CompressionType), or a [`JournalCompression`], which
+    /// additionally supports zstd (`zstd` feature).
+    ///
+    /// Zstd is only supported for the journal; keyspace data blocks are compressed by
+    /// `lsm-tree`, which does not support zstd.
+    #[must_use]
+    pub fn journal_compression<C: Into<JournalCompression>>(mut self, comp: C) -> Self {
+        self.inner.journal_compression_type = comp.into();
+        self
+    }
+
+    /// Sets the minimum size of values that are compressed when written into the journal.
+    ///
+    /// Smaller values are written uncompressed, because compressing them barely
+    /// saves space. If 0, journal compression is disabled.
+    ///
+    /// Default = 4 KiB
+    #[must_use]
+    pub fn journal_compression_threshold(mut self, bytes: usize) -> Self {
+        self.inner.journal_compression_threshold = bytes;
+        self
+    }
+
+    /// Sets the recovery mode to use when replaying journals on startup.
+    ///
+    /// Default = [`RecoveryMode::TolerateCorruptTail`]
+    ///
+    /// Use [`RecoveryMode::AbsoluteConsistency`] to fail on any corruption, including
+    /// an incomplete last batch, or [`RecoveryMode::SkipInvalidBatches`] to recover as
+    /// much data as possible.
+    #[must_use]
+    pub fn journal_recovery_mode(mut self, mode: RecoveryMode) -> Self {
+        self.inner.journal_recovery_mode = mode;
+        self
+    }
+
+    /// Recovers the database to the state after the batch with the given seqno was written.
+    ///
+    /// Journaled batches with a higher seqno are **permanently** discarded on recovery, so
+    /// writes after reopening continue from that point in time.
+    /// Together with archived journals (see [`DatabaseBuilder::journal_archiver`](crate::DatabaseBuilder::journal_archiver)),
+    /// this allows point-in-time recovery: copy the archived journals back into the journal folder
+    /// of a restored copy of the database, then open it with the target seqno.
+    ///
+    /// Opening fails with [`Error::RecoveryTargetUnreachable`](crate::Error::RecoveryTargetUnreachable)
+    /// if a keyspace has already flushed data above the target seqno.
+    ///
+    /// Keyspaces that were created or deleted after the target seqno stay created or deleted.
+    ///
+    /// Has no effect when creating a new database.
+    #[must_use]
+    pub fn recover_until_seqno(mut self, seqno: SeqNo) -> Self {
+        self.inner.recover_until_seqno = Some(seqno);
+        self
+    }
+
+    /// Sets the archiver that receives journals once their data has been flushed.
+    ///
+    /// By default, flushed journals are deleted.
+    ///
+    /// Archived journals can be used for point-in-time recovery or incremental backups.
+    /// Use [`DirectoryArchiver`](crate::DirectoryArchiver) to move journals into an archive
+    /// directory, or pass a closure to handle journals yourself.
+    #[must_use]
+    pub fn journal_archiver<A: JournalArchiver + 'static>(mut self, archiver: A) -> Self {
+        self.inner.journal_archiver = Some(Arc::new(archiver));
+        self
+    }
+
+    /// Sets the encryption provider that encrypts the journal.
+    ///
+    /// Only the keys and values written into journals are encrypted.
+    /// Keyspace tables, blob files and the meta keyspace are **not** encrypted,
+    /// so data is stored in plaintext once it is flushed. Encrypting them at rest
+    /// requires encryption support in `lsm-tree`, which does not exist yet.
+    ///
+    /// The same provider needs to be used when reopening the database, otherwise journal
+    /// recovery fails with [`Error::Decrypt`](crate::Error::Decrypt).
+    ///
+    /// See [`EncryptionProvider`] for how to rotate keys.
+    #[must_use]
+    pub fn journal_encryption<E: EncryptionProvider + 'static>(mut self, provider: E) -> Self {
+        self.inner.journal_encryption = Some(Arc::new(provider));
+        self
+    }
+
+    /// Sets the size new journal files are pre-allocated to.
+    ///
+    /// Journals are rotated once they grow past a fraction of
+    /// [`DatabaseBuilder::max_journaling_size`](crate::DatabaseBuilder::max_journaling_size),
+    /// so a smaller pre-allocation saves disk space for small databases.
+    ///
+    /// Set to 0 to disable pre-allocation.
+    ///
+    /// Default = 64 MiB
     #[must_use]
-    pub fn journal_compression(mut self, comp: CompressionType) -> Self {
-        self.inner.journal_compression_type = comp;
+    pub fn journal_preallocation_size(mut self, bytes: u64) -> Self {
+        self.inner.journal_preallocation_size = bytes;
+        self
+    }
+
+    /// Sets the amount of evicted journal files that are kept to be reused as new journals.
+    ///
+    /// Reusing journal files avoids file system metadata updates (creating, pre-allocating
+    /// and deleting files) on workloads that rotate journals often.
+    /// Recycled journal files take up disk space that is not counted towards
+    /// [`DatabaseBuilder::max_journaling_size`](crate::DatabaseBuilder::max_journaling_size).
+    ///
+    /// Journals that are moved away by a
+    /// [`DatabaseBuilder::journal_archiver`](crate::DatabaseBuilder::journal_archiver) are not recycled.
+    ///
+    /// A recycled journal file is not cleared, instead every entry written into it is tagged
+    /// with the journal's ID. So if a crash interrupts a write, the stale data of the file's
+    /// previous use behind the incomplete batch is recognized as the end of the journal,
+    /// and the incomplete batch is discarded like in any other journal
+    /// (see [`RecoveryMode`]).
+    ///
+    /// Read-only databases (and secondaries) hard link the journals, so recycled journals
+    /// are not reused while any read-only database is open.
+    ///
+    /// Default = 0 (disabled)
+    #[must_use]
+    pub fn max_recycled_journals(mut self, n: usize) -> Self {
+        self.inner.max_recycled_journals = n;
         self
     }
 
@@ -53,6 +195,36 @@ impl<O: Openable> Builder<O> {
         self
     }
 
+    /// Syncs the journal in a background thread in the given interval, using [`PersistMode::SyncData`](crate::PersistMode::SyncData).
+    ///
+    /// This bounds the amount of data that can be lost on power loss or OS crash,
+    /// without syncing on every write.
+    ///
+    /// Can be changed at runtime using [`Database::set_journal_sync_interval`](crate::Database::set_journal_sync_interval).
+    ///
+    /// Default = disabled
+    #[must_use]
+    pub fn journal_sync_interval(mut self, interval: Duration) -> Self {
+        self.inner.journal_sync_interval = Some(interval);
+        self
+    }
+
+    /// Syncs the journal in a background thread whenever the given amount of bytes
+    /// has been written to it, using [`PersistMode::SyncData`](crate::PersistMode::SyncData).
+    ///
+    /// Can be combined with [`DatabaseBuilder::journal_sync_interval`](crate::DatabaseBuilder::journal_sync_interval).
+    ///
+    /// Can be changed at runtime using [`Database::set_journal_bytes_per_sync`](crate::Database::set_journal_bytes_per_sync).
+    ///
+    /// Setting 0 disables byte-based syncing.
+    ///
+    /// Default = disabled
+    #[must_use]
+    pub fn journal_bytes_per_sync(mut self, bytes: u64) -> Self {
+        self.inner.journal_bytes_per_sync = (bytes > 0).then_some(bytes);
+        self
+    }
+
     /// Sets the number of worker threads.
     ///
     /// Default = min(# CPU cores, 4)
@@ -143,8 +315,49 @@ impl<O: Openable> Builder<O> {
         self
     }
 
+    /// Opens the database in read-only mode.
+    ///
+    /// A read-only database does not take the database lock, so it can be opened
+    /// while another process writes to the database.
+    /// It contains the data that was written before it was opened, later writes are not visible.
+    ///
+    /// All write operations fail with [`Error::ReadOnly`](crate::Error::ReadOnly),
+    /// and no background threads are started.
+    ///
+    /// The journals and tables are hard linked into a temporary folder inside the database folder,
+    /// so write access to the database folder is still required.
+    /// The linked files are never written to. The folder is deleted when the database is dropped,
+    /// or, if the process crashed, the next time the database is opened writable.
+    ///
+    /// Opening a database that does not exist fails.
+    ///
+    /// Default = false
+    #[must_use]
+    pub fn read_only(mut self, flag: bool) -> Self {
+        self.inner.read_only = flag;
+        self
+    }
+
+    /// If `false`, keyspace folders that are not referenced by any keyspace name,
+    /// or that were never fully initialized, are kept when the database is opened.
+    ///
+    /// Such folders are left behind by deleting or creating a keyspace, if the process crashed
+    /// in between. They may also be the result of a damaged meta keyspace, in which case deleting
+    /// them would lose data, so disable this if they should instead be inspected,
+    /// e.g. using [`Database::repair`](crate::Database::repair).
+    ///
+    /// Default = true
+    #[must_use]
+    pub fn delete_unreferenced_keyspaces(mut self, flag: bool) -> Self {
+        self.inner.delete_unreferenced_keyspaces = flag;
+        self
+    }
+
     /// Sets the `Database` to clean upon drop.
     ///
+    /// If a separate [journal folder](Self::journal_path) is used, only the journals are deleted from it,
+    /// and the folder itself is only deleted if it is empty afterwards.
+    ///
     /// # Examples
     ///
     /// ```
@@ -0,0 +1,266 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    journal::batch_reader::JournalBatchReader,
+    keyspace::{InternalKeyspaceId, KeyspaceKey},
+    meta_keyspace::MetaKeyspace,
+    HashMap,
+};
+use lsm_tree::{SeqNo, UserKey, UserValue, ValueType};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Type of a change
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ChangeType {
+    /// Key was inserted or updated
+    Insert,
+
+    /// Key was removed
+    Remove,
+
+    /// Key was removed using a weak tombstone, which is experimental
+    RemoveWeak,
+}
+
+impl From<ValueType> for ChangeType {
+    fn from(value: ValueType) -> Self {
+        match value {
+            ValueType::Value => Self::Insert,
+            ValueType::Tombstone => Self::Remove,
+            ValueType::WeakTombstone => Self::RemoveWeak,
+            ValueType::Indirection => unreachable!("journal never contains indirections"),
+        }
+    }
+}
+
+/// A single write inside a [`ChangeBatch`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeItem {
+    /// Name of the keyspace that was written to
+    pub keyspace: KeyspaceKey,
+
+    /// User key
+    pub key: UserKey,
+
+    /// User value, empty for removals
+    pub value: UserValue,
+
+    /// Type of change
+    pub change_type: ChangeType,
+}
+
+/// A committed write batch
+///
+/// Single writes (e.g. [`Keyspace::insert`](crate::Keyspace::insert)) are
+/// emitted as a batch of one item.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeBatch {
+    /// Sequence number the batch was committed with
+    pub seqno: SeqNo,
+
+    /// Writes of the batch, in the order they were written
+    pub items: Vec<ChangeItem>,
+}
+
+/// Live feed of committed write batches, see [`Database::subscribe`](crate::Database::subscribe)
+///
+/// The subscription ends when the database is dropped.
+pub struct Subscription {
+    rx: flume::Receiver<ChangeBatch>,
+}
+
+impl Subscription {
+    /// Returns the next batch if one is available, without blocking.
+    #[must_use]
+    pub fn try_recv(&self) -> Option<ChangeBatch> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Waits for the next batch for up to `timeout`.
+    #[must_use]
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ChangeBatch> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+
+    /// Returns the number of batches that are queued, but not yet received.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// Returns `true` if no batches are queued.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rx.is_empty()
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = ChangeBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Iterator over write batches that are still in the journal, see [`Database::changes_since`](crate::Database::changes_since)
+pub struct ChangeIter {
+    meta_keyspace: MetaKeyspace,
+    readers: std::vec::IntoIter<JournalBatchReader>,
+    reader: Option<JournalBatchReader>,
+
+    /// Lowest seqno to return
+    lo: SeqNo,
+
+    /// Batches at or above this seqno were written after the iterator was created
+    hi: SeqNo,
+
+    names: HashMap<InternalKeyspaceId, Option<KeyspaceKey>>,
+}
+
+impl ChangeIter {
+    pub(crate) fn new(
+        meta_keyspace: MetaKeyspace,
+        readers: Vec<JournalBatchReader>,
+        lo: SeqNo,
+        hi: SeqNo,
+    ) -> Self {
+        Self {
+            meta_keyspace,
+            readers: readers.into_iter(),
+            reader: None,
+            lo,
+            hi,
+            names: HashMap::default(),
+        }
+    }
+
+    fn resolve_name(&mut self, id: InternalKeyspaceId) -> crate::Result<Option<KeyspaceKey>> {
+        if let Some(name) = self.names.get(&id) {
+            return Ok(name.clone());
+        }
+
+        let name = self.meta_keyspace.resolve_id(id)?;
+        self.names.insert(id, name.clone());
+        Ok(name)
+    }
+}
+
+impl Iterator for ChangeIter {
+    type Item = crate::Result<ChangeBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let reader = match &mut self.reader {
+                Some(reader) => reader,
+                None => self.reader.insert(self.readers.next()?),
+            };
+
+            let Some(batch) = reader.next() else {
+                self.reader = None;
+                continue;
+            };
+            let batch = fail_iter!(batch);
+
+            if batch.seqno >= self.hi {
+                // NOTE: Journals are written in seqno order, so we are done
+                self.reader = None;
+                self.readers = Vec::new().into_iter();
+                return None;
+            }
+
+            if batch.seqno < self.lo {
+                continue;
+            }
+
+            let mut items = Vec::with_capacity(batch.items.len());
+
+            for item in batch.items {
+                // NOTE: Skip items of deleted keyspaces
+                let Some(keyspace) = fail_iter!(self.resolve_name(item.keyspace_id)) else {
+                    continue;
+                };
+
+                items.push(ChangeItem {
+                    keyspace,
+                    key: item.key,
+                    value: item.value,
+                    change_type: item.value_type.into(),
+                });
+            }
+
+            if items.is_empty() {
+                continue;
+            }
+
+            return Some(Ok(ChangeBatch {
+                seqno: batch.seqno,
+                items,
+            }));
+        }
+    }
+}
+
+/// Keeps track of change subscribers
+#[derive(Default)]
+pub struct ChangeFeed {
+    subscribers: Mutex<Vec<flume::Sender<ChangeBatch>>>,
+
+    /// Number of subscribers, so writers can skip building
+    /// change events if nobody is listening
+    subscriber_count: AtomicUsize,
+}
+
+impl ChangeFeed {
+    pub fn subscribe(&self) -> Subscription {
+        let (tx, rx) = flume::unbounded();
+
+        #[expect(clippy::expect_used)]
+        let mut subscribers = self.subscribers.lock().expect("lock is poisoned");
+        subscribers.push(tx);
+        self.subscriber_count
+            .store(subscribers.len(), Ordering::Release);
+
+        Subscription { rx }
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.subscriber_count.load(Ordering::Acquire) > 0
+    }
+
+    /// Sends the batch to all subscribers, removing subscribers that have gone away.
+    ///
+    /// Needs to be called through the publish queue, so batches are emitted in seqno order.
+    pub fn publish(&self, batch: ChangeBatch) {
+        #[expect(clippy::expect_used)]
+        let mut subscribers = self.subscribers.lock().expect("lock is poisoned");
+
+        if let Some((last, rest)) = subscribers.split_last() {
+            let mut is_disconnected = false;
+
+            for tx in rest {
+                is_disconnected |= tx.send(batch.clone()).is_err();
+            }
+
+            // NOTE: Move the batch into the last subscriber to save a clone
+            is_disconnected |= last.send(batch).is_err();
+
+            if is_disconnected {
+                subscribers.retain(|tx| !tx.is_disconnected());
+
+                log::trace!("Change feed now has {} subscribers", subscribers.len());
+
+                self.subscriber_count
+                    .store(subscribers.len(), Ordering::Release);
+            }
+        }
+    }
+}
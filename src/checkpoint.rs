@@ -0,0 +1,310 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    file::{fsync_directory, LSM_CURRENT_VERSION_MARKER},
+    journal::{batch_reader::JournalBatchReader, reader::JournalReader, writer::PersistMode},
+    Database, EncryptionProvider,
+};
+use lsm_tree::{
+    file::{BLOBS_FOLDER, TABLES_FOLDER},
+    AbstractTree, AnyTree, BlobFile, SeqNo, Table,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Files that make up a consistent copy of the database
+pub struct DatabaseFiles {
+    /// Every batch below this seqno is contained
+    pub seqno: SeqNo,
+
+    pub meta_tree: TreeCheckpoint,
+    pub trees: Vec<TreeCheckpoint>,
+}
+
+impl DatabaseFiles {
+    /// Captures the current version of every tree, and copies the journals into `journal_folder`.
+    ///
+    /// Writes are blocked while the versions are captured,
+    /// journal rotations until the journals are copied.
+    pub fn capture(db: &Database, journal_folder: &Path) -> crate::Result<Self> {
+        use std::sync::atomic::Ordering;
+
+        let mut journal_writer = db.journal.get_writer();
+
+        if db.is_poisoned.load(Ordering::Relaxed) {
+            return Err(crate::Error::Poisoned);
+        }
+
+        // IMPORTANT: Flush buffered writes, so the journal copies contain every committed batch
+        if let Err(e) = journal_writer.persist(PersistMode::Buffer) {
+            db.is_poisoned.store(true, Ordering::Release);
+
+            log::error!(
+                "flush failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+            );
+
+            return Err(crate::Error::Poisoned);
+        }
+
+        // NOTE: While we hold the journal lock, no batch can be written, so the tables
+        // only contain batches below this seqno
+        let seqno = db.supervisor.seqno.get();
+
+        // NOTE: Holding the journal manager prevents journals from being
+        // deleted or rotated until they are copied
+        #[expect(clippy::expect_used)]
+        let journal_manager = db
+            .supervisor
+            .journal_manager
+            .read()
+            .expect("lock is poisoned");
+
+        let meta_tree = TreeCheckpoint::new("0".into(), &db.meta_keyspace.inner)?;
+
+        let trees = {
+            #[expect(clippy::expect_used)]
+            let keyspaces = db.keyspaces.read().expect("lock is poisoned");
+
+            keyspaces
+                .values()
+                .map(|keyspace| TreeCheckpoint::new(keyspace.id().to_string(), &keyspace.tree))
+                .collect::<crate::Result<Vec<_>>>()?
+        };
+
+        let sealed_journal_paths = journal_manager
+            .sealed_journal_paths()
+            .cloned()
+            .collect::<Vec<_>>();
+        let active_journal_path = journal_writer.path.clone();
+
+        drop(journal_writer);
+
+        copy_journals(
+            &sealed_journal_paths,
+            &active_journal_path,
+            journal_folder,
+            seqno,
+            db.config.journal_encryption.clone(),
+        )?;
+
+        drop(journal_manager);
+
+        Ok(Self {
+            seqno,
+            meta_tree,
+            trees,
+        })
+    }
+}
+
+/// Point-in-time copy of the current version of a tree
+///
+/// Holding the tables and blob files prevents them from being deleted by compactions,
+/// until they are linked into the checkpoint.
+pub struct TreeCheckpoint {
+    /// Folder name of the tree inside the keyspaces folder
+    folder_name: String,
+
+    version_id: u64,
+
+    /// Contents of the `current` version pointer
+    current_file: Vec<u8>,
+
+    /// Contents of the version file
+    version_file: Vec<u8>,
+
+    tables: Vec<Table>,
+    blob_files: Vec<BlobFile>,
+}
+
+impl TreeCheckpoint {
+    /// Takes the current version of the tree.
+    pub fn new(folder_name: String, tree: &AnyTree) -> crate::Result<Self> {
+        let folder = &tree.tree_config().path;
+
+        loop {
+            let version = tree.current_version();
+
+            // NOTE: Versions are persisted while holding the version lock,
+            // so the version files cannot change while we read them
+            let version_lock = tree.get_version_history_lock();
+
+            let current_file = std::fs::read(folder.join(LSM_CURRENT_VERSION_MARKER))?;
+
+            let persisted_id = current_file
+                .get(..std::mem::size_of::<u64>())
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u64::from_le_bytes);
+
+            if persisted_id != Some(version.id()) {
+                log::trace!(
+                    "Version of tree at {} changed during checkpoint, retrying",
+                    folder.display(),
+                );
+                continue;
+            }
+
+            let version_file = std::fs::read(folder.join(format!("v{}", version.id())))?;
+
+            drop(version_lock);
+
+            return Ok(Self {
+                folder_name,
+                version_id: version.id(),
+                current_file,
+                version_file,
+                tables: version.iter_tables().cloned().collect(),
+                blob_files: version.blob_files.iter().cloned().collect(),
+            });
+        }
+    }
+
+    /// Writes the tree into the given keyspaces folder.
+    ///
+    /// If `link` is `true`, tables and blob files are hard linked, otherwise they are copied.
+    pub fn write_to(&self, keyspaces_folder: &Path, link: bool) -> crate::Result<()> {
+        let folder = keyspaces_folder.join(&self.folder_name);
+        let tables_folder = folder.join(TABLES_FOLDER);
+        let blobs_folder = folder.join(BLOBS_FOLDER);
+
+        std::fs::create_dir_all(&tables_folder)?;
+
+        for table in &self.tables {
+            let dest = tables_folder.join(table.id().to_string());
+            link_or_copy(&table.path, &dest, link)?;
+        }
+
+        if !self.blob_files.is_empty() {
+            std::fs::create_dir_all(&blobs_folder)?;
+
+            for blob_file in &self.blob_files {
+                let dest = blobs_folder.join(blob_file.id().to_string());
+                link_or_copy(blob_file.path(), &dest, link)?;
+            }
+
+            fsync_directory(&blobs_folder)?;
+        }
+
+        fsync_directory(&tables_folder)?;
+
+        self.write_version(&folder)
+    }
+
+    /// Writes the version file and the version pointer into the tree folder.
+    pub fn write_version(&self, folder: &Path) -> crate::Result<()> {
+        std::fs::create_dir_all(folder)?;
+
+        write_synced(
+            &folder.join(format!("v{}", self.version_id)),
+            &self.version_file,
+        )?;
+
+        // IMPORTANT: Write the version pointer last, the tree is not recovered without it
+        write_synced(&folder.join(LSM_CURRENT_VERSION_MARKER), &self.current_file)?;
+
+        fsync_directory(folder)?;
+
+        Ok(())
+    }
+
+    /// Returns the folder name of the tree inside the keyspaces folder.
+    pub fn folder_name(&self) -> &str {
+        &self.folder_name
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    pub fn blob_files(&self) -> &[BlobFile] {
+        &self.blob_files
+    }
+}
+
+/// Hard links (or copies) a file.
+///
+/// Falls back to copying, if the file is on another file system.
+pub fn link_or_copy(src: &Path, dest: &Path, link: bool) -> crate::Result<()> {
+    if link {
+        match std::fs::hard_link(src, dest) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                log::debug!(
+                    "Cannot hard link {} to another file system, copying instead",
+                    src.display(),
+                );
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    std::fs::copy(src, dest)?;
+    std::fs::File::open(dest)?.sync_all()?;
+
+    Ok(())
+}
+
+/// Writes a new file and fsyncs it.
+pub fn write_synced(path: &Path, bytes: &[u8]) -> crate::Result<()> {
+    use std::io::Write;
+
+    let mut file = std::fs::File::create_new(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Copies sealed journals, and the active journal up to (excluding) `seqno`.
+///
+/// The active journal may still be written to, so everything at or above `seqno` is
+/// truncated from the copy afterwards.
+pub fn copy_journals(
+    sealed_journal_paths: &[PathBuf],
+    active_journal_path: &Path,
+    dest: &Path,
+    seqno: SeqNo,
+    encryption: Option<Arc<dyn EncryptionProvider>>,
+) -> crate::Result<()> {
+    for path in sealed_journal_paths
+        .iter()
+        .map(PathBuf::as_path)
+        .chain(std::iter::once(active_journal_path))
+    {
+        #[expect(clippy::expect_used, reason = "journals always have a file name")]
+        let file_name = path.file_name().expect("should have file name");
+
+        link_or_copy(path, &dest.join(file_name), false)?;
+    }
+
+    #[expect(clippy::expect_used, reason = "journals always have a file name")]
+    let active_journal_path = dest.join(
+        active_journal_path
+            .file_name()
+            .expect("should have file name"),
+    );
+
+    if let Some(limit) = seqno.checked_sub(1) {
+        let reader = JournalBatchReader::new(
+            JournalReader::new(&active_journal_path)?.with_encryption(encryption),
+        )
+        .with_seqno_limit(Some(limit));
+
+        for batch in reader {
+            batch?;
+        }
+    } else {
+        // NOTE: Nothing was written before the checkpoint
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&active_journal_path)?;
+        file.set_len(0)?;
+        file.sync_all()?;
+    }
+
+    Ok(())
+}
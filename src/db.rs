@@ -3,34 +3,48 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::{
+    backup::{self, BackupId},
     batch::WriteBatch,
+    change_feed::{ChangeFeed, ChangeIter, Subscription},
+    checkpoint::{write_synced, DatabaseFiles},
     db_config::Config,
-    file::{fsync_directory, FJALL_MARKER, KEYSPACES_FOLDER, LOCK_FILE},
+    export,
+    file::{fsync_directory, FJALL_MARKER, JOURNAL_PATH_MARKER, KEYSPACES_FOLDER, LOCK_FILE},
     flush::manager::FlushManager,
-    journal::{manager::JournalManager, writer::PersistMode, Journal},
+    journal::{
+        batch_reader::JournalBatchReader, manager::JournalManager, reader::JournalReader,
+        recovery::RECYCLED_JOURNAL_SUFFIX, writer::PersistMode, Journal,
+    },
     keyspace::{name::is_valid_keyspace_name, KeyspaceKey},
     locked_file::LockedFileGuard,
-    meta_keyspace::MetaKeyspace,
+    meta_keyspace::{meta_tree_config, MetaKeyspace},
     poison_dart::PoisonDart,
+    publish_queue::PublishQueue,
+    read_only::{remove_stale_views, ReadOnlyView},
     recovery::{recover_keyspaces, recover_sealed_memtables},
+    repair::{self, RepairReport},
+    restore,
     snapshot::Snapshot,
     snapshot_tracker::SnapshotTracker,
-    stats::Stats,
+    stats::{DatabaseStats, KeyspaceStats, Stats},
     supervisor::{Supervisor, SupervisorInner},
     tx::single_writer::Openable,
+    verify::{self, VerifyOptions, VerifyReport},
     version::FormatVersion,
     worker_pool::{WorkerMessage, WorkerPool},
     write_buffer_manager::WriteBufferManager,
     HashMap, Keyspace, KeyspaceCreateOptions,
 };
-use lsm_tree::{AbstractTree, SequenceNumberCounter};
+use lsm_tree::{AbstractTree, SeqNo, SequenceNumberCounter};
 use std::{
     fs::remove_dir_all,
-    path::Path,
+    io::Write,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, AtomicUsize},
         Arc, Mutex, RwLock,
     },
+    time::Duration,
 };
 
 pub type Keyspaces = HashMap<KeyspaceKey, Keyspace>;
@@ -58,6 +72,9 @@ pub struct DatabaseInner {
     /// Counter of background threads
     pub(crate) active_thread_counter: Arc<AtomicUsize>,
 
+    /// True once the journal syncer thread was spawned
+    pub(crate) is_journal_syncer_spawned: AtomicBool,
+
     /// True if fsync failed
     pub(crate) is_poisoned: Arc<AtomicBool>,
 
@@ -71,6 +88,9 @@ pub struct DatabaseInner {
     pub worker_messager: flume::Sender<WorkerMessage>,
 
     pub(crate) lock_file: LockedFileGuard,
+
+    /// Folder the trees of a read-only database are opened from
+    pub(crate) read_only_view: Option<Arc<ReadOnlyView>>,
 }
 
 impl Drop for DatabaseInner {
@@ -78,6 +98,7 @@ impl Drop for DatabaseInner {
         log::debug!("Dropping database");
 
         self.stop_signal.send();
+        self.journal.stop_background_sync();
 
         let _ = self.worker_pool.rx.drain().count();
 
@@ -102,12 +123,23 @@ impl Drop for DatabaseInner {
             .expect("lock is poisoned")
             .clear();
 
-        if self.config.clean_path_on_drop {
+        // NOTE: A read-only database never deletes the database folder
+        if self.config.clean_path_on_drop && !self.config.read_only {
             log::info!(
                 "Deleting database because temporary=true: {}",
                 self.config.path.display(),
             );
 
+            // NOTE: The journal folder may contain other files, so only our journals are deleted
+            if self.config.journal_path != self.config.path {
+                if let Err(err) = remove_journals(&self.config.journal_path) {
+                    log::warn!(
+                        "Failed to clean up journal path: {} - {err}",
+                        self.config.journal_path.display()
+                    );
+                }
+            }
+
             if let Err(err) = remove_dir_all(&self.config.path) {
                 log::warn!(
                     "Failed to clean up path: {} - {err}",
@@ -203,15 +235,569 @@ impl Database {
         batch
     }
 
-    // TODO: refactor: accessor to stats(), so we don't have that many methods in DB
+    /// Subscribes to all write batches committed after this call.
+    ///
+    /// Each single write, write batch or transaction is emitted as one [`ChangeBatch`](crate::ChangeBatch),
+    /// in ascending seqno order, once it is visible to readers.
+    ///
+    /// A write batch with a durability (see [`WriteBatch::durability`]) is only emitted
+    /// after the journal is persisted accordingly.
+    ///
+    /// The subscription is unbounded, so a subscriber that does not keep up will buffer
+    /// batches in memory. Drop the subscription to unsubscribe.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{ChangeType, Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let db = Database::builder(folder).open()?;
+    /// # let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// let changes = db.subscribe();
+    ///
+    /// tree.insert("a", "abc")?;
+    /// tree.remove("a")?;
+    ///
+    /// let batch = changes.try_recv().expect("should have batch");
+    /// assert_eq!(1, batch.items.len());
+    /// assert_eq!(ChangeType::Insert, batch.items[0].change_type);
+    ///
+    /// let batch = changes.try_recv().expect("should have batch");
+    /// assert_eq!(ChangeType::Remove, batch.items[0].change_type);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn subscribe(&self) -> Subscription {
+        self.supervisor.change_feed.subscribe()
+    }
+
+    /// Returns all write batches starting at `seqno` that are still stored in the journals.
+    ///
+    /// This can be used to resume consuming changes (see [`Database::subscribe`]) after a restart,
+    /// by passing the seqno following the last consumed batch.
+    ///
+    /// The iterator only returns batches that were committed before this call.
+    /// Journals are deleted once their data is flushed to disk, so changes are only
+    /// available for a limited time. Writes to keyspaces that have been deleted are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let db = Database::builder(folder).open()?;
+    /// # let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// tree.insert("a", "abc")?;
+    /// tree.insert("b", "def")?;
+    ///
+    /// let changes = db.changes_since(0)?.collect::<fjall::Result<Vec<_>>>()?;
+    /// assert_eq!(2, changes.len());
+    ///
+    /// let changes = db.changes_since(changes[0].seqno + 1)?.collect::<fjall::Result<Vec<_>>>()?;
+    /// assert_eq!(1, changes.len());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChangesEvicted`](crate::Error::ChangesEvicted) if journals
+    /// that may have contained batches at or above `seqno` were already deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn changes_since(&self, seqno: SeqNo) -> crate::Result<ChangeIter> {
+        use std::sync::atomic::Ordering;
+
+        let mut journal_writer = self.journal.get_writer();
+
+        if self.is_poisoned.load(Ordering::Relaxed) {
+            return Err(crate::Error::Poisoned);
+        }
+
+        // IMPORTANT: Flush buffered writes, so the readers see every committed batch
+        if let Err(e) = journal_writer.persist(PersistMode::Buffer) {
+            self.is_poisoned.store(true, Ordering::Release);
+
+            log::error!(
+                "flush failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+            );
+
+            return Err(crate::Error::Poisoned);
+        }
+
+        // NOTE: Every batch below the current seqno has been written at this point
+        let hi = self.supervisor.seqno.get();
+
+        #[expect(clippy::expect_used)]
+        let journal_manager = self
+            .supervisor
+            .journal_manager
+            .read()
+            .expect("lock is poisoned");
+
+        let evicted_seqno = journal_manager.evicted_seqno();
+
+        if seqno < evicted_seqno {
+            return Err(crate::Error::ChangesEvicted(evicted_seqno));
+        }
+
+        // NOTE: Open the journal files while holding the locks, so they
+        // cannot be deleted before we get a handle to them
+        let readers = journal_manager
+            .sealed_journal_paths()
+            .chain(std::iter::once(&journal_writer.path))
+            .map(|path| {
+                JournalReader::new_read_only(path).map(|reader| {
+                    JournalBatchReader::new(
+                        reader.with_encryption(self.config.journal_encryption.clone()),
+                    )
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        drop(journal_manager);
+        drop(journal_writer);
+
+        Ok(ChangeIter::new(
+            self.meta_keyspace.clone(),
+            readers,
+            seqno,
+            hi,
+        ))
+    }
+
+    /// Creates a consistent copy of the database at `path`, which can be opened like any other database.
+    ///
+    /// Writes can continue while the checkpoint is created.
+    /// Tables and blob files are immutable, so they are hard linked into the checkpoint,
+    /// falling back to copying if `path` is on another file system.
+    /// Journals are copied, so the checkpoint contains all batches below the returned seqno,
+    /// which are replayed when opening the checkpoint.
+    ///
+    /// Data of keyspaces without journaling that was not flushed yet is not contained in the checkpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let db = Database::builder(folder.path().join("db")).open()?;
+    /// # let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// tree.insert("a", "abc")?;
+    ///
+    /// db.checkpoint(folder.path().join("checkpoint"))?;
+    ///
+    /// let checkpoint = Database::builder(folder.path().join("checkpoint")).open()?;
+    /// let tree = checkpoint.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// assert!(tree.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if `path` already exists, or an IO error occurred.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn checkpoint(&self, path: impl AsRef<Path>) -> crate::Result<SeqNo> {
+        if self.config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::create_dir(path)?;
+
+        log::info!("Creating checkpoint at {}", path.display());
+
+        let DatabaseFiles {
+            seqno,
+            meta_tree,
+            trees,
+        } = DatabaseFiles::capture(self, path)?;
+
+        let keyspaces_folder = path.join(KEYSPACES_FOLDER);
+
+        // NOTE: The meta keyspace is small, so it is copied
+        meta_tree.write_to(&keyspaces_folder, false)?;
+
+        for tree in &trees {
+            tree.write_to(&keyspaces_folder, true)?;
+        }
+
+        std::fs::File::create_new(path.join(LOCK_FILE))?.sync_all()?;
+
+        // NOTE: Lastly, write the version marker, so the checkpoint is only recovered once complete
+        write_synced(
+            &path.join(FJALL_MARKER),
+            &std::fs::read(self.config.path.join(FJALL_MARKER))?,
+        )?;
+
+        // IMPORTANT: fsync folders on Unix
+        fsync_directory(&keyspaces_folder)?;
+        fsync_directory(path)?;
+
+        log::info!(
+            "Created checkpoint at {} with seqno={seqno}",
+            path.display(),
+        );
+
+        Ok(seqno)
+    }
+
+    /// Creates a full backup in the backup folder at `path`, and returns its ID.
+    ///
+    /// Like [`Database::checkpoint`], the backup is consistent and writes can continue,
+    /// but every table and blob file is copied, so the backup can be stored on another disk.
+    ///
+    /// A backup folder can contain many backups, which share their tables and blob files,
+    /// see [`Database::backup_incremental`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occurred.
+    pub fn backup(&self, path: impl AsRef<Path>) -> crate::Result<BackupId> {
+        backup::backup(self, path.as_ref(), None)
+    }
+
+    /// Creates an incremental backup in the backup folder at `path`, and returns its ID.
+    ///
+    /// Only tables and blob files that are not part of the backup `since` are copied,
+    /// in addition to the journals and the keyspace metadata.
+    ///
+    /// Every backup can be restored on its own using [`Database::restore_backup`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let db = Database::builder(folder.path().join("db")).open()?;
+    /// # let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// let backups = folder.path().join("backups");
+    ///
+    /// tree.insert("a", "abc")?;
+    /// let full = db.backup(&backups)?;
+    ///
+    /// tree.insert("b", "abc")?;
+    /// let incremental = db.backup_incremental(&backups, full)?;
+    ///
+    /// Database::restore_backup(&backups, incremental, folder.path().join("restored"))?;
+    ///
+    /// let restored = Database::builder(folder.path().join("restored")).open()?;
+    /// let tree = restored.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// assert_eq!(2, tree.len()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BackupNotFound`](crate::Error::BackupNotFound) if the backup `since` does not exist.
+    ///
+    /// Returns error, if an IO error occurred.
+    pub fn backup_incremental(
+        &self,
+        path: impl AsRef<Path>,
+        since: BackupId,
+    ) -> crate::Result<BackupId> {
+        backup::backup(self, path.as_ref(), Some(since))
+    }
+
+    /// Lists the IDs of all complete backups in the backup folder at `path`, in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occurred.
+    pub fn list_backups(path: impl AsRef<Path>) -> crate::Result<Vec<BackupId>> {
+        backup::list_backups(path.as_ref())
+    }
+
+    /// Restores a backup from the backup folder at `path` into a new database folder at `target`.
+    ///
+    /// Tables and blob files are hard linked from the backup folder, if possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BackupNotFound`](crate::Error::BackupNotFound) if the backup does not exist.
+    ///
+    /// Returns error, if `target` already exists, or an IO error occurred.
+    pub fn restore_backup(
+        path: impl AsRef<Path>,
+        backup_id: BackupId,
+        target: impl AsRef<Path>,
+    ) -> crate::Result<()> {
+        backup::restore(path.as_ref(), backup_id, target.as_ref())
+    }
+
+    /// Restores the checkpoint at `checkpoint` into the database folder at `target`.
+    ///
+    /// Before `target` is touched, the checkpoint is copied into a temporary folder next to it,
+    /// and verified: the version marker needs to be valid, and every table and blob file
+    /// referenced by the current version of a keyspace needs to exist with the correct checksum.
+    /// Tables and blob files are hard linked from the checkpoint, if possible.
+    ///
+    /// If `target` is not empty, the restore is refused, unless `force` is set, in which case
+    /// the database at `target` is replaced. Only a folder containing a database is replaced.
+    /// If that database uses an external journal folder (see
+    /// [`DatabaseBuilder::journal_path`](crate::DatabaseBuilder::journal_path)), the restored
+    /// database keeps using it, and its old journals are replaced by the checkpoint's journals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let db = Database::builder(folder.path().join("db")).open()?;
+    /// let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    ///
+    /// tree.insert("a", "abc")?;
+    /// db.checkpoint(folder.path().join("checkpoint"))?;
+    ///
+    /// tree.insert("b", "abc")?;
+    /// drop(tree);
+    /// drop(db);
+    ///
+    /// Database::restore_from(folder.path().join("checkpoint"), folder.path().join("db"), true)?;
+    ///
+    /// let db = Database::builder(folder.path().join("db")).open()?;
+    /// let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// assert_eq!(1, tree.len()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Locked`](crate::Error::Locked), if the checkpoint or the database
+    /// at `target` is in use.
+    ///
+    /// Returns error, if the checkpoint is invalid, `target` is not empty
+    /// (and `force` is not set), or an IO error occurred.
+    pub fn restore_from(
+        checkpoint: impl AsRef<Path>,
+        target: impl AsRef<Path>,
+        force: bool,
+    ) -> crate::Result<()> {
+        restore::restore_from(checkpoint.as_ref(), target.as_ref(), force)
+    }
+
+    /// Repairs the database at `path`, which must not be in use.
+    ///
+    /// When opening a database, keyspace folders that are not referenced by any keyspace name,
+    /// or that were never fully initialized, are deleted (see
+    /// [`DatabaseBuilder::delete_unreferenced_keyspaces`](crate::DatabaseBuilder::delete_unreferenced_keyspaces)).
+    /// Instead, this moves such folders into the `.quarantine` folder inside the database folder,
+    /// so they can be inspected.
+    ///
+    /// If the meta keyspace, which keeps the keyspace names and their configuration, cannot be opened,
+    /// it is quarantined and recreated. Every intact keyspace is then registered under the name
+    /// `recovered_<id>`, with the default configuration.
+    ///
+    /// Missing configuration entries of a keyspace are reset to their defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # {
+    /// #     let db = Database::builder(&folder).open()?;
+    /// #     db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// # }
+    /// let report = Database::repair(&folder)?;
+    /// assert!(report.quarantined.is_empty());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Locked`](crate::Error::Locked), if the database is in use.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn repair(path: impl AsRef<Path>) -> crate::Result<RepairReport> {
+        repair::repair(path.as_ref())
+    }
+
+    /// Creates a keyspace from an export written by [`Keyspace::export`].
+    ///
+    /// The keyspace is created with the exported name and options,
+    /// and the exported items are bulk loaded into it.
+    ///
+    /// If the export is invalid or incomplete, the keyspace is deleted again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let db = Database::builder(folder.path().join("a")).open()?;
+    /// let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// tree.insert("a", "abc")?;
+    ///
+    /// let mut export = vec![];
+    /// tree.export(&db.snapshot(), &mut export)?;
+    ///
+    /// let other_db = Database::builder(folder.path().join("b")).open()?;
+    /// let tree = other_db.import(&*export)?;
+    /// assert_eq!("default", &**tree.name());
+    /// assert!(tree.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, the export is invalid,
+    /// or a keyspace with the same name already exists.
+    pub fn import<R: std::io::Read>(&self, reader: R) -> crate::Result<Keyspace> {
+        export::import(self, reader)
+    }
+
+    /// Verifies the integrity of the database, so corruption is found before a read hits it.
+    ///
+    /// The following is checked:
+    ///
+    /// - the checksum of every table and blob file of every keyspace
+    /// - every block of every table can be decoded, and its items are sorted
+    /// - every key of a table can be found through the table's index and filter
+    /// - every keyspace folder is referenced by a keyspace name
+    /// - every batch of every journal is valid
+    ///
+    /// Reads and writes can continue while the database is verified.
+    /// Found errors are collected in the returned report, per file.
+    ///
+    /// A keyspace that was deleted, but of which a handle is still held, is reported
+    /// as unreferenced, because its folder is only deleted once it is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions, VerifyOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let db = Database::builder(folder).open()?;
+    /// # let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// tree.insert("a", "abc")?;
+    /// tree.rotate_memtable_and_wait()?;
+    ///
+    /// let report = db.verify(&VerifyOptions::default())?;
+    /// assert!(report.is_ok());
+    /// assert!(report.table_count >= 1);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, that is not specific to a single file.
+    pub fn verify(&self, options: &VerifyOptions) -> crate::Result<VerifyReport> {
+        verify::verify(self, options)
+    }
+
+    /// Runs [`Database::verify`] in a background thread.
+    ///
+    /// Use [`VerifyOptions::max_bytes_per_second`] to limit the impact on other reads and writes.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the thread could not be spawned.
+    pub fn verify_in_background(
+        &self,
+        options: VerifyOptions,
+    ) -> crate::Result<std::thread::JoinHandle<crate::Result<VerifyReport>>> {
+        let db = self.clone();
+
+        std::thread::Builder::new()
+            .name("fjall:verify".to_string())
+            .spawn(move || verify::verify(&db, &options))
+            .map_err(Into::into)
+    }
+
+    /// Returns statistics of the database and all of its keyspaces, gathered in one call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Database, KeyspaceCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let db = Database::builder(folder).open()?;
+    /// let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    /// tree.insert("a", "abc")?;
+    ///
+    /// let stats = db.stats()?;
+    /// assert_eq!(1, stats.journal_count);
+    /// assert_eq!(1, stats.keyspaces["default"].approximate_len);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    #[expect(clippy::missing_panics_doc)]
+    pub fn stats(&self) -> crate::Result<DatabaseStats> {
+        use std::sync::atomic::Ordering::Relaxed;
+
+        #[expect(clippy::expect_used)]
+        let keyspaces = self
+            .keyspaces
+            .read()
+            .expect("lock is poisoned")
+            .iter()
+            .map(|(name, keyspace)| {
+                let tree = &keyspace.tree;
+
+                let stats = KeyspaceStats {
+                    l0_run_count: tree.l0_run_count(),
+                    l0_table_count: tree.level_table_count(0).unwrap_or_default(),
+                    table_count: tree.table_count(),
+                    blob_file_count: tree.blob_file_count(),
+                    active_memtable_size: tree.active_memtable().size(),
+                    sealed_memtable_count: tree.sealed_memtable_count(),
+                    approximate_len: tree.approximate_len(),
+                    disk_space: tree.disk_space(),
+                };
+
+                (name.to_string(), stats)
+            })
+            .collect();
+
+        Ok(DatabaseStats {
+            write_buffer_size: self.supervisor.write_buffer_size.get(),
+            outstanding_flushes: self.supervisor.flush_manager.len(),
+            active_compactions: self.stats.active_compaction_count.load(Relaxed),
+            compactions_completed: self.stats.compactions_completed.load(Relaxed),
+            time_compacting: Duration::from_micros(self.stats.time_compacting.load(Relaxed)),
+            journal_count: self.journal_count(),
+            journal_disk_space: self.journal_disk_space()?,
+            open_snapshots: self.supervisor.snapshot_tracker.open_snapshots(),
+            is_poisoned: self.is_poisoned.load(Relaxed),
+            keyspaces,
+        })
+    }
 
     /// Returns the current write buffer size (active + sealed memtables).
     ///
     /// # Experimental
     ///
     /// This is a non-stable API currently.
-    #[must_use]
     #[doc(hidden)]
+    #[must_use]
+    #[deprecated(note = "use `Database::stats` instead")]
     pub fn write_buffer_size(&self) -> u64 {
         self.supervisor.write_buffer_size.get()
     }
@@ -223,6 +809,7 @@ impl Database {
     /// This is a non-stable API currently.
     #[doc(hidden)]
     #[must_use]
+    #[deprecated(note = "use `Database::stats` instead")]
     pub fn outstanding_flushes(&self) -> usize {
         self.supervisor.flush_manager.len()
     }
@@ -234,6 +821,7 @@ impl Database {
     /// This is a non-stable API currently.
     #[doc(hidden)]
     #[must_use]
+    #[deprecated(note = "use `Database::stats` instead")]
     pub fn time_compacting(&self) -> std::time::Duration {
         let us = self
             .stats
@@ -250,6 +838,7 @@ impl Database {
     /// This is a non-stable API currently.
     #[doc(hidden)]
     #[must_use]
+    #[deprecated(note = "use `Database::stats` instead")]
     pub fn active_compactions(&self) -> usize {
         self.stats
             .active_compaction_count
@@ -263,6 +852,7 @@ impl Database {
     /// This is a non-stable API currently.
     #[doc(hidden)]
     #[must_use]
+    #[deprecated(note = "use `Database::stats` instead")]
     pub fn compactions_completed(&self) -> usize {
         self.stats
             .compactions_completed
@@ -356,6 +946,10 @@ impl Database {
     ///
     /// Returns error, if an IO error occurred.
     pub fn persist(&self, mode: PersistMode) -> crate::Result<()> {
+        if self.config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         if self.is_poisoned.load(std::sync::atomic::Ordering::Relaxed) {
             return Err(crate::Error::Poisoned);
         }
@@ -392,7 +986,7 @@ impl Database {
         );
 
         let db = Self::create_or_recover(config)?;
-        // db.start_background_threads()?;
+        db.start_background_threads()?;
 
         #[cfg(feature = "__internal_whitebox")]
         crate::drop::increment_drop_counter();
@@ -400,6 +994,112 @@ impl Database {
         Ok(db)
     }
 
+    /// Starts background threads that are not part of the worker pool.
+    ///
+    /// The flush and compaction workers are already started when the database is created or recovered,
+    /// so currently this only starts the journal syncer, if background syncing is enabled.
+    pub(crate) fn start_background_threads(&self) -> crate::Result<()> {
+        if self.config.read_only {
+            return Ok(());
+        }
+
+        self.journal
+            .set_sync_interval(self.config.journal_sync_interval);
+        self.journal
+            .set_bytes_per_sync(self.config.journal_bytes_per_sync);
+
+        if self.config.journal_sync_interval.is_some()
+            || self.config.journal_bytes_per_sync.is_some()
+        {
+            self.spawn_journal_syncer()?;
+        }
+
+        Ok(())
+    }
+
+    /// Spawns the thread that syncs the journal in the background,
+    /// see [`DatabaseBuilder::journal_sync_interval`](crate::DatabaseBuilder::journal_sync_interval).
+    ///
+    /// Does nothing if the thread was already spawned, or if the database is read-only.
+    fn spawn_journal_syncer(&self) -> crate::Result<()> {
+        use std::sync::atomic::Ordering::{AcqRel, Relaxed, Release};
+
+        if self.config.read_only || self.is_journal_syncer_spawned.swap(true, AcqRel) {
+            return Ok(());
+        }
+
+        let journal = self.journal.clone();
+        let poison_dart = PoisonDart::new(self.is_poisoned.clone());
+        let thread_counter = self.active_thread_counter.clone();
+
+        thread_counter.fetch_add(1, Relaxed);
+
+        std::thread::Builder::new()
+            .name("fjall:journal-sync".to_string())
+            .spawn({
+                let thread_counter = thread_counter.clone();
+
+                move || {
+                    while journal.wait_for_background_sync() {
+                        log::trace!("Syncing journal in background");
+
+                        if let Err(e) = journal.persist(PersistMode::SyncData) {
+                            log::error!("Background journal sync failed, which is a FATAL, and possibly hardware-related, failure: {e:?}");
+                            poison_dart.poison();
+                            break;
+                        }
+                    }
+
+                    log::debug!("Journal syncer closes because DB is dropping");
+                    thread_counter.fetch_sub(1, Relaxed);
+                }
+            })
+            .inspect_err(|_| {
+                thread_counter.fetch_sub(1, Relaxed);
+                self.is_journal_syncer_spawned.store(false, Release);
+            })?;
+
+        Ok(())
+    }
+
+    /// Sets the interval in which the journal is synced in the background,
+    /// `None` disables interval-based syncing.
+    ///
+    /// See [`DatabaseBuilder::journal_sync_interval`](crate::DatabaseBuilder::journal_sync_interval).
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if the background syncer could not be started.
+    pub fn set_journal_sync_interval(&self, interval: Option<Duration>) -> crate::Result<()> {
+        self.journal.set_sync_interval(interval);
+
+        if interval.is_some() {
+            self.spawn_journal_syncer()?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the amount of written bytes after which the journal is synced in the background,
+    /// `None` (or 0) disables byte-based syncing.
+    ///
+    /// See [`DatabaseBuilder::journal_bytes_per_sync`](crate::DatabaseBuilder::journal_bytes_per_sync).
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if the background syncer could not be started.
+    pub fn set_journal_bytes_per_sync(&self, bytes: Option<u64>) -> crate::Result<()> {
+        let bytes = bytes.filter(|&bytes| bytes > 0);
+
+        self.journal.set_bytes_per_sync(bytes);
+
+        if bytes.is_some() {
+            self.spawn_journal_syncer()?;
+        }
+
+        Ok(())
+    }
+
     /// Same as [`Database::open`], but does not start background threads.
     ///
     /// Needed to open a database without threads for testing.
@@ -407,7 +1107,9 @@ impl Database {
     /// Should not be user-facing.
     #[doc(hidden)]
     pub fn create_or_recover(config: Config) -> crate::Result<Self> {
-        if config.path.join(FJALL_MARKER).try_exists()? {
+        if config.read_only {
+            Self::recover_read_only(&config)
+        } else if config.path.join(FJALL_MARKER).try_exists()? {
             Self::recover(config)
         } else {
             Self::create_new(config)
@@ -425,6 +1127,10 @@ impl Database {
     #[doc(hidden)]
     #[expect(clippy::needless_pass_by_value)]
     pub fn delete_keyspace(&self, handle: Keyspace) -> crate::Result<()> {
+        if self.config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         self.meta_keyspace.remove_keyspace(&handle.name)?;
 
         handle
@@ -460,6 +1166,10 @@ impl Database {
         Ok(if let Some(keyspace) = keyspaces.get(name) {
             keyspace.clone()
         } else {
+            if self.config.read_only {
+                return Err(crate::Error::ReadOnly);
+            }
+
             let name: KeyspaceKey = name.into();
 
             let keyspace_id = self.keyspace_id_counter.next();
@@ -527,26 +1237,93 @@ impl Database {
         self.supervisor.snapshot_tracker.get()
     }
 
-    fn check_version<P: AsRef<Path>>(path: P) -> crate::Result<()> {
+    pub(crate) fn check_version<P: AsRef<Path>>(path: P) -> crate::Result<FormatVersion> {
         let bytes = std::fs::read(path.as_ref().join(FJALL_MARKER))?;
 
         if let Some(version) = FormatVersion::parse_file_header(&bytes) {
             if version == FormatVersion::V2 {
                 log::error!("It looks like you are trying to open a V2 database - the database needs a manual migration, a tool is available at https://github.com/fjall-rs/migrate-v2-v3.");
             }
-            if version as u8 > 3 {
-                log::error!("It looks like you are trying to open a database from the future. Are you a time traveller?");
-            }
-            if version != FormatVersion::V3 {
+            if !matches!(version, FormatVersion::V3 | FormatVersion::V4) {
                 return Err(crate::Error::InvalidVersion(Some(version)));
             }
+            Ok(version)
         } else {
-            return Err(crate::Error::InvalidVersion(None));
+            if bytes.starts_with(b"FJL") && bytes.get(3).is_some_and(|&version| version > 4) {
+                log::error!("It looks like you are trying to open a database from the future. Are you a time traveller?");
+            }
+            Err(crate::Error::InvalidVersion(None))
+        }
+    }
+
+    /// Upgrades the version marker of a V3 database to V4.
+    ///
+    /// V4 journals may contain compact and recycled entries, which V3 releases can not read,
+    /// so this needs to happen before anything is written to the journal.
+    fn upgrade_version(path: &Path) -> crate::Result<()> {
+        log::info!(
+            "Upgrading database at {} from format version {} to {}",
+            path.display(),
+            FormatVersion::V3,
+            FormatVersion::V4,
+        );
+
+        // NOTE: Write to a temporary file first, so the marker is replaced atomically
+        let mut marker = tempfile::NamedTempFile::new_in(path)?;
+        FormatVersion::V4.write_file_header(&mut marker)?;
+        marker.as_file().sync_all()?;
+        marker
+            .persist(path.join(FJALL_MARKER))
+            .map_err(|e| crate::Error::Io(e.error))?;
+
+        fsync_directory(path)?;
+
+        Ok(())
+    }
+
+    /// Checks that the journal folder is the one the database was created with.
+    fn check_journal_path(config: &Config) -> crate::Result<()> {
+        let marker_path = config.path.join(JOURNAL_PATH_MARKER);
+
+        let recorded_path = if marker_path.try_exists()? {
+            PathBuf::from(String::from_utf8_lossy(&std::fs::read(marker_path)?).as_ref())
+        } else {
+            config.path.clone()
+        };
+
+        if recorded_path.to_string_lossy() != config.journal_path.to_string_lossy() {
+            log::error!(
+                "Database was created with journal path {}, but was opened with journal path {}",
+                recorded_path.display(),
+                config.journal_path.display(),
+            );
+            return Err(crate::Error::JournalPathMismatch(recorded_path));
         }
 
         Ok(())
     }
 
+    /// Recovers existing database from directory, without modifying it.
+    fn recover_read_only(config: &Config) -> crate::Result<Self> {
+        const RETRIES: usize = 3;
+
+        for i in 1..=RETRIES {
+            match Self::recover(config.clone()) {
+                // NOTE: A concurrent writer may delete a keyspace while it is linked
+                Err(crate::Error::Io(e))
+                    if e.kind() == std::io::ErrorKind::NotFound && i < RETRIES =>
+                {
+                    log::debug!(
+                        "File was deleted while opening read-only database, retrying: {e:?}"
+                    );
+                }
+                result => return result,
+            }
+        }
+
+        unreachable!()
+    }
+
     /// Recovers existing database from directory.
     #[expect(clippy::too_many_lines)]
     #[doc(hidden)]
@@ -554,27 +1331,74 @@ impl Database {
         log::info!("Recovering database at {}", config.path.display());
 
         // Check version
-        Self::check_version(&config.path)?;
+        let version = Self::check_version(&config.path)?;
 
-        let lock_file = LockedFileGuard::try_acquire(&config.path.join(LOCK_FILE))?;
+        let lock_file = if config.read_only {
+            LockedFileGuard::unlocked()
+        } else {
+            LockedFileGuard::try_acquire(&config.path.join(LOCK_FILE))?
+        };
 
-        // TODO:
-        // let recovery_mode = config.journal_recovery_mode;
+        Self::check_journal_path(&config)?;
+
+        if !config.read_only {
+            // IMPORTANT: Upgrade before anything is written to the journal
+            if version == FormatVersion::V3 {
+                Self::upgrade_version(&config.path)?;
+            }
+
+            remove_stale_views(&config.path)?;
+        }
+
+        let read_only_view = if config.read_only {
+            Some(Arc::new(ReadOnlyView::create(
+                &config.path,
+                &config.journal_path,
+            )?))
+        } else {
+            None
+        };
 
         // Reload active journal
-        let journal_recovery = Journal::recover(
-            &config.path,
-            config.journal_compression_type,
-            config.journal_compression_threshold,
-        )?;
+        let journal_recovery = if let Some(view) = &read_only_view {
+            Journal::recover_read_only(view.journals_folder())?
+        } else {
+            Journal::recover(
+                &config.journal_path,
+                config.journal_compression_type,
+                config.journal_compression_threshold,
+                config.journal_preallocation_size,
+            )?
+        };
         log::debug!("journal recovery result: {journal_recovery:#?}");
 
-        let active_journal = Arc::new(journal_recovery.active);
-        active_journal.get_writer().persist(PersistMode::SyncAll)?;
+        let active_journal = Arc::new(
+            journal_recovery
+                .active
+                .with_encryption(config.journal_encryption.clone()),
+        );
+
+        if !config.read_only {
+            active_journal.get_writer().persist(PersistMode::SyncAll)?;
+        }
+
+        let keyspaces_folder = read_only_view.as_ref().map_or_else(
+            || config.path.join(KEYSPACES_FOLDER),
+            |view| view.keyspaces_folder(),
+        );
 
         let sealed_journals = journal_recovery.sealed;
 
-        let journal_manager = JournalManager::new();
+        let (oldest_journal_id, oldest_journal_path) = sealed_journals
+            .first()
+            .cloned()
+            .unwrap_or_else(|| (journal_recovery.active_id, active_journal.path()));
+
+        let journal_manager = JournalManager::new(
+            config.journal_archiver.clone(),
+            config.max_recycled_journals,
+            config.path.clone(),
+        );
 
         let seqno = SequenceNumberCounter::default();
         let visible_seqno = SequenceNumberCounter::default();
@@ -584,35 +1408,16 @@ impl Database {
             xxhash_rust::xxh3::Xxh3Builder::new(),
         )));
 
-        let meta_tree = lsm_tree::Config::new(
-            config.path.join(KEYSPACES_FOLDER).join("0"),
+        let meta_tree = meta_tree_config(
+            keyspaces_folder.join("0"),
             seqno.clone(),
             visible_seqno.clone(),
         )
         .use_cache(config.cache.clone())
         .use_descriptor_table(config.descriptor_table.clone())
-        .expect_point_read_hits(true)
-        .data_block_size_policy(crate::config::BlockSizePolicy::all(4_096))
-        .data_block_hash_ratio_policy(crate::config::HashRatioPolicy::all(8.0))
-        .data_block_compression_policy(crate::config::CompressionPolicy::disabled())
-        .data_block_restart_interval_policy(crate::config::RestartIntervalPolicy::all(1))
-        .index_block_compression_policy(crate::config::CompressionPolicy::disabled())
-        .filter_policy(crate::config::FilterPolicy::new([
-            lsm_tree::config::FilterPolicyEntry::Bloom(
-                lsm_tree::config::BloomConstructionPolicy::FalsePositiveRate(0.0001),
-            ),
-            lsm_tree::config::FilterPolicyEntry::Bloom(
-                lsm_tree::config::BloomConstructionPolicy::FalsePositiveRate(0.01),
-            ),
-        ]))
         .open()?;
 
-        let meta_keyspace = MetaKeyspace::new(
-            meta_tree,
-            keyspaces.clone(),
-            seqno.clone(),
-            visible_seqno.clone(),
-        );
+        let meta_keyspace = MetaKeyspace::new(meta_tree, keyspaces.clone());
 
         let supervisor = Supervisor::new(SupervisorInner {
             flush_manager: FlushManager::new(),
@@ -620,6 +1425,8 @@ impl Database {
             snapshot_tracker: SnapshotTracker::new(visible_seqno),
             journal_manager: Arc::new(RwLock::new(journal_manager)),
             backpressure_lock: Mutex::default(),
+            change_feed: ChangeFeed::default(),
+            publish_queue: PublishQueue::default(),
             seqno,
         });
 
@@ -628,8 +1435,15 @@ impl Database {
 
         let is_poisoned = Arc::<AtomicBool>::default();
 
+        // NOTE: A read-only database never flushes or compacts
+        let worker_threads = if config.read_only {
+            0
+        } else {
+            config.worker_threads
+        };
+
         let (worker_pool, worker_messager) = WorkerPool::new(
-            config.worker_threads,
+            worker_threads,
             &supervisor,
             &stats,
             &active_thread_counter,
@@ -648,15 +1462,35 @@ impl Database {
             keyspaces,
             stop_signal: lsm_tree::stop_signal::StopSignal::default(),
             active_thread_counter,
+            is_journal_syncer_spawned: AtomicBool::default(),
             is_poisoned,
             stats,
             lock_file,
+            read_only_view,
         };
 
         let db = Self(Arc::new(inner));
 
         // Recover keyspaces
-        recover_keyspaces(&db, &meta_keyspace)?;
+        recover_keyspaces(&db, &meta_keyspace, &keyspaces_folder)?;
+
+        // NOTE: Flushed data cannot be rolled back, so check before discarding any journaled batches
+        if let Some(target_seqno) = db.config.recover_until_seqno {
+            #[expect(clippy::expect_used)]
+            let keyspaces = db.keyspaces.read().expect("lock is poisoned");
+
+            for keyspace in keyspaces.values() {
+                if let Some(persisted_seqno) = keyspace.tree.get_highest_persisted_seqno() {
+                    if persisted_seqno > target_seqno {
+                        log::error!(
+                            "Keyspace {:?} has persisted seqno={persisted_seqno}, cannot recover to seqno={target_seqno}",
+                            keyspace.name,
+                        );
+                        return Err(crate::Error::RecoveryTargetUnreachable(persisted_seqno));
+                    }
+                }
+            }
+        }
 
         // Recover sealed memtables by walking through old journals
         recover_sealed_memtables(
@@ -685,9 +1519,18 @@ impl Database {
             if !journal_recovery.was_active_created {
                 log::trace!("Recovering active memtables from active journal");
 
-                let reader = db.journal.get_reader()?;
+                let mut reader = if db.config.read_only {
+                    JournalBatchReader::new(
+                        JournalReader::new_read_only(db.journal.path())?
+                            .with_encryption(db.config.journal_encryption.clone()),
+                    )
+                } else {
+                    db.journal.get_reader()?
+                }
+                .with_recovery_mode(db.config.journal_recovery_mode)
+                .with_seqno_limit(db.config.recover_until_seqno);
 
-                for batch in reader {
+                for batch in reader.by_ref() {
                     let batch = batch?;
 
                     for item in batch.items {
@@ -719,6 +1562,11 @@ impl Database {
                     }
                 }
 
+                // NOTE: A secondary database continues reading the active journal from here
+                if let Some(view) = &db.read_only_view {
+                    view.set_journal_pos(reader.last_valid_pos());
+                }
+
                 for keyspace in keyspaces.values() {
                     let size = keyspace.tree.active_memtable().size();
 
@@ -744,6 +1592,25 @@ impl Database {
             }
         }
 
+        // NOTE: If older journals were deleted in a previous run, everything below the first
+        // retained batch (which is the start marker of the journal) is treated as evicted
+        if oldest_journal_id > 0 {
+            let first_seqno = JournalBatchReader::new(
+                JournalReader::new_read_only(&oldest_journal_path)?
+                    .with_encryption(db.config.journal_encryption.clone()),
+            )
+            .next()
+            .transpose()?
+            .map(|batch| batch.seqno);
+
+            #[expect(clippy::expect_used)]
+            db.supervisor
+                .journal_manager
+                .write()
+                .expect("lock is poisoned")
+                .set_evicted_seqno(first_seqno.unwrap_or_else(|| db.supervisor.seqno.get()));
+        }
+
         db.supervisor
             .snapshot_tracker
             .set(db.supervisor.seqno.get());
@@ -751,7 +1618,7 @@ impl Database {
         db.supervisor.snapshot_tracker.gc();
 
         for keyspace in db.keyspaces.read().expect("lock is poisoned").values() {
-            if keyspace.tree.sealed_memtable_count() > 0 {
+            if keyspace.tree.sealed_memtable_count() > 0 && !db.config.read_only {
                 keyspace.worker_messager.send(WorkerMessage::Flush).ok();
             }
         }
@@ -769,21 +1636,43 @@ impl Database {
 
         let lock_file = LockedFileGuard::create_new(&config.path.join(LOCK_FILE))?;
 
-        let journal_folder_path = &config.path;
+        let journal_folder_path = &config.journal_path;
         let keyspaces_folder_path = config.path.join(KEYSPACES_FOLDER);
 
+        // NOTE: Journals of another database would be recovered into this one when reopening
+        if config.journal_path != config.path && contains_journals(journal_folder_path)? {
+            log::error!(
+                "Journal folder {} already contains journals",
+                journal_folder_path.display(),
+            );
+
+            return Err(crate::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "journal folder already contains journals",
+            )));
+        }
+
         std::fs::create_dir_all(&keyspaces_folder_path)?;
 
+        // NOTE: Record the journal folder, so we can detect a mismatch when reopening
+        if config.journal_path != config.path {
+            let mut marker = std::fs::File::create_new(config.path.join(JOURNAL_PATH_MARKER))?;
+            marker.write_all(config.journal_path.to_string_lossy().as_bytes())?;
+            marker.sync_all()?;
+        }
+
         let active_journal_path = journal_folder_path.join("0.jnl");
-        let journal = Journal::create_new(&active_journal_path)?.with_compression(
-            config.journal_compression_type,
-            config.journal_compression_threshold,
-        );
+        let journal = Journal::create_new(&active_journal_path, config.journal_preallocation_size)?
+            .with_compression(
+                config.journal_compression_type,
+                config.journal_compression_threshold,
+            )
+            .with_encryption(config.journal_encryption.clone());
         let journal = Arc::new(journal);
 
         // NOTE: Lastly, fsync .fjall marker, which contains the version
         let mut marker = std::fs::File::create_new(config.path.join(FJALL_MARKER))?;
-        FormatVersion::V3.write_file_header(&mut marker)?;
+        FormatVersion::V4.write_file_header(&mut marker)?;
         marker.sync_all()?;
 
         // IMPORTANT: fsync folders on Unix
@@ -798,42 +1687,29 @@ impl Database {
             xxhash_rust::xxh3::Xxh3Builder::new(),
         )));
 
-        let meta_tree = lsm_tree::Config::new(
+        let meta_tree = meta_tree_config(
             config.path.join(KEYSPACES_FOLDER).join("0"),
             seqno.clone(),
             visible_seqno.clone(),
         )
         .use_cache(config.cache.clone())
         .use_descriptor_table(config.descriptor_table.clone())
-        .expect_point_read_hits(true)
-        .data_block_size_policy(crate::config::BlockSizePolicy::all(4_096))
-        .data_block_hash_ratio_policy(crate::config::HashRatioPolicy::all(8.0))
-        .data_block_compression_policy(crate::config::CompressionPolicy::disabled())
-        .data_block_restart_interval_policy(crate::config::RestartIntervalPolicy::all(1))
-        .index_block_compression_policy(crate::config::CompressionPolicy::disabled())
-        .filter_policy(crate::config::FilterPolicy::new([
-            lsm_tree::config::FilterPolicyEntry::Bloom(
-                lsm_tree::config::BloomConstructionPolicy::FalsePositiveRate(0.0001),
-            ),
-            lsm_tree::config::FilterPolicyEntry::Bloom(
-                lsm_tree::config::BloomConstructionPolicy::FalsePositiveRate(0.01),
-            ),
-        ]))
         .open()?;
 
-        let meta_keyspace = MetaKeyspace::new(
-            meta_tree,
-            keyspaces.clone(),
-            seqno.clone(),
-            visible_seqno.clone(),
-        );
+        let meta_keyspace = MetaKeyspace::new(meta_tree, keyspaces.clone());
 
         let supervisor = Supervisor::new(SupervisorInner {
             flush_manager: FlushManager::new(),
             write_buffer_size: WriteBufferManager::default(),
             snapshot_tracker: SnapshotTracker::new(visible_seqno),
-            journal_manager: Arc::new(RwLock::new(JournalManager::new())),
+            journal_manager: Arc::new(RwLock::new(JournalManager::new(
+                config.journal_archiver.clone(),
+                config.max_recycled_journals,
+                config.path.clone(),
+            ))),
             backpressure_lock: Mutex::default(),
+            change_feed: ChangeFeed::default(),
+            publish_queue: PublishQueue::default(),
             seqno,
         });
 
@@ -861,20 +1737,82 @@ impl Database {
             keyspaces,
             stop_signal: lsm_tree::stop_signal::StopSignal::default(),
             active_thread_counter,
+            is_journal_syncer_spawned: AtomicBool::default(),
             is_poisoned,
             stats,
             lock_file,
+            read_only_view: None,
         };
 
         Ok(Self(Arc::new(inner)))
     }
 }
 
+fn is_journal_file(file_name: &std::ffi::OsStr) -> bool {
+    file_name
+        .to_str()
+        .is_some_and(|file_name| file_name.ends_with(RECYCLED_JOURNAL_SUFFIX))
+        || Path::new(file_name)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jnl"))
+}
+
+fn contains_journals(folder: &Path) -> std::io::Result<bool> {
+    match std::fs::read_dir(folder) {
+        Ok(dirents) => {
+            for dirent in dirents {
+                if is_journal_file(&dirent?.file_name()) {
+                    return Ok(true);
+                }
+            }
+
+            Ok(false)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Removes the (recycled) journals in the given folder,
+/// and the folder itself if it is empty afterwards.
+fn remove_journals(folder: &Path) -> std::io::Result<()> {
+    for dirent in std::fs::read_dir(folder)? {
+        let dirent = dirent?;
+
+        if is_journal_file(&dirent.file_name()) {
+            std::fs::remove_file(dirent.path())?;
+        }
+    }
+
+    if std::fs::read_dir(folder)?.next().is_none() {
+        std::fs::remove_dir(folder)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use test_log::test;
 
+    #[test]
+    pub fn test_journal_syncer_spawned_lazily() -> crate::Result<()> {
+        use std::sync::atomic::Ordering::Acquire;
+
+        let folder = tempfile::tempdir()?;
+        let db = Database::builder(&folder).open()?;
+        assert!(!db.is_journal_syncer_spawned.load(Acquire));
+
+        db.set_journal_bytes_per_sync(Some(0))?;
+        assert!(!db.is_journal_syncer_spawned.load(Acquire));
+
+        db.set_journal_sync_interval(Some(Duration::from_millis(1)))?;
+        assert!(db.is_journal_syncer_spawned.load(Acquire));
+
+        Ok(())
+    }
+
     #[test]
     pub fn test_exotic_keyspace_names() -> crate::Result<()> {
         let folder = tempfile::tempdir()?;
@@ -2,19 +2,31 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use crate::path::absolute_path;
-use lsm_tree::{Cache, CompressionType, DescriptorTable};
+use crate::{
+    journal::{error::RecoveryMode, writer::DEFAULT_PRE_ALLOCATED_BYTES},
+    path::absolute_path,
+    EncryptionProvider, JournalArchiver, JournalCompression,
+};
+use lsm_tree::{Cache, DescriptorTable, SeqNo};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 /// Global database configuration
 #[derive(Clone)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "config flags are independent"
+)]
 pub struct Config {
     /// Base path of database
     pub(crate) path: PathBuf,
 
+    /// Folder of the journals, by default the base path
+    pub(crate) journal_path: PathBuf,
+
     /// When true, the path will be deleted upon drop
     pub(crate) clean_path_on_drop: bool,
 
@@ -35,13 +47,42 @@ pub struct Config {
 
     pub(crate) manual_journal_persist: bool,
 
+    /// Interval in which the journal is synced in the background
+    pub(crate) journal_sync_interval: Option<Duration>,
+
+    /// Amount of written bytes after which the journal is synced in the background
+    pub(crate) journal_bytes_per_sync: Option<u64>,
+
     /// Amount of concurrent worker threads
     pub(crate) worker_threads: usize,
 
-    pub(crate) journal_compression_type: CompressionType,
+    pub(crate) journal_compression_type: JournalCompression,
 
     pub(crate) journal_compression_threshold: usize,
-    // pub(crate) journal_recovery_mode: RecoveryMode,
+
+    /// How to handle corrupt or incomplete batches when recovering the journal
+    pub(crate) journal_recovery_mode: RecoveryMode,
+
+    /// If set, journaled batches above this seqno are discarded on recovery
+    pub(crate) recover_until_seqno: Option<SeqNo>,
+
+    /// Receives fully flushed journals instead of deleting them
+    pub(crate) journal_archiver: Option<Arc<dyn JournalArchiver>>,
+
+    /// Encrypts data that is written into journals
+    pub(crate) journal_encryption: Option<Arc<dyn EncryptionProvider>>,
+
+    /// Size new journal files are pre-allocated to
+    pub(crate) journal_preallocation_size: u64,
+
+    /// Amount of evicted journal files to keep around for reuse
+    pub(crate) max_recycled_journals: usize,
+
+    /// If `true`, the database is opened without taking the lock and rejects writes
+    pub(crate) read_only: bool,
+
+    /// If `true`, unreferenced and uninitialized keyspace folders are deleted on recovery
+    pub(crate) delete_unreferenced_keyspaces: bool,
 }
 
 const DEFAULT_CPU_CORES: usize = 4;
@@ -63,21 +104,33 @@ impl Config {
         let queried_cores = std::thread::available_parallelism().map(usize::from);
         let worker_threads = queried_cores.unwrap_or(1).min(DEFAULT_CPU_CORES);
 
+        let path = absolute_path(path);
+
         Self {
-            path: absolute_path(path),
+            journal_path: path.clone(),
+            path,
             clean_path_on_drop: false,
             descriptor_table: Arc::new(DescriptorTable::new(get_open_file_limit())),
             max_write_buffer_size_in_bytes: /* 128 MiB */ 128 * 1_024 * 1_024,
             max_journaling_size_in_bytes: /* 512 MiB */ 512 * 1_024 * 1_024,
             worker_threads,
-            // journal_recovery_mode: RecoveryMode::default(),
+            journal_recovery_mode: RecoveryMode::default(),
+            recover_until_seqno: None,
+            journal_archiver: None,
+            journal_encryption: None,
+            journal_preallocation_size: DEFAULT_PRE_ALLOCATED_BYTES,
+            max_recycled_journals: 0,
+            read_only: false,
+            delete_unreferenced_keyspaces: true,
             manual_journal_persist: false,
+            journal_sync_interval: None,
+            journal_bytes_per_sync: None,
 
             #[cfg(not(feature = "lz4"))]
-            journal_compression_type: CompressionType::None,
+            journal_compression_type: JournalCompression::None,
 
             #[cfg(feature = "lz4")]
-            journal_compression_type: CompressionType::Lz4,
+            journal_compression_type: JournalCompression::Lz4,
 
             journal_compression_threshold: 4_096,
 
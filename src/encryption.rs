@@ -0,0 +1,199 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+/// Encrypts journal items before they are written to disk, and decrypts them when they are read back
+///
+/// Only journals are encrypted: the keys and values of journaled items are encrypted,
+/// while their keyspace IDs, lengths and batch markers are not (but are authenticated,
+/// see [`EncryptionProvider::encrypt`]).
+///
+/// This is **not** encryption at rest: keyspace tables, blob files and the meta keyspace are
+/// written by `lsm-tree`, which does not support encryption, so they are stored **unencrypted**.
+/// Once a memtable is flushed, its data is on disk in plaintext, so this only protects data
+/// that is still in the journal.
+///
+/// See [`DatabaseBuilder::journal_encryption`](crate::DatabaseBuilder::journal_encryption).
+///
+/// Ciphertexts should be self-describing (e.g. contain the nonce), because nothing else is stored
+/// alongside them.
+///
+/// # Key rotation
+///
+/// Journals are only read on recovery and by [`Database::changes_since`](crate::Database::changes_since).
+/// After rotating keys, the provider needs to be able to decrypt data encrypted using the
+/// previous key, until all journals written using that key have been evicted (and archived).
+/// A provider can achieve that by prefixing ciphertexts with a key ID.
+pub trait EncryptionProvider: Send + Sync {
+    /// Encrypts the given plaintext.
+    ///
+    /// `aad` is associated data (the item's header), which is not encrypted, but needs
+    /// to be authenticated, so tampering with it is detected when decrypting.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if the data could not be encrypted.
+    fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> crate::Result<Vec<u8>>;
+
+    /// Decrypts the given ciphertext, authenticating it together with `aad`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if the data could not be decrypted or authenticated.
+    ///
+    /// Any error is reported as [`Error::Decrypt`](crate::Error::Decrypt).
+    fn decrypt(&self, ciphertext: &[u8], aad: &[u8]) -> crate::Result<Vec<u8>>;
+}
+
+/// Encrypts data using ChaCha20-Poly1305
+///
+/// Each ciphertext is prefixed with a random 96-bit nonce.
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{ChaCha20Poly1305Provider, Database};
+/// # let folder = tempfile::tempdir()?;
+/// let key = [0; 32]; // NOTE: Load the key from a secure location instead
+///
+/// let db = Database::builder(&folder)
+///     .journal_encryption(ChaCha20Poly1305Provider::new(key))
+///     .open()?;
+/// #
+/// # Ok::<_, fjall::Error>(())
+/// ```
+#[cfg(feature = "encryption")]
+pub struct ChaCha20Poly1305Provider {
+    cipher: chacha20poly1305::ChaCha20Poly1305,
+}
+
+#[cfg(feature = "encryption")]
+impl std::fmt::Debug for ChaCha20Poly1305Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // NOTE: Do not leak the key
+        f.debug_struct("ChaCha20Poly1305Provider")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl ChaCha20Poly1305Provider {
+    /// Size of the nonce that prefixes every ciphertext
+    const NONCE_SIZE: usize = 12;
+
+    /// Creates a provider using the given 256-bit key.
+    #[must_use]
+    pub fn new(key: [u8; 32]) -> Self {
+        use chacha20poly1305::KeyInit;
+
+        Self {
+            cipher: chacha20poly1305::ChaCha20Poly1305::new(&key.into()),
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl EncryptionProvider for ChaCha20Poly1305Provider {
+    fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> crate::Result<Vec<u8>> {
+        use chacha20poly1305::{
+            aead::{Aead, AeadCore, OsRng, Payload},
+            ChaCha20Poly1305,
+        };
+
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+
+        let ciphertext = self.cipher.encrypt(&nonce, payload).map_err(|e| {
+            log::error!("Encryption failed: {e}");
+            std::io::Error::other("encryption failed")
+        })?;
+
+        let mut bytes = Vec::with_capacity(Self::NONCE_SIZE + ciphertext.len());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+
+        Ok(bytes)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8], aad: &[u8]) -> crate::Result<Vec<u8>> {
+        use chacha20poly1305::{
+            aead::{Aead, Payload},
+            Nonce,
+        };
+
+        let Some((nonce, ciphertext)) = ciphertext.split_at_checked(Self::NONCE_SIZE) else {
+            log::error!("Ciphertext is too short");
+            return Err(crate::Error::Decrypt);
+        };
+
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|e| {
+                log::error!("Decryption failed: {e}");
+                crate::Error::Decrypt
+            })
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "encryption")]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn chacha20_poly1305_roundtrip() -> crate::Result<()> {
+        let provider = ChaCha20Poly1305Provider::new([1; 32]);
+
+        let ciphertext = provider.encrypt(b"hello", b"header")?;
+        assert_ne!(
+            b"hello",
+            &ciphertext[ChaCha20Poly1305Provider::NONCE_SIZE..][..5]
+        );
+        assert_eq!(b"hello", &*provider.decrypt(&ciphertext, b"header")?);
+
+        // Nonces are random
+        assert_ne!(ciphertext, provider.encrypt(b"hello", b"header")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chacha20_poly1305_wrong_key() -> crate::Result<()> {
+        let ciphertext = ChaCha20Poly1305Provider::new([1; 32]).encrypt(b"hello", b"header")?;
+
+        assert!(matches!(
+            ChaCha20Poly1305Provider::new([2; 32]).decrypt(&ciphertext, b"header"),
+            Err(crate::Error::Decrypt),
+        ));
+        assert!(matches!(
+            ChaCha20Poly1305Provider::new([1; 32]).decrypt(&ciphertext[..4], b"header"),
+            Err(crate::Error::Decrypt),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn chacha20_poly1305_wrong_aad() -> crate::Result<()> {
+        let provider = ChaCha20Poly1305Provider::new([1; 32]);
+        let ciphertext = provider.encrypt(b"hello", b"header")?;
+
+        assert!(matches!(
+            provider.decrypt(&ciphertext, b"tampered"),
+            Err(crate::Error::Decrypt),
+        ));
+
+        Ok(())
+    }
+}
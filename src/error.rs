@@ -3,8 +3,10 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::{
-    journal::error::RecoveryError as JournalRecoveryError, version::FormatVersion, CompressionType,
+    backup::BackupId, journal::error::RecoveryError as JournalRecoveryError,
+    version::FormatVersion, JournalCompression, SeqNo,
 };
+use std::path::PathBuf;
 
 /// Errors that may occur in the storage engine
 #[derive(Debug)]
@@ -23,7 +25,11 @@ pub enum Error {
     InvalidVersion(Option<FormatVersion>),
 
     /// Decompression failed
-    Decompress(CompressionType),
+    Decompress(JournalCompression),
+
+    /// Decryption failed, e.g. because the data was encrypted using another key,
+    /// or no encryption provider was configured
+    Decrypt,
 
     /// Invalid journal trailer detected
     InvalidTrailer,
@@ -48,6 +54,33 @@ pub enum Error {
 
     /// Database is unrecoverable, see logs for details
     Unrecoverable,
+
+    /// The requested changes are not available anymore, because the journals
+    /// containing them have already been deleted
+    ///
+    /// Contains the lowest seqno that changes can still be read from.
+    ChangesEvicted(SeqNo),
+
+    /// The database can not be recovered to the requested seqno, because
+    /// newer data has already been flushed to disk
+    ///
+    /// Contains the highest persisted seqno.
+    RecoveryTargetUnreachable(SeqNo),
+
+    /// The database was opened with a different journal folder than it was created with
+    ///
+    /// Contains the journal folder the database was created with.
+    JournalPathMismatch(PathBuf),
+
+    /// The backup does not exist, or is incomplete
+    BackupNotFound(BackupId),
+
+    /// The database was opened in read-only mode, so it can not be written to
+    ReadOnly,
+
+    /// A write batch wrote into keyspaces with and without journaling,
+    /// so it could not be recovered atomically
+    MixedJournaling,
 }
 
 impl std::fmt::Display for Error {
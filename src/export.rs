@@ -0,0 +1,411 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+//! Portable dump format of a single keyspace
+//!
+//! ```text
+//! header:  magic, format version, keyspace name, options, checksum
+//! items:   [tag, key, value]*
+//! trailer: end tag, item count, checksum
+//! ```
+//!
+//! The header is checksummed separately, so the options can be verified
+//! before the keyspace is created.
+
+use crate::{
+    keyspace::{name::is_valid_keyspace_name, options::CreateOptions, KeyspaceKey},
+    Database, HashMap, Keyspace, Readable, Snapshot,
+};
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use lsm_tree::{UserKey, UserValue};
+use std::io::{BufReader, BufWriter, Read, Write};
+
+/// Magic bytes at the start of every export
+const MAGIC: &[u8; 4] = b"FJEX";
+
+/// Version of the export format
+const FORMAT_VERSION: u8 = 1;
+
+const TAG_END: u8 = 0;
+const TAG_ITEM: u8 = 1;
+
+/// Config keys are prefixed by a tag and the keyspace ID, which is not exported
+const CONFIG_KEY_PREFIX_LEN: usize = 1 + std::mem::size_of::<crate::keyspace::InternalKeyspaceId>();
+
+fn invalid_data(msg: &'static str) -> crate::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg).into()
+}
+
+/// Writer that checksums everything written through it
+struct ChecksumWriter<W: Write> {
+    inner: W,
+    hasher: xxhash_rust::xxh3::Xxh3,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: xxhash_rust::xxh3::Xxh3::default(),
+        }
+    }
+
+    /// Writes the checksum of everything written since the last checksum.
+    fn write_checksum(&mut self) -> std::io::Result<()> {
+        let checksum = self.hasher.digest();
+        self.hasher.reset();
+        self.inner.write_u64::<LE>(checksum)
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+
+        #[expect(clippy::indexing_slicing, reason = "n <= buf.len()")]
+        self.hasher.update(&buf[..n]);
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader that checksums everything read through it
+struct ChecksumReader<R: Read> {
+    inner: R,
+    hasher: xxhash_rust::xxh3::Xxh3,
+}
+
+impl<R: Read> ChecksumReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: xxhash_rust::xxh3::Xxh3::default(),
+        }
+    }
+
+    /// Reads a checksum and compares it against everything read since the last checksum.
+    fn verify_checksum(&mut self) -> crate::Result<()> {
+        let expected = self.hasher.digest();
+        self.hasher.reset();
+
+        if self.inner.read_u64::<LE>()? != expected {
+            return Err(invalid_data("export checksum mismatch"));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+
+        #[expect(clippy::indexing_slicing, reason = "n <= buf.len()")]
+        self.hasher.update(&buf[..n]);
+
+        Ok(n)
+    }
+}
+
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Writes every item of the keyspace, as seen by the snapshot.
+///
+/// Returns the number of exported items.
+pub fn export<W: Write>(keyspace: &Keyspace, snapshot: &Snapshot, writer: W) -> crate::Result<u64> {
+    let mut writer = ChecksumWriter::new(BufWriter::new(writer));
+
+    writer.write_all(MAGIC)?;
+    writer.write_u8(FORMAT_VERSION)?;
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "keyspace names are max. 255 bytes"
+    )]
+    writer.write_u8(keyspace.name.len() as u8)?;
+    writer.write_all(keyspace.name.as_bytes())?;
+
+    let options = keyspace.config.encode_kvs(0);
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "there are only a few options"
+    )]
+    writer.write_u16::<LE>(options.len() as u16)?;
+
+    for (key, value) in options {
+        #[expect(clippy::indexing_slicing, reason = "config keys are always prefixed")]
+        let name = &key[CONFIG_KEY_PREFIX_LEN..];
+
+        #[expect(clippy::cast_possible_truncation, reason = "option names are short")]
+        writer.write_u8(name.len() as u8)?;
+        writer.write_all(name)?;
+
+        #[expect(clippy::cast_possible_truncation, reason = "option values are short")]
+        writer.write_u32::<LE>(value.len() as u32)?;
+        writer.write_all(&value)?;
+    }
+
+    writer.write_checksum()?;
+
+    let mut item_count = 0;
+
+    for kv in snapshot.iter(keyspace) {
+        let (key, value) = kv.into_inner()?;
+
+        writer.write_u8(TAG_ITEM)?;
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "keys are limited to 65535 bytes"
+        )]
+        writer.write_u16::<LE>(key.len() as u16)?;
+        writer.write_all(&key)?;
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "values are limited to 2^32 bytes"
+        )]
+        writer.write_u32::<LE>(value.len() as u32)?;
+        writer.write_all(&value)?;
+
+        item_count += 1;
+    }
+
+    writer.write_u8(TAG_END)?;
+    writer.write_u64::<LE>(item_count)?;
+    writer.write_checksum()?;
+
+    writer.flush()?;
+
+    log::debug!(
+        "Exported {item_count} items of keyspace {:?} at seqno={}",
+        keyspace.name,
+        snapshot.seqno(),
+    );
+
+    Ok(item_count)
+}
+
+/// Reads the header of an export, returning the keyspace name and options.
+fn read_header<R: Read>(
+    reader: &mut ChecksumReader<R>,
+) -> crate::Result<(KeyspaceKey, CreateOptions)> {
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+
+    if &magic != MAGIC {
+        return Err(invalid_data("invalid export header"));
+    }
+
+    let version = reader.read_u8()?;
+
+    if version != FORMAT_VERSION {
+        log::error!("Unsupported export format version: {version}");
+        return Err(invalid_data("unsupported export format version"));
+    }
+
+    let name_len = reader.read_u8()?;
+    let name = read_bytes(reader, name_len.into())?;
+
+    let option_count = reader.read_u16::<LE>()?;
+    let mut options = HashMap::<String, UserValue>::default();
+
+    for _ in 0..option_count {
+        let option_name_len = reader.read_u8()?;
+        let option_name = read_bytes(reader, option_name_len.into())?;
+
+        let value_len = reader.read_u32::<LE>()?;
+        let value = read_bytes(reader, value_len as usize)?;
+
+        let option_name =
+            String::from_utf8(option_name).map_err(|_| invalid_data("invalid option name"))?;
+
+        options.insert(option_name, value.into());
+    }
+
+    // IMPORTANT: Verify the header before decoding the options, decoding trusts its input
+    reader.verify_checksum()?;
+
+    let name = String::from_utf8(name).map_err(|_| invalid_data("invalid keyspace name"))?;
+
+    if !is_valid_keyspace_name(&name) {
+        return Err(invalid_data("invalid keyspace name"));
+    }
+
+    let options = CreateOptions::decode_kvs(|option_name| Ok(options.get(option_name).cloned()))?;
+
+    Ok((name.into(), options))
+}
+
+/// Ingests the items of an export into the (new) keyspace.
+fn ingest_items<R: Read>(
+    keyspace: &Keyspace,
+    reader: &mut ChecksumReader<R>,
+) -> crate::Result<u64> {
+    let mut ingestion = keyspace.start_ingestion()?;
+
+    let mut item_count = 0;
+    let mut prev_key: Option<UserKey> = None;
+
+    loop {
+        match reader.read_u8()? {
+            TAG_ITEM => {
+                let key_len = reader.read_u16::<LE>()?;
+                let key: UserKey = read_bytes(reader, key_len.into())?.into();
+
+                let value_len = reader.read_u32::<LE>()?;
+                let value = read_bytes(reader, value_len as usize)?;
+
+                // NOTE: Ingestion panics on unsorted input
+                if prev_key.as_ref().is_some_and(|prev| *prev >= key) {
+                    return Err(invalid_data("export items are not sorted"));
+                }
+
+                ingestion.write(key.clone(), value)?;
+                prev_key = Some(key);
+
+                item_count += 1;
+            }
+            TAG_END => break,
+            tag => return Err(crate::Error::InvalidTag(("ExportTag", tag))),
+        }
+    }
+
+    if reader.read_u64::<LE>()? != item_count {
+        return Err(invalid_data("export item count mismatch"));
+    }
+
+    // IMPORTANT: Only make the items visible if the export is intact
+    reader.verify_checksum()?;
+
+    ingestion.finish()?;
+
+    Ok(item_count)
+}
+
+/// Creates a keyspace from an export.
+pub fn import<R: Read>(db: &Database, reader: R) -> crate::Result<Keyspace> {
+    if db.config.read_only {
+        return Err(crate::Error::ReadOnly);
+    }
+
+    let mut reader = ChecksumReader::new(BufReader::new(reader));
+
+    let (name, options) = read_header(&mut reader)?;
+
+    let keyspace = {
+        #[expect(clippy::expect_used)]
+        let keyspaces = db.keyspaces.write().expect("lock is poisoned");
+
+        if keyspaces.contains_key(&name) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("keyspace {name:?} already exists"),
+            )
+            .into());
+        }
+
+        let keyspace_id = db.keyspace_id_counter.next();
+        let keyspace = Keyspace::create_new(keyspace_id, db, name.clone(), options)?;
+
+        db.meta_keyspace
+            .create_keyspace(keyspace_id, &name, keyspace.clone(), keyspaces)?;
+
+        #[cfg(feature = "__internal_whitebox")]
+        crate::drop::increment_drop_counter();
+
+        keyspace
+    };
+
+    match ingest_items(&keyspace, &mut reader) {
+        Ok(item_count) => {
+            log::debug!("Imported {item_count} items into keyspace {name:?}");
+            Ok(keyspace)
+        }
+        Err(e) => {
+            log::error!("Import of keyspace {name:?} failed: {e:?}");
+
+            // NOTE: Do not leave a partially imported keyspace behind
+            db.delete_keyspace(keyspace)?;
+
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta_keyspace::encode_config_key;
+    use test_log::test;
+
+    #[test]
+    fn export_config_key_prefix() {
+        let key = encode_config_key(0, "filter_policy");
+        assert_eq!(b"filter_policy", &key[CONFIG_KEY_PREFIX_LEN..]);
+    }
+
+    #[test]
+    fn import_missing_option() -> crate::Result<()> {
+        let folder = tempfile::tempdir()?;
+
+        let db = Database::builder(folder.path().join("a")).open()?;
+        let tree = db.keyspace("default", crate::KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+
+        let mut export = vec![];
+        tree.export(&db.snapshot(), &mut export)?;
+
+        // Rewrite the header without the "max_memtable_size" option
+        let reader = &mut &export[..];
+
+        let mut header = vec![];
+        header.extend(read_bytes(reader, MAGIC.len() + 1)?);
+
+        let name_len = reader.read_u8()?;
+        header.push(name_len);
+        header.extend(read_bytes(reader, name_len.into())?);
+
+        let option_count = reader.read_u16::<LE>()?;
+        header.write_u16::<LE>(option_count - 1)?;
+
+        for _ in 0..option_count {
+            let option_name_len = reader.read_u8()?;
+            let option_name = read_bytes(reader, option_name_len.into())?;
+            let value_len = reader.read_u32::<LE>()?;
+            let value = read_bytes(reader, value_len as usize)?;
+
+            if option_name != b"max_memtable_size" {
+                header.push(option_name_len);
+                header.extend(option_name);
+                header.write_u32::<LE>(value_len)?;
+                header.extend(value);
+            }
+        }
+
+        let _checksum = reader.read_u64::<LE>()?;
+        let checksum = xxhash_rust::xxh3::xxh3_64(&header);
+        header.write_u64::<LE>(checksum)?;
+        header.extend_from_slice(reader);
+
+        let other_db = Database::builder(folder.path().join("b")).open()?;
+
+        assert!(matches!(
+            other_db.import(&*header),
+            Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::InvalidData,
+        ));
+        assert!(!other_db.keyspace_exists("default"));
+
+        Ok(())
+    }
+}
@@ -11,8 +11,14 @@ pub const KEYSPACES_FOLDER: &str = "keyspaces";
 pub const LOCK_FILE: &str = "lock";
 pub const FJALL_MARKER: &str = "version";
 
+/// Contains the journal folder, if it is not the database folder
+pub const JOURNAL_PATH_MARKER: &str = "journal_path";
+
 pub const LSM_CURRENT_VERSION_MARKER: &str = "current";
 
+/// Contains damaged or unreferenced keyspace folders, see `Database::repair`
+pub const QUARANTINE_FOLDER: &str = ".quarantine";
+
 #[cfg(not(target_os = "windows"))]
 pub fn fsync_directory<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
     let path = path.as_ref();
@@ -0,0 +1,246 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use super::recovery::JournalId;
+use crate::file::fsync_directory;
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Handles journals that are no longer needed by the database
+///
+/// Once every keyspace has flushed the data of a sealed journal, the journal
+/// is handed to the archiver instead of being deleted.
+/// The journal file is named `{id}.jnl`, where `id` is increasing.
+///
+/// The archiver may move, copy or ignore the file. If the file still exists at its
+/// original path after [`JournalArchiver::archive`] returns, it is deleted.
+///
+/// If archiving fails, the journal is kept and archiving is retried on the
+/// next journal maintenance.
+///
+/// Archiving runs in a background worker while holding the journal manager lock,
+/// so it should not block for too long.
+///
+/// Any `Fn(&Path) -> fjall::Result<()>` closure can be used as an archiver.
+pub trait JournalArchiver: Send + Sync {
+    /// Archives the journal file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if the journal could not be archived.
+    fn archive(&self, path: &Path) -> crate::Result<()>;
+}
+
+impl<F: Fn(&Path) -> crate::Result<()> + Send + Sync> JournalArchiver for F {
+    fn archive(&self, path: &Path) -> crate::Result<()> {
+        self(path)
+    }
+}
+
+/// Moves journals into an archive directory
+///
+/// Optionally, only the newest journals are retained, see [`DirectoryArchiver::max_journal_count`]
+/// and [`DirectoryArchiver::max_size`].
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Database, DirectoryArchiver};
+/// # let folder = tempfile::tempdir()?;
+/// let archiver = DirectoryArchiver::new(folder.path().join("archive")).max_journal_count(100);
+///
+/// let db = Database::builder(folder.path().join("db"))
+///     .journal_archiver(archiver)
+///     .open()?;
+/// #
+/// # Ok::<_, fjall::Error>(())
+/// ```
+pub struct DirectoryArchiver {
+    path: PathBuf,
+    max_count: Option<usize>,
+    max_bytes: Option<u64>,
+
+    /// Makes sure pruning does not run concurrently, if the archiver is shared between databases
+    lock: Mutex<()>,
+}
+
+impl DirectoryArchiver {
+    /// Creates an archiver that moves journals into the given directory.
+    ///
+    /// The directory is created if it does not exist.
+    ///
+    /// Journals are moved using a hard link, so the directory should be on the same
+    /// file system as the database, otherwise journals are copied.
+    ///
+    /// Archived journals are never overwritten, so the directory can not be shared
+    /// by multiple databases: archiving a journal whose name is already taken fails,
+    /// and the journal is kept.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().into(),
+            max_count: None,
+            max_bytes: None,
+            lock: Mutex::default(),
+        }
+    }
+
+    /// Sets the maximum amount of archived journals to keep.
+    ///
+    /// If exceeded, the oldest journals are deleted.
+    ///
+    /// Default = unlimited
+    #[must_use]
+    pub fn max_journal_count(mut self, n: usize) -> Self {
+        self.max_count = Some(n);
+        self
+    }
+
+    /// Sets the maximum size of all archived journals in bytes.
+    ///
+    /// If exceeded, the oldest journals are deleted.
+    /// The newest journal is always retained.
+    ///
+    /// Default = unlimited
+    #[must_use]
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Returns the path of the archive directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the archived journals, from oldest to newest.
+    fn list_journals(&self) -> crate::Result<Vec<(JournalId, PathBuf, u64)>> {
+        let mut journals = vec![];
+
+        for dirent in std::fs::read_dir(&self.path)? {
+            let dirent = dirent?;
+
+            if !dirent.file_type()?.is_file() {
+                continue;
+            }
+
+            let Some(journal_id) = dirent
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_suffix(".jnl"))
+                .and_then(|basename| basename.parse::<JournalId>().ok())
+            else {
+                continue;
+            };
+
+            journals.push((journal_id, dirent.path(), dirent.metadata()?.len()));
+        }
+
+        journals.sort_by_key(|(id, _, _)| *id);
+
+        Ok(journals)
+    }
+
+    fn prune(&self) -> crate::Result<()> {
+        if self.max_count.is_none() && self.max_bytes.is_none() {
+            return Ok(());
+        }
+
+        let journals = self.list_journals()?;
+        let mut total_bytes = journals.iter().map(|(_, _, size)| size).sum::<u64>();
+        let mut deleted_count = 0;
+
+        for (journal_id, path, size) in &journals {
+            let remaining = journals.len() - deleted_count;
+
+            let exceeds_count = self.max_count.is_some_and(|n| remaining > n);
+            let exceeds_size = remaining > 1 && self.max_bytes.is_some_and(|n| total_bytes > n);
+
+            if !exceeds_count && !exceeds_size {
+                break;
+            }
+
+            log::debug!(
+                "Deleting archived journal {journal_id} at {}",
+                path.display()
+            );
+
+            std::fs::remove_file(path).inspect_err(|e| {
+                log::error!(
+                    "Failed to delete archived journal at {}: {e:?}",
+                    path.display(),
+                );
+            })?;
+
+            total_bytes = total_bytes.saturating_sub(*size);
+            deleted_count += 1;
+        }
+
+        if deleted_count > 0 {
+            fsync_directory(&self.path)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl JournalArchiver for DirectoryArchiver {
+    fn archive(&self, path: &Path) -> crate::Result<()> {
+        #[expect(clippy::expect_used)]
+        let _lock = self.lock.lock().expect("lock is poisoned");
+
+        let file_name = path.file_name().ok_or_else(|| {
+            log::error!("Invalid journal file name: {}", path.display());
+            crate::Error::JournalRecovery(crate::JournalRecoveryError::InvalidFileName)
+        })?;
+
+        std::fs::create_dir_all(&self.path)?;
+
+        let dest = self.path.join(file_name);
+
+        log::debug!("Archiving journal {} to {}", path.display(), dest.display());
+
+        // NOTE: Link instead of renaming, because a rename would replace an existing journal
+        match std::fs::hard_link(path, &dest) {
+            Ok(()) => {}
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::CrossesDevices | std::io::ErrorKind::Unsupported
+                ) =>
+            {
+                // NOTE: Archive directory is on another file system, so we need to copy,
+                // and only move the complete copy into place
+                let mut copy = tempfile::NamedTempFile::new_in(&self.path)?;
+                std::io::copy(&mut std::fs::File::open(path)?, &mut copy)?;
+                copy.as_file().sync_all()?;
+                copy.persist_noclobber(&dest).map_err(|e| {
+                    log::error!(
+                        "Failed to move journal copy to {}: {:?}",
+                        dest.display(),
+                        e.error,
+                    );
+                    e.error
+                })?;
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to move journal {} to {}: {e:?}",
+                    path.display(),
+                    dest.display(),
+                );
+                return Err(e.into());
+            }
+        }
+
+        std::fs::remove_file(path)?;
+
+        // IMPORTANT: fsync folder on Unix
+        fsync_directory(&self.path)?;
+
+        self.prune()
+    }
+}
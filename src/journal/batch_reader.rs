@@ -2,26 +2,64 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use super::reader::JournalReader;
-use crate::{journal::entry::Entry, keyspace::InternalKeyspaceId, JournalRecoveryError};
+use super::{error::RecoveryMode, reader::JournalReader};
+use crate::{
+    journal::entry::Entry, keyspace::InternalKeyspaceId, JournalCompression, JournalRecoveryError,
+};
 use lsm_tree::{SeqNo, UserKey, UserValue, ValueType};
 use std::{fs::OpenOptions, hash::Hasher};
 
+/// Validity of a journaled batch
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BatchStatus {
+    /// Batch is intact, and would be recovered
+    Valid,
+
+    /// Batch checksum does not match its items
+    ChecksumMismatch {
+        /// Checksum stored in the end marker
+        expected: u64,
+
+        /// Checksum of the items that were read
+        got: u64,
+    },
+
+    /// Batch has less items than its start marker announced
+    InsufficientLength,
+
+    /// Batch has more items than its start marker announced
+    TooManyItems,
+
+    /// Batch has no end marker, because it was cut off, or followed by another batch
+    MissingTerminator,
+}
+
 #[derive(Debug)]
 pub struct ReadBatchItem {
     pub keyspace_id: InternalKeyspaceId,
     pub key: UserKey,
     pub value: UserValue,
     pub value_type: ValueType,
+    pub compression: JournalCompression,
 }
 
 #[derive(Debug)]
 pub struct Batch {
     pub(crate) seqno: SeqNo,
     pub(crate) items: Vec<ReadBatchItem>,
+
+    /// File offset of the batch's start marker
+    pub(crate) offset: u64,
+
+    /// Item count announced by the batch's start marker
+    pub(crate) item_count: u32,
+
+    /// Always [`BatchStatus::Valid`], unless invalid batches are yielded,
+    /// see [`JournalBatchReader::with_invalid_batches`]
+    pub(crate) status: BatchStatus,
 }
 
-#[expect(clippy::module_name_repetitions)]
+#[expect(clippy::module_name_repetitions, clippy::struct_excessive_bools)]
 pub struct JournalBatchReader {
     reader: JournalReader,
     items: Vec<ReadBatchItem>,
@@ -30,23 +68,136 @@ pub struct JournalBatchReader {
     batch_seqno: SeqNo,
     last_valid_pos: u64,
     checksum_builder: xxhash_rust::xxh3::Xxh3,
+    recovery_mode: RecoveryMode,
+
+    /// Set in [`RecoveryMode::SkipInvalidBatches`] when the current batch
+    /// was found to be invalid, so it is discarded when its end marker is reached
+    is_batch_invalid: bool,
+
+    /// Batches above this seqno are truncated from the journal
+    seqno_limit: Option<SeqNo>,
+
+    /// Set once a batch above the seqno limit was found
+    is_limit_reached: bool,
+
+    /// Seqno of the last valid batch
+    last_batch_seqno: SeqNo,
+
+    /// File offset of the current batch's start marker
+    batch_offset: u64,
+
+    /// Item count announced by the current batch's start marker
+    batch_item_count: u32,
+
+    /// File offset of the last entry that was read
+    last_entry_pos: u64,
+
+    /// If set, invalid batches are yielded instead of skipped
+    yield_invalid_batches: bool,
+
+    /// Error that is returned after yielding the batch it interrupted
+    pending_error: Option<crate::Error>,
 }
 
 impl JournalBatchReader {
     pub fn new(reader: JournalReader) -> Self {
+        let recovery_mode = reader.recovery_mode;
+        let last_valid_pos = reader.last_valid_pos;
+
         Self {
             reader,
             items: Vec::with_capacity(10),
             checksum_builder: xxhash_rust::xxh3::Xxh3::new(),
             is_in_batch: false,
             batch_seqno: 0,
-            last_valid_pos: 0,
+            last_valid_pos,
             batch_counter: 0,
+            recovery_mode,
+            is_batch_invalid: false,
+            seqno_limit: None,
+            is_limit_reached: false,
+            last_batch_seqno: 0,
+            batch_offset: 0,
+            batch_item_count: 0,
+            last_entry_pos: last_valid_pos,
+            yield_invalid_batches: false,
+            pending_error: None,
         }
     }
 
+    /// Yields invalid batches with their [`BatchStatus`], instead of failing or skipping them.
+    ///
+    /// Items and end markers without a start marker are returned as [`JournalRecoveryError::MissingStart`],
+    /// after which reading continues. Undecodable entries are returned as errors, after yielding the batch they interrupted.
+    ///
+    /// Used to inspect corrupt journals, so the journal is never truncated.
+    pub fn with_invalid_batches(mut self) -> Self {
+        self.yield_invalid_batches = true;
+        self.recovery_mode = RecoveryMode::SkipInvalidBatches;
+        self.reader.recovery_mode = RecoveryMode::AbsoluteConsistency;
+        self.reader.is_read_only = true;
+        self
+    }
+
+    /// Sets the recovery mode, which decides how invalid batches are handled.
+    pub fn with_recovery_mode(mut self, mode: RecoveryMode) -> Self {
+        self.recovery_mode = mode;
+        self.reader.recovery_mode = mode;
+        self
+    }
+
+    /// Stops at the first batch with a seqno above `limit`, truncating
+    /// the journal, so that batch and everything after it is discarded.
+    pub fn with_seqno_limit(mut self, limit: Option<SeqNo>) -> Self {
+        self.seqno_limit = limit;
+        self
+    }
+
+    /// Treats batches below `seqno` as stale data behind the end of a recycled journal.
+    ///
+    /// Used when continuing to read a journal, after batches were already read from it.
+    pub fn with_last_seqno(mut self, seqno: SeqNo) -> Self {
+        self.last_batch_seqno = seqno;
+        self
+    }
+
+    /// Returns the position after the last valid batch.
+    pub fn last_valid_pos(&self) -> u64 {
+        self.last_valid_pos
+    }
+
+    /// Returns the position of the last entry that was read (or failed to be read).
+    pub fn last_entry_pos(&self) -> u64 {
+        self.last_entry_pos
+    }
+
+    /// Takes the current batch, which has no end marker.
+    fn take_unterminated_batch(&mut self) -> Batch {
+        let batch = Batch {
+            seqno: self.batch_seqno,
+            items: std::mem::take(&mut self.items),
+            offset: self.batch_offset,
+            item_count: self.batch_item_count,
+            status: BatchStatus::MissingTerminator,
+        };
+        self.reset_batch();
+        batch
+    }
+
+    fn reset_batch(&mut self) {
+        self.is_in_batch = false;
+        self.is_batch_invalid = false;
+        self.batch_counter = 0;
+        self.items.clear();
+        self.checksum_builder = xxhash_rust::xxh3::Xxh3::new();
+    }
+
     // TODO: reallocate space
     fn truncate_to(&self, last_valid_pos: u64) -> crate::Result<()> {
+        if self.reader.is_read_only {
+            return Ok(());
+        }
+
         log::trace!("Truncating journal to {last_valid_pos}");
 
         // TODO: on windows, reading file probably needs to be closed first...?
@@ -60,6 +211,13 @@ impl JournalBatchReader {
 
     fn on_close(&self) -> crate::Result<()> {
         if self.is_in_batch {
+            if self.recovery_mode == RecoveryMode::AbsoluteConsistency {
+                log::error!("Invalid batch: missing terminator");
+                return Err(crate::Error::JournalRecovery(
+                    JournalRecoveryError::MissingTerminator,
+                ));
+            }
+
             log::debug!("Invalid batch: missing terminator, but last batch, so probably incomplete, discarding to keep atomicity");
 
             // Discard batch
@@ -73,68 +231,232 @@ impl JournalBatchReader {
 impl Iterator for JournalBatchReader {
     type Item = crate::Result<Batch>;
 
+    #[expect(clippy::too_many_lines)]
     fn next(&mut self) -> Option<Self::Item> {
         use crate::Error::JournalRecovery;
 
+        if let Some(e) = self.pending_error.take() {
+            return Some(Err(e));
+        }
+
+        if self.is_limit_reached {
+            return None;
+        }
+
         loop {
+            self.last_entry_pos = self.reader.last_valid_pos;
+
             let Some(item) = self.reader.next() else {
+                if self.yield_invalid_batches && self.is_in_batch {
+                    self.is_limit_reached = true;
+                    return Some(Ok(self.take_unterminated_batch()));
+                }
+
                 fail_iter!(self.on_close());
                 return None;
             };
-            let item = fail_iter!(item);
+
+            let item = match item {
+                Ok(item) => item,
+                Err(e) if self.yield_invalid_batches && self.is_in_batch => {
+                    self.pending_error = Some(e);
+                    self.is_limit_reached = true;
+                    return Some(Ok(self.take_unterminated_batch()));
+                }
+                Err(e) => return Some(Err(e)),
+            };
 
             let journal_file_pos = self.reader.last_valid_pos;
 
             match item {
                 Entry::Start { item_count, seqno } => {
-                    if self.is_in_batch {
-                        log::debug!("Invalid batch: found batch start inside batch");
-
-                        // Discard batch
+                    // NOTE: Batch seqnos never decrease inside a journal, so this is stale
+                    // data behind the end of a recycled journal, which was not overwritten yet
+                    if seqno < self.last_batch_seqno {
+                        log::debug!(
+                            "Found batch with seqno={seqno} below previous seqno={}, assuming end of recycled journal",
+                            self.last_batch_seqno,
+                        );
+
+                        if self.yield_invalid_batches && self.is_in_batch {
+                            self.is_limit_reached = true;
+                            return Some(Ok(self.take_unterminated_batch()));
+                        }
+
+                        if self.is_in_batch
+                            && self.recovery_mode == RecoveryMode::AbsoluteConsistency
+                        {
+                            log::error!("Invalid batch: missing terminator");
+                            return Some(Err(JournalRecovery(
+                                JournalRecoveryError::MissingTerminator,
+                            )));
+                        }
+
+                        self.reset_batch();
+
+                        // Discard (possibly incomplete) batch and everything after it
                         fail_iter!(self.truncate_to(self.last_valid_pos));
 
                         return None;
                     }
 
+                    // NOTE: The unterminated batch is yielded after the new batch was started
+                    let unterminated_batch = (self.yield_invalid_batches && self.is_in_batch)
+                        .then(|| self.take_unterminated_batch());
+
+                    if self.is_in_batch {
+                        match self.recovery_mode {
+                            RecoveryMode::TolerateCorruptTail => {
+                                log::debug!("Invalid batch: found batch start inside batch");
+
+                                // Discard batch
+                                fail_iter!(self.truncate_to(self.last_valid_pos));
+
+                                return None;
+                            }
+                            RecoveryMode::SkipInvalidBatches => {
+                                log::warn!("Invalid batch: found batch start inside batch, skipping unterminated batch");
+                            }
+                            RecoveryMode::AbsoluteConsistency => {
+                                log::error!("Invalid batch: found batch start inside batch");
+                                return Some(Err(JournalRecovery(
+                                    JournalRecoveryError::MissingTerminator,
+                                )));
+                            }
+                        }
+                    }
+
+                    self.reset_batch();
+
                     self.is_in_batch = true;
                     self.batch_counter = item_count;
+                    self.batch_item_count = item_count;
                     self.batch_seqno = seqno;
+                    self.batch_offset = self.last_entry_pos;
+
+                    if let Some(batch) = unterminated_batch {
+                        return Some(Ok(batch));
+                    }
                 }
                 Entry::End(expected_checksum) => {
-                    if self.batch_counter > 0 {
-                        log::error!("Invalid batch: insufficient length");
-                        return Some(Err(JournalRecovery(
-                            JournalRecoveryError::InsufficientLength,
-                        )));
+                    if !self.is_in_batch {
+                        match self.recovery_mode {
+                            RecoveryMode::TolerateCorruptTail => {
+                                log::error!("Invalid batch: found end marker without start marker");
+
+                                // Discard batch
+                                fail_iter!(self.truncate_to(self.last_valid_pos));
+
+                                return None;
+                            }
+                            RecoveryMode::SkipInvalidBatches => {
+                                log::warn!("Invalid batch: found end marker without start marker, skipping");
+
+                                if self.yield_invalid_batches {
+                                    return Some(Err(JournalRecovery(
+                                        JournalRecoveryError::MissingStart,
+                                    )));
+                                }
+
+                                self.last_valid_pos = journal_file_pos;
+                                continue;
+                            }
+                            RecoveryMode::AbsoluteConsistency => {
+                                log::error!("Invalid batch: found end marker without start marker");
+                                return Some(Err(JournalRecovery(
+                                    JournalRecoveryError::MissingStart,
+                                )));
+                            }
+                        }
                     }
 
-                    if !self.is_in_batch {
-                        log::error!("Invalid batch: found end marker without start marker");
+                    let got_checksum = self.checksum_builder.finish();
 
-                        // Discard batch
-                        fail_iter!(self.truncate_to(self.last_valid_pos));
+                    let (error, status) = if self.is_batch_invalid {
+                        (
+                            Some(JournalRecoveryError::TooManyItems),
+                            BatchStatus::TooManyItems,
+                        )
+                    } else if self.batch_counter > 0 {
+                        log::error!("Invalid batch: insufficient length");
+                        (
+                            Some(JournalRecoveryError::InsufficientLength),
+                            BatchStatus::InsufficientLength,
+                        )
+                    } else if got_checksum != expected_checksum {
+                        log::error!("Invalid batch: checksum check failed, expected: {expected_checksum}, got: {got_checksum}");
+                        (
+                            Some(JournalRecoveryError::ChecksumMismatch),
+                            BatchStatus::ChecksumMismatch {
+                                expected: expected_checksum,
+                                got: got_checksum,
+                            },
+                        )
+                    } else {
+                        (None, BatchStatus::Valid)
+                    };
 
-                        return None;
+                    if let Some(error) = error {
+                        if self.recovery_mode != RecoveryMode::SkipInvalidBatches {
+                            return Some(Err(JournalRecovery(error)));
+                        }
+
+                        log::warn!(
+                            "Skipping invalid batch with seqno={} ({error:?})",
+                            self.batch_seqno,
+                        );
+
+                        let batch = Batch {
+                            seqno: self.batch_seqno,
+                            items: std::mem::take(&mut self.items),
+                            offset: self.batch_offset,
+                            item_count: self.batch_item_count,
+                            status,
+                        };
+
+                        self.reset_batch();
+                        self.last_valid_pos = journal_file_pos;
+
+                        if self.yield_invalid_batches {
+                            return Some(Ok(batch));
+                        }
+
+                        continue;
                     }
 
-                    let got_checksum = self.checksum_builder.finish();
-                    self.checksum_builder = xxhash_rust::xxh3::Xxh3::new();
+                    if self
+                        .seqno_limit
+                        .is_some_and(|limit| self.batch_seqno > limit)
+                    {
+                        log::debug!(
+                            "Found batch with seqno={} above recovery target, discarding rest of journal",
+                            self.batch_seqno,
+                        );
 
-                    if got_checksum != expected_checksum {
-                        log::error!("Invalid batch: checksum check failed, expected: {expected_checksum}, got: {got_checksum}");
-                        return Some(Err(JournalRecovery(JournalRecoveryError::ChecksumMismatch)));
+                        self.reset_batch();
+                        self.is_limit_reached = true;
+
+                        // Discard batch and everything after it
+                        fail_iter!(self.truncate_to(self.last_valid_pos));
+
+                        return None;
                     }
 
                     // Reset all variables
                     self.is_in_batch = false;
                     self.batch_counter = 0;
+                    self.checksum_builder = xxhash_rust::xxh3::Xxh3::new();
 
                     self.last_valid_pos = journal_file_pos;
+                    self.last_batch_seqno = self.batch_seqno;
 
                     let items = std::mem::take(&mut self.items);
                     return Some(Ok(Batch {
                         seqno: self.batch_seqno,
                         items,
+                        offset: self.batch_offset,
+                        item_count: self.batch_item_count,
+                        status,
                     }));
                 }
                 Entry::Item {
@@ -144,39 +466,71 @@ impl Iterator for JournalBatchReader {
                     value_type,
                     compression,
                 } => {
-                    let item = Entry::Item {
-                        keyspace_id,
-                        key: key.clone(),
-                        value: value.clone(),
-                        value_type,
-                        compression,
-                    };
-                    let mut bytes = Vec::with_capacity(100);
-                    fail_iter!(item.encode_into(&mut bytes));
-
-                    self.checksum_builder.update(&bytes);
-
                     if !self.is_in_batch {
-                        log::debug!("Invalid batch: found end marker without start marker");
-
-                        // Discard batch
-                        fail_iter!(self.truncate_to(self.last_valid_pos));
+                        match self.recovery_mode {
+                            RecoveryMode::TolerateCorruptTail => {
+                                log::debug!("Invalid batch: found item without start marker");
+
+                                // Discard batch
+                                fail_iter!(self.truncate_to(self.last_valid_pos));
+
+                                return None;
+                            }
+                            RecoveryMode::SkipInvalidBatches => {
+                                log::warn!(
+                                    "Invalid batch: found item without start marker, skipping"
+                                );
+
+                                if self.yield_invalid_batches {
+                                    return Some(Err(JournalRecovery(
+                                        JournalRecoveryError::MissingStart,
+                                    )));
+                                }
+
+                                continue;
+                            }
+                            RecoveryMode::AbsoluteConsistency => {
+                                log::error!("Invalid batch: found item without start marker");
+                                return Some(Err(JournalRecovery(
+                                    JournalRecoveryError::MissingStart,
+                                )));
+                            }
+                        }
+                    }
 
-                        return None;
+                    if self.is_batch_invalid && !self.yield_invalid_batches {
+                        continue;
                     }
 
+                    // NOTE: Checksum the raw bytes, because items may have been written in an older format
+                    self.checksum_builder.update(self.reader.entry_bytes());
+
                     if self.batch_counter == 0 {
-                        log::error!("Invalid batch: Expected end marker (too many items in batch)");
-                        return Some(Err(JournalRecovery(JournalRecoveryError::TooManyItems)));
+                        if !self.is_batch_invalid {
+                            log::error!(
+                                "Invalid batch: Expected end marker (too many items in batch)"
+                            );
+                        }
+
+                        if self.recovery_mode != RecoveryMode::SkipInvalidBatches {
+                            return Some(Err(JournalRecovery(JournalRecoveryError::TooManyItems)));
+                        }
+
+                        self.is_batch_invalid = true;
+
+                        if !self.yield_invalid_batches {
+                            continue;
+                        }
+                    } else {
+                        self.batch_counter -= 1;
                     }
 
-                    self.batch_counter -= 1;
-
                     self.items.push(ReadBatchItem {
                         keyspace_id,
                         key,
                         value,
                         value_type,
+                        compression,
                     });
                 }
             }
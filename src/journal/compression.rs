@@ -0,0 +1,115 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use lsm_tree::CompressionType;
+use std::borrow::Cow;
+
+/// Compression algorithm for values that are written into the journal
+///
+/// In addition to the [`CompressionType`]s that are supported by keyspaces,
+/// journals can be compressed using zstd (`zstd` feature).
+/// Keyspace data blocks can not use zstd, see [`CompressionType`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum JournalCompression {
+    /// No compression
+    None,
+
+    /// LZ4 compression
+    #[cfg(feature = "lz4")]
+    Lz4,
+
+    /// Zstd compression using the given compression level
+    ///
+    /// Higher levels result in smaller journals, but slower writes.
+    /// Level 0 uses zstd's default level.
+    ///
+    /// The level is not stored in the journal, so items that are read
+    /// back from a journal always report level 0.
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+}
+
+impl From<CompressionType> for JournalCompression {
+    fn from(value: CompressionType) -> Self {
+        match value {
+            CompressionType::None => Self::None,
+
+            #[cfg(feature = "lz4")]
+            CompressionType::Lz4 => Self::Lz4,
+        }
+    }
+}
+
+impl JournalCompression {
+    /// Returns the ID that is stored in the item header.
+    pub(crate) fn id(self) -> u8 {
+        match self {
+            Self::None => 0,
+
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => 1,
+
+            #[cfg(feature = "zstd")]
+            Self::Zstd(_) => 2,
+        }
+    }
+
+    /// Parses the ID that is stored in the item header.
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::None),
+
+            #[cfg(feature = "lz4")]
+            1 => Some(Self::Lz4),
+
+            #[cfg(feature = "zstd")]
+            2 => Some(Self::Zstd(0)),
+
+            _ => None,
+        }
+    }
+
+    /// Encodes the compression, including the zstd level, for the keyspace config.
+    pub(crate) fn encode_config(self) -> Vec<u8> {
+        #[cfg(feature = "zstd")]
+        if let Self::Zstd(level) = self {
+            let mut bytes = vec![self.id()];
+            bytes.extend_from_slice(&level.to_le_bytes());
+            return bytes;
+        }
+
+        vec![self.id()]
+    }
+
+    /// Decodes the compression from the keyspace config.
+    ///
+    /// Returns `None` if the compression type is not supported by this build.
+    pub(crate) fn decode_config(bytes: &[u8]) -> Option<Self> {
+        let (&id, rest) = bytes.split_first()?;
+
+        match Self::from_id(id)? {
+            #[cfg(feature = "zstd")]
+            Self::Zstd(_) => Some(Self::Zstd(i32::from_le_bytes(rest.try_into().ok()?))),
+
+            compression => {
+                debug_assert!(rest.is_empty());
+                Some(compression)
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "zstd"), expect(clippy::unnecessary_wraps))]
+    pub(crate) fn compress(self, value: &[u8]) -> std::io::Result<Cow<'_, [u8]>> {
+        match self {
+            Self::None => Ok(Cow::Borrowed(value)),
+
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => Ok(Cow::Owned(lz4_flex::compress(value))),
+
+            #[cfg(feature = "zstd")]
+            Self::Zstd(level) => zstd::bulk::compress(value, level).map(Cow::Owned),
+        }
+    }
+}
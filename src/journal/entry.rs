@@ -2,13 +2,12 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use crate::{file::MAGIC_BYTES, keyspace::InternalKeyspaceId, Slice};
+use super::{compression::JournalCompression, recovery::JournalId};
+use crate::{file::MAGIC_BYTES, keyspace::InternalKeyspaceId, EncryptionProvider, Slice};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-use lsm_tree::{
-    coding::{Decode, Encode},
-    CompressionType, SeqNo, UserKey, UserValue, ValueType,
-};
+use lsm_tree::{coding::Decode, CompressionType, SeqNo, UserKey, UserValue, ValueType};
 use std::io::{Read, Write};
+use varint_rs::{VarintReader, VarintWriter};
 
 /// Journal entry. Every batch is composed as a Start, followed by N items, followed by an End.
 ///
@@ -19,6 +18,9 @@ use std::io::{Read, Write};
 /// - The end entry terminates each batch with the magic string: [`TRAILER_MAGIC`].
 ///
 /// - If a start entry is detected, while inside a batch, the batch is broken.
+///
+/// Items are written in the compact format (see [`serialize_marker_item`]), but journals written
+/// by older versions contain items in the v1 format, which can still be read.
 #[derive(Debug, Eq, PartialEq)]
 pub enum Entry {
     Start {
@@ -30,12 +32,173 @@ pub enum Entry {
         key: UserKey,
         value: UserValue,
         value_type: ValueType,
-        compression: CompressionType,
+        compression: JournalCompression,
     },
     End(u64),
 }
 
+/// Header bit that marks the keyspace ID as omitted, because it is the same as the previous item's
+const SAME_KEYSPACE_BIT: u8 = 0b0000_0100;
+
+/// Header bits that encode the value type
+const VALUE_TYPE_MASK: u8 = 0b0000_0011;
+
+/// Shift of the header bits that encode the compression type
+const COMPRESSION_SHIFT: u8 = 3;
+
+/// Header bits that encode the compression type (after shifting)
+const COMPRESSION_MASK: u8 = 0b0000_1111;
+
+/// Header bit that marks the key and value as encrypted
+const ENCRYPTED_BIT: u8 = 0b1000_0000;
+
+/// Returns the associated data that authenticates the header of an encrypted item
+///
+/// Contains the keyspace ID even if the item omits it, so an item can not be moved
+/// to another keyspace either.
+fn item_aad(
+    header: u8,
+    keyspace_id: InternalKeyspaceId,
+    key_len: u16,
+    value_len: u32,
+    on_disk_value_len: u32,
+) -> [u8; 19] {
+    let mut aad = [0; 19];
+    aad[0] = header;
+    aad[1..9].copy_from_slice(&keyspace_id.to_le_bytes());
+    aad[9..11].copy_from_slice(&key_len.to_le_bytes());
+    aad[11..15].copy_from_slice(&value_len.to_le_bytes());
+    aad[15..19].copy_from_slice(&on_disk_value_len.to_le_bytes());
+    aad
+}
+
+/// Writes an item in the compact format
+///
+/// The format is:
+///
+/// - tag (1 byte)
+/// - header (1 byte): value type, compression, whether the keyspace ID is omitted,
+///   and whether the item is encrypted
+/// - keyspace ID (varint), omitted if it is the same as `prev_keyspace_id`
+/// - key length (varint)
+/// - value length (varint)
+/// - on-disk (compressed) value length (varint), only if compressed
+/// - key
+/// - (compressed) value
+///
+/// If encrypted, the key and (compressed) value are replaced by their ciphertext,
+/// prefixed by its length (varint). The header is authenticated as associated data.
+///
+/// `prev_keyspace_id` is the keyspace ID of the previous item in the same batch.
+#[expect(clippy::too_many_arguments)]
 pub fn serialize_marker_item<W: Write>(
+    writer: &mut W,
+    prev_keyspace_id: Option<InternalKeyspaceId>,
+    keyspace_id: InternalKeyspaceId,
+    key: &[u8],
+    value: &[u8],
+    value_type: ValueType,
+    compression: JournalCompression,
+    encryption: Option<&dyn EncryptionProvider>,
+) -> crate::Result<()> {
+    writer.write_u8(Tag::CompactItem.into())?;
+
+    let value_type_bits = match value_type {
+        ValueType::Value => 0,
+        ValueType::Tombstone => 1,
+        ValueType::WeakTombstone => 2,
+        ValueType::Indirection => 3,
+    };
+
+    let is_same_keyspace = prev_keyspace_id == Some(keyspace_id);
+
+    let mut header = value_type_bits | (compression.id() << COMPRESSION_SHIFT);
+    if is_same_keyspace {
+        header |= SAME_KEYSPACE_BIT;
+    }
+    if encryption.is_some() {
+        header |= ENCRYPTED_BIT;
+    }
+    writer.write_u8(header)?;
+
+    if !is_same_keyspace {
+        writer.write_u64_varint(keyspace_id)?;
+    }
+
+    let compressed_value = compression.compress(value)?;
+
+    // NOTE: Truncation is okay and actually needed
+    #[expect(clippy::cast_possible_truncation)]
+    writer.write_u16_varint(key.len() as u16)?;
+
+    // NOTE: Truncation is okay and actually needed
+    #[expect(clippy::cast_possible_truncation)]
+    writer.write_u32_varint(value.len() as u32)?;
+
+    if compression != JournalCompression::None {
+        // NOTE: Truncation is okay and actually needed
+        #[expect(clippy::cast_possible_truncation)]
+        writer.write_u32_varint(compressed_value.len() as u32)?;
+    }
+
+    if let Some(encryption) = encryption {
+        let mut plaintext = Vec::with_capacity(key.len() + compressed_value.len());
+        plaintext.extend_from_slice(key);
+        plaintext.extend_from_slice(&compressed_value);
+
+        // NOTE: Truncation is okay and actually needed
+        #[expect(clippy::cast_possible_truncation)]
+        let aad = item_aad(
+            header,
+            keyspace_id,
+            key.len() as u16,
+            value.len() as u32,
+            compressed_value.len() as u32,
+        );
+
+        let ciphertext = encryption.encrypt(&plaintext, &aad)?;
+
+        // NOTE: Truncation is okay and actually needed
+        #[expect(clippy::cast_possible_truncation)]
+        writer.write_u32_varint(ciphertext.len() as u32)?;
+
+        writer.write_all(&ciphertext)?;
+    } else {
+        writer.write_all(key)?;
+
+        writer.write_all(&compressed_value)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the prefix of an entry in a recycled journal
+///
+/// The journal ID tells entries apart from stale entries of the journal file's previous use.
+pub fn serialize_recycled_prefix<W: Write>(
+    writer: &mut W,
+    journal_id: JournalId,
+) -> std::io::Result<()> {
+    writer.write_u8(Tag::Recycled.into())?;
+    writer.write_u64::<LittleEndian>(journal_id)
+}
+
+/// Reads the prefix of an entry in a recycled journal, returning the journal ID
+pub fn decode_recycled_prefix<R: Read>(reader: &mut R) -> crate::Result<JournalId> {
+    let tag = reader.read_u8()?;
+
+    if tag != u8::from(Tag::Recycled) {
+        return Err(crate::Error::InvalidTag(("JournalMarkerTag", tag)));
+    }
+
+    Ok(reader.read_u64::<LittleEndian>()?)
+}
+
+/// Writes an item in the v1 format, which uses fixed-size integers
+///
+/// Only written by older versions, so journals of those can still be recovered.
+#[cfg(test)]
+pub fn serialize_marker_item_v1<W: Write>(
     writer: &mut W,
     keyspace_id: InternalKeyspaceId,
     key: &[u8],
@@ -43,21 +206,15 @@ pub fn serialize_marker_item<W: Write>(
     value_type: ValueType,
     compression: CompressionType,
 ) -> Result<(), lsm_tree::Error> {
+    use lsm_tree::coding::Encode;
+
     writer.write_u8(Tag::Item.into())?;
 
     writer.write_u8(u8::from(value_type))?;
 
     compression.encode_into(writer)?;
 
-    let compressed_value = match compression {
-        CompressionType::None => std::borrow::Cow::Borrowed(value),
-
-        #[cfg(feature = "lz4")]
-        CompressionType::Lz4 => {
-            let compressed = lz4_flex::compress(value);
-            std::borrow::Cow::Owned(compressed)
-        }
-    };
+    let compressed_value = JournalCompression::from(compression).compress(value)?;
 
     // NOTE: Truncation is okay and actually needed
     writer.write_u64::<LittleEndian>(keyspace_id)?;
@@ -81,22 +238,111 @@ pub fn serialize_marker_item<W: Write>(
     Ok(())
 }
 
+/// Rejects value lengths that the compressed value can not possibly decompress to.
+///
+/// The value length is read from the journal, so it needs to be checked before
+/// allocating the decompression buffer.
+#[cfg(any(feature = "lz4", feature = "zstd"))]
+fn check_value_len(
+    compression: JournalCompression,
+    value_len: u32,
+    on_disk_value_len: u32,
+    max_ratio: u64,
+) -> crate::Result<()> {
+    if u64::from(value_len) > u64::from(on_disk_value_len) * max_ratio {
+        log::error!(
+            "Value length {value_len} exceeds the maximum for {on_disk_value_len} compressed bytes"
+        );
+        return Err(crate::Error::Decompress(compression));
+    }
+    Ok(())
+}
+
+fn read_value<R: Read>(
+    reader: &mut R,
+    compression: JournalCompression,
+    value_len: u32,
+    on_disk_value_len: u32,
+) -> Result<UserValue, crate::Error> {
+    match compression {
+        JournalCompression::None => {
+            debug_assert_eq!(value_len, on_disk_value_len);
+            Ok(Slice::from_reader(reader, on_disk_value_len as usize)?)
+        }
+
+        #[cfg(feature = "lz4")]
+        JournalCompression::Lz4 => {
+            // NOTE: A LZ4 sequence can not expand to more than 255 bytes per input byte
+            check_value_len(compression, value_len, on_disk_value_len, 255)?;
+
+            let compressed_value = Slice::from_reader(reader, on_disk_value_len as usize)?;
+
+            #[warn(unsafe_code)]
+            let mut value = unsafe { Slice::builder_unzeroed(value_len as usize) };
+
+            let size = lz4_flex::decompress_into(&compressed_value, &mut value).map_err(|e| {
+                log::error!("LZ4 decompression failed: {e}");
+                crate::Error::Decompress(compression)
+            })?;
+
+            if size != value.len() {
+                log::error!("Decompressed size does not match expected value size");
+                return Err(crate::Error::Decompress(compression));
+            }
+
+            Ok(Slice::from(value.freeze()))
+        }
+
+        #[cfg(feature = "zstd")]
+        JournalCompression::Zstd(_) => {
+            // NOTE: The smallest zstd block (3 byte header, 1 byte RLE) expands to at most 128 KiB
+            check_value_len(compression, value_len, on_disk_value_len, 32_768)?;
+
+            let compressed_value = Slice::from_reader(reader, on_disk_value_len as usize)?;
+
+            let value =
+                zstd::bulk::decompress(&compressed_value, value_len as usize).map_err(|e| {
+                    log::error!("Zstd decompression failed: {e}");
+                    crate::Error::Decompress(compression)
+                })?;
+
+            if value.len() != value_len as usize {
+                log::error!("Decompressed size does not match expected value size");
+                return Err(crate::Error::Decompress(compression));
+            }
+
+            Ok(Slice::from(value))
+        }
+    }
+}
+
 pub enum Tag {
     Start = 1,
+
+    /// Item in the v1 format
     Item = 2,
+
     End = 3,
+
+    /// Item in the compact format
+    CompactItem = 4,
+
+    /// Prefix of every entry in a recycled journal, followed by the journal ID
+    Recycled = 5,
 }
 
 impl TryFrom<u8> for Tag {
     type Error = crate::Error;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        use Tag::{End, Item, Start};
+        use Tag::{CompactItem, End, Item, Recycled, Start};
 
         match value {
             1 => Ok(Start),
             2 => Ok(Item),
             3 => Ok(End),
+            4 => Ok(CompactItem),
+            5 => Ok(Recycled),
             _ => Err(crate::Error::InvalidTag(("JournalMarkerTag", value))),
         }
     }
@@ -132,7 +378,16 @@ impl Entry {
                 value_type,
                 compression,
             } => {
-                serialize_marker_item(writer, *keyspace_id, key, value, *value_type, *compression)?;
+                serialize_marker_item(
+                    writer,
+                    None,
+                    *keyspace_id,
+                    key,
+                    value,
+                    *value_type,
+                    *compression,
+                    None,
+                )?;
             }
             End(val) => {
                 writer.write_u8(Tag::End.into())?;
@@ -147,7 +402,16 @@ impl Entry {
         Ok(())
     }
 
-    pub(crate) fn decode_from<R: Read>(reader: &mut R) -> Result<Self, crate::Error> {
+    /// Decodes an entry.
+    ///
+    /// `prev_keyspace_id` is the keyspace ID of the previous item in the same batch,
+    /// which compact items may omit.
+    #[expect(clippy::too_many_lines)]
+    pub(crate) fn decode_from<R: Read>(
+        reader: &mut R,
+        prev_keyspace_id: Option<InternalKeyspaceId>,
+        encryption: Option<&dyn EncryptionProvider>,
+    ) -> Result<Self, crate::Error> {
         match reader.read_u8()?.try_into()? {
             Tag::Start => {
                 let item_count = reader.read_u32::<LittleEndian>()?;
@@ -156,11 +420,16 @@ impl Entry {
             }
             Tag::Item => {
                 let value_type = reader.read_u8()?;
-                let value_type = value_type
-                    .try_into()
-                    .map_err(|()| lsm_tree::Error::InvalidTag(("ValueType", value_type)))?;
 
-                let compression = CompressionType::decode_from(reader)?;
+                // NOTE: The journal never contains indirections
+                let value_type = match ValueType::try_from(value_type) {
+                    Ok(ValueType::Indirection) | Err(()) => {
+                        return Err(crate::Error::InvalidTag(("ValueType", value_type)));
+                    }
+                    Ok(value_type) => value_type,
+                };
+
+                let compression = CompressionType::decode_from(reader)?.into();
 
                 // Read keyspace ID
                 let keyspace_id = reader.read_u64::<LittleEndian>()?;
@@ -175,44 +444,96 @@ impl Entry {
                 let on_disk_value_len = reader.read_u32::<LittleEndian>()?;
 
                 let key = Slice::from_reader(reader, usize::from(key_len))?;
+                let value = read_value(reader, compression, value_len, on_disk_value_len)?;
 
-                let value = match compression {
-                    CompressionType::None => {
-                        debug_assert_eq!(value_len, on_disk_value_len);
-                        Slice::from_reader(reader, on_disk_value_len as usize)?
-                    }
+                Ok(Self::Item {
+                    keyspace_id,
+                    key,
+                    value,
+                    value_type,
+                    compression,
+                })
+            }
+            Tag::CompactItem => {
+                let header = reader.read_u8()?;
+
+                let value_type = match header & VALUE_TYPE_MASK {
+                    0 => ValueType::Value,
+                    1 => ValueType::Tombstone,
+                    2 => ValueType::WeakTombstone,
+                    // NOTE: The journal never contains indirections
+                    _ => return Err(crate::Error::InvalidTag(("CompactItemHeader", header))),
+                };
 
-                    #[cfg(feature = "lz4")]
-                    CompressionType::Lz4 => {
-                        let compressed_value =
-                            Slice::from_reader(reader, on_disk_value_len as usize)?;
+                let compression =
+                    JournalCompression::from_id((header >> COMPRESSION_SHIFT) & COMPRESSION_MASK)
+                        .ok_or(crate::Error::InvalidTag(("CompactItemHeader", header)))?;
 
-                        #[warn(unsafe_code)]
-                        let mut value = unsafe { Slice::builder_unzeroed(value_len as usize) };
+                let keyspace_id = if header & SAME_KEYSPACE_BIT == 0 {
+                    reader.read_u64_varint()?
+                } else {
+                    prev_keyspace_id
+                        .ok_or(crate::Error::InvalidTag(("CompactItemHeader", header)))?
+                };
 
-                        let size = lz4_flex::decompress_into(&compressed_value, &mut value)
-                            .map_err(|e| {
-                                log::error!("LZ4 decompression failed: {e}");
-                                crate::Error::Decompress(CompressionType::Lz4)
-                            })?;
+                let key_len = reader.read_u16_varint()?;
+                let value_len = reader.read_u32_varint()?;
 
-                        if size != value.len() {
-                            log::error!("Decompressed size does not match expected value size");
-                            return Err(crate::Error::Decompress(CompressionType::Lz4));
-                        }
+                let on_disk_value_len = if compression == JournalCompression::None {
+                    value_len
+                } else {
+                    reader.read_u32_varint()?
+                };
 
-                        Slice::from(value.freeze())
-                    }
+                if header & ENCRYPTED_BIT == 0 {
+                    let key = Slice::from_reader(reader, usize::from(key_len))?;
+                    let value = read_value(reader, compression, value_len, on_disk_value_len)?;
+
+                    return Ok(Self::Item {
+                        keyspace_id,
+                        key,
+                        value,
+                        value_type,
+                        compression,
+                    });
+                }
+
+                let ciphertext_len = reader.read_u32_varint()?;
+                let ciphertext = Slice::from_reader(reader, ciphertext_len as usize)?;
+
+                let Some(encryption) = encryption else {
+                    log::error!("Journal item is encrypted, but no encryption provider is set");
+                    return Err(crate::Error::Decrypt);
                 };
 
+                let aad = item_aad(header, keyspace_id, key_len, value_len, on_disk_value_len);
+
+                let plaintext = encryption.decrypt(&ciphertext, &aad).map_err(|e| {
+                    log::error!("Journal item could not be decrypted: {e:?}");
+                    crate::Error::Decrypt
+                })?;
+
+                if plaintext.len() != usize::from(key_len) + on_disk_value_len as usize {
+                    log::error!("Decrypted size does not match expected key and value size");
+                    return Err(crate::Error::Decrypt);
+                }
+
+                let (key, mut value) = plaintext.split_at(usize::from(key_len));
+                let value = read_value(&mut value, compression, value_len, on_disk_value_len)?;
+
                 Ok(Self::Item {
                     keyspace_id,
-                    key,
+                    key: Slice::from(key),
                     value,
                     value_type,
                     compression,
                 })
             }
+            // NOTE: The prefix is read by the journal reader, so it cannot appear inside an entry
+            Tag::Recycled => Err(crate::Error::InvalidTag((
+                "JournalMarkerTag",
+                u8::from(Tag::Recycled),
+            ))),
             Tag::End => {
                 let checksum = reader.read_u64::<LittleEndian>()?;
 
@@ -242,12 +563,12 @@ mod tests {
             key: vec![1, 2, 3].into(),
             value: vec![].into(),
             value_type: ValueType::Value,
-            compression: CompressionType::None,
+            compression: JournalCompression::None,
         };
 
         let serialized_data = item.encode_into_vec();
         let mut reader = &serialized_data[..];
-        let deserialized_item = Entry::decode_from(&mut reader)?;
+        let deserialized_item = Entry::decode_from(&mut reader, None, None)?;
 
         assert_eq!(item, deserialized_item);
 
@@ -260,7 +581,7 @@ mod tests {
 
         // Try to deserialize with invalid data
         let mut reader = &invalid_data[..];
-        let result = Entry::decode_from(&mut reader);
+        let result = Entry::decode_from(&mut reader, None, None);
 
         match result {
             Ok(_) => panic!("should error"),
@@ -276,18 +597,127 @@ mod tests {
 
     #[test]
     fn test_invalid_tag() {
-        let invalid_data = [4u8; 1]; // Invalid tag
+        let invalid_data = [5u8; 1]; // Invalid tag
 
         // Try to deserialize with invalid data
         let mut reader = &invalid_data[..];
-        let result = Entry::decode_from(&mut reader);
+        let result = Entry::decode_from(&mut reader, None, None);
 
         match result {
             Ok(_) => panic!("should error"),
             Err(error) => match error {
-                crate::Error::InvalidTag(("JournalMarkerTag", 4)) => {}
+                crate::Error::InvalidTag(("JournalMarkerTag", 5)) => {}
                 _ => panic!("should throw InvalidTag"),
             },
         }
     }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn test_lz4_value_len_too_large() {
+        let compressed = lz4_flex::compress(&b"abc".repeat(1_000));
+
+        #[expect(clippy::cast_possible_truncation)]
+        let result = read_value(
+            &mut &compressed[..],
+            JournalCompression::Lz4,
+            u32::MAX,
+            compressed.len() as u32,
+        );
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::Decompress(JournalCompression::Lz4))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    #[expect(clippy::expect_used)]
+    fn test_zstd_value_len_too_large() {
+        let compressed = zstd::bulk::compress(&b"abc".repeat(1_000), 3).expect("should compress");
+
+        #[expect(clippy::cast_possible_truncation)]
+        let result = read_value(
+            &mut &compressed[..],
+            JournalCompression::Zstd(3),
+            u32::MAX,
+            compressed.len() as u32,
+        );
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::Decompress(JournalCompression::Zstd(_)))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "encryption")]
+    fn test_encrypted_header_is_authenticated() -> crate::Result<()> {
+        let provider = crate::ChaCha20Poly1305Provider::new([1; 32]);
+
+        let mut serialized_data = vec![];
+        serialize_marker_item(
+            &mut serialized_data,
+            None,
+            0,
+            &[1, 2, 3],
+            &[4, 5, 6],
+            ValueType::Value,
+            JournalCompression::None,
+            Some(&provider),
+        )?;
+
+        let mut reader = &serialized_data[..];
+        assert!(matches!(
+            Entry::decode_from(&mut reader, None, Some(&provider)),
+            Ok(Entry::Item { .. }),
+        ));
+
+        // NOTE: Turn the value into a tombstone
+        serialized_data[1] ^= 1;
+
+        let mut reader = &serialized_data[..];
+        assert!(matches!(
+            Entry::decode_from(&mut reader, None, Some(&provider)),
+            Err(crate::Error::Decrypt),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn test_serialize_and_deserialize_zstd() -> crate::Result<()> {
+        let value = b"abc".repeat(1_000);
+
+        let mut serialized_data = vec![];
+        serialize_marker_item(
+            &mut serialized_data,
+            None,
+            0,
+            &[1, 2, 3],
+            &value,
+            ValueType::Value,
+            JournalCompression::Zstd(3),
+            None,
+        )?;
+        assert!(serialized_data.len() < value.len());
+
+        let mut reader = &serialized_data[..];
+        let deserialized_item = Entry::decode_from(&mut reader, None, None)?;
+
+        assert_eq!(
+            Entry::Item {
+                keyspace_id: 0,
+                key: vec![1, 2, 3].into(),
+                value: value.into(),
+                value_type: ValueType::Value,
+                compression: JournalCompression::Zstd(0),
+            },
+            deserialized_item,
+        );
+
+        Ok(())
+    }
 }
@@ -17,11 +17,20 @@ pub enum RecoveryMode {
     /// This is the default mode.
     #[default]
     TolerateCorruptTail,
-    // TODO: in the future?
-    /*  /// Skips corrupt (invalid checksum) batches. This may violate
+
+    /// Skips corrupt (invalid checksum) batches. This may violate
     /// consistency, but will recover as much data as possible.
-    SkipInvalidBatches, */
-    // TODO: absolute consistency
+    ///
+    /// Because journal entries are not framed, recovery still stops at the first
+    /// entry that cannot be decoded at all; everything after it is discarded.
+    SkipInvalidBatches,
+
+    /// Any corruption or incomplete batch, including at the tail of the journal,
+    /// is treated as an error, and recovery fails.
+    ///
+    /// Use this if losing the last write after a crash is not acceptable,
+    /// and recovery should instead require manual intervention.
+    AbsoluteConsistency,
 }
 
 /// Errors that can occur during journal recovery
@@ -31,8 +40,12 @@ pub enum RecoveryError {
     /// Batch had less items than expected, so it's incomplete
     InsufficientLength,
 
-    /* /// Batch was not terminated, so it's possibly incomplete
-    MissingTerminator, */
+    /// Batch was not terminated, so it's possibly incomplete
+    MissingTerminator,
+
+    /// An item or end marker was found without a preceding start marker
+    MissingStart,
+
     /// Too many items in batch
     TooManyItems,
 
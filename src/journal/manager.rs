@@ -2,10 +2,13 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use super::writer::Writer;
-use crate::Keyspace;
+use super::{archive::JournalArchiver, recovery::RECYCLED_JOURNAL_SUFFIX, writer::Writer};
+use crate::{read_only::has_views, Keyspace};
 use lsm_tree::{AbstractTree, SeqNo};
-use std::{path::PathBuf, sync::MutexGuard};
+use std::{
+    path::PathBuf,
+    sync::{Arc, MutexGuard},
+};
 
 /// Stores the highest seqno of a keyspace found in a journal.
 #[derive(Clone)]
@@ -40,10 +43,38 @@ impl std::fmt::Debug for Item {
 ///
 /// Each journal may contain items of different keyspaces.
 #[expect(clippy::module_name_repetitions)]
-#[derive(Debug)]
 pub struct JournalManager {
     items: Vec<Item>,
     disk_space_in_bytes: u64,
+
+    /// Batches below this seqno may have been in journals that were already deleted
+    evicted_seqno: SeqNo,
+
+    /// Receives fully flushed journals, otherwise they are deleted
+    archiver: Option<Arc<dyn JournalArchiver>>,
+
+    /// Evicted journal files that can be reused as new journals
+    recycled: Vec<PathBuf>,
+
+    /// Max amount of evicted journal files to keep for reuse
+    max_recycled: usize,
+
+    /// Folder of the database, which contains the views of read-only databases
+    db_path: PathBuf,
+}
+
+impl std::fmt::Debug for JournalManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JournalManager")
+            .field("items", &self.items)
+            .field("disk_space_in_bytes", &self.disk_space_in_bytes)
+            .field("evicted_seqno", &self.evicted_seqno)
+            .field("has_archiver", &self.archiver.is_some())
+            .field("recycled", &self.recycled)
+            .field("max_recycled", &self.max_recycled)
+            .field("db_path", &self.db_path)
+            .finish()
+    }
 }
 
 impl Drop for JournalManager {
@@ -56,13 +87,22 @@ impl Drop for JournalManager {
 }
 
 impl JournalManager {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(
+        archiver: Option<Arc<dyn JournalArchiver>>,
+        max_recycled: usize,
+        db_path: PathBuf,
+    ) -> Self {
         #[cfg(feature = "__internal_whitebox")]
         crate::drop::increment_drop_counter();
 
         Self {
             items: Vec::with_capacity(10),
             disk_space_in_bytes: 0,
+            evicted_seqno: 0,
+            archiver,
+            recycled: Vec::with_capacity(max_recycled),
+            max_recycled,
+            db_path,
         }
     }
 
@@ -86,12 +126,29 @@ impl JournalManager {
         self.items.len()
     }
 
+    /// Returns the paths of all sealed journals, from oldest to newest
+    pub(crate) fn sealed_journal_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.items.iter().map(|item| &item.path)
+    }
+
+    /// Returns the seqno below which journaled batches may have been deleted already.
+    pub(crate) fn evicted_seqno(&self) -> SeqNo {
+        self.evicted_seqno
+    }
+
+    /// Marks batches below `seqno` as possibly deleted.
+    ///
+    /// Used on recovery, if older journals have been deleted in a previous run.
+    pub(crate) fn set_evicted_seqno(&mut self, seqno: SeqNo) {
+        self.evicted_seqno = self.evicted_seqno.max(seqno);
+    }
+
     /// Returns the amount of bytes used on disk by journals
     pub(crate) fn disk_space_used(&self) -> u64 {
         self.disk_space_in_bytes
     }
 
-    /// Performs maintenance, maybe deleting some old journals
+    /// Performs maintenance, maybe deleting or archiving some old journals
     pub(crate) fn maintenance(&mut self) -> crate::Result<()> {
         log::debug!("Running journal maintenance");
 
@@ -128,27 +185,92 @@ impl JournalManager {
             // [2] Checking the seqno is safe because the queues inside the flush manager are FIFO.
             //
             // IMPORTANT: On recovery, the journals need to be flushed from oldest to newest.
-            log::trace!("Removing fully flushed journal at {}", item.path.display());
-            std::fs::remove_file(&item.path).inspect_err(|e| {
-                log::error!(
-                    "Failed to clean up stale journal file at {}: {e:?}",
+            if let Some(archiver) = &self.archiver {
+                log::trace!("Archiving fully flushed journal at {}", item.path.display());
+
+                // NOTE: Keep the journal, so archiving is retried on the next maintenance
+                if let Err(e) = archiver.archive(&item.path) {
+                    log::error!(
+                        "Failed to archive journal file at {}: {e:?}",
+                        item.path.display(),
+                    );
+                    return Ok(());
+                }
+            }
+
+            // IMPORTANT: The journal must not be replayed on recovery,
+            // so make sure it is gone, even if the archiver only copied it
+            if self.recycled.len() < self.max_recycled && item.path.try_exists()? {
+                let mut recycled_path = item.path.clone().into_os_string();
+                recycled_path.push(RECYCLED_JOURNAL_SUFFIX);
+                let recycled_path = PathBuf::from(recycled_path);
+
+                log::trace!(
+                    "Recycling fully flushed journal at {} as {}",
                     item.path.display(),
+                    recycled_path.display(),
                 );
-            })?;
+
+                std::fs::rename(&item.path, &recycled_path).inspect_err(|e| {
+                    log::error!(
+                        "Failed to recycle journal file at {}: {e:?}",
+                        item.path.display(),
+                    );
+                })?;
+
+                self.recycled.push(recycled_path);
+            } else if item.path.try_exists()? {
+                log::trace!("Removing fully flushed journal at {}", item.path.display());
+                std::fs::remove_file(&item.path).inspect_err(|e| {
+                    log::error!(
+                        "Failed to clean up stale journal file at {}: {e:?}",
+                        item.path.display(),
+                    );
+                })?;
+            }
 
             self.disk_space_in_bytes = self.disk_space_in_bytes.saturating_sub(item.size_in_bytes);
+
+            if let Some(lsn) = item.watermarks.iter().map(|wm| wm.lsn).max() {
+                self.evicted_seqno = self.evicted_seqno.max(lsn + 1);
+            }
+
             self.items.remove(0);
         }
     }
 
+    /// Seals the active journal, and starts a new one at `seqno`.
     pub(crate) fn rotate_journal(
         &mut self,
         journal_writer: &mut MutexGuard<Writer>,
         watermarks: Vec<EvictionWatermark>,
+        seqno: SeqNo,
     ) -> crate::Result<()> {
         let journal_size = journal_writer.len()?;
 
-        let (sealed_path, _) = journal_writer.rotate()?;
+        // IMPORTANT: Read-only views hard link the journals, so a recycled journal may
+        // still be read by a view, and must not be overwritten
+        let recycled = match self.recycled.pop() {
+            Some(path) if has_views(&self.db_path)? => {
+                log::debug!(
+                    "Not reusing recycled journal at {}, because read-only views exist",
+                    path.display(),
+                );
+
+                std::fs::remove_file(&path).inspect_err(|e| {
+                    log::error!(
+                        "Failed to remove recycled journal file at {}: {e:?}",
+                        path.display(),
+                    );
+                })?;
+
+                None
+            }
+            recycled => recycled,
+        };
+
+        let (sealed_path, _) = journal_writer.rotate(recycled)?;
+        journal_writer.write_start_marker(seqno)?;
 
         self.enqueue(Item {
             path: sealed_path,
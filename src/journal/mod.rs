@@ -2,31 +2,40 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
+pub mod archive;
 pub mod batch_reader;
+pub mod compression;
 pub mod entry;
 pub mod error;
 pub mod manager;
 pub mod reader;
-mod recovery;
+pub mod recovery;
 pub mod writer;
 
 #[cfg(test)]
 mod test;
 
 use self::writer::PersistMode;
-use crate::file::fsync_directory;
+use crate::{file::fsync_directory, EncryptionProvider};
 use batch_reader::JournalBatchReader;
-use lsm_tree::CompressionType;
+use compression::JournalCompression;
 use reader::JournalReader;
 use recovery::{recover_journals, RecoveryResult};
 use std::{
     path::{Path, PathBuf},
-    sync::{Mutex, MutexGuard},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
 };
-use writer::Writer;
+use writer::{GroupCommit, Writer};
 
 pub struct Journal {
     writer: Mutex<Writer>,
+
+    /// Shared sync state of the writer, which outlives journal rotations
+    group_commit: Arc<GroupCommit>,
+
+    /// If `true`, the journal file was opened without write access
+    is_read_only: bool,
 }
 
 impl std::fmt::Debug for Journal {
@@ -37,6 +46,10 @@ impl std::fmt::Debug for Journal {
 
 impl Drop for Journal {
     fn drop(&mut self) {
+        if self.is_read_only {
+            return;
+        }
+
         log::trace!("Dropping journal, trying to flush");
 
         match self.persist(PersistMode::SyncAll) {
@@ -54,7 +67,7 @@ impl Drop for Journal {
 }
 
 impl Journal {
-    pub fn with_compression(self, comp: CompressionType, threshold: usize) -> Self {
+    pub fn with_compression(self, comp: JournalCompression, threshold: usize) -> Self {
         {
             let mut writer = self.writer.lock().expect("lock is poisoned");
             writer.set_compression(comp, threshold);
@@ -62,13 +75,34 @@ impl Journal {
         self
     }
 
-    fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
-        Ok(Self {
-            writer: Mutex::new(Writer::from_file(path)?),
-        })
+    pub fn with_encryption(self, encryption: Option<Arc<dyn EncryptionProvider>>) -> Self {
+        self.get_writer().set_encryption(encryption);
+        self
+    }
+
+    fn from_writer(writer: Writer) -> Self {
+        Self {
+            group_commit: writer.group_commit().clone(),
+            writer: Mutex::new(writer),
+            is_read_only: false,
+        }
+    }
+
+    fn from_file<P: AsRef<Path>>(path: P, pre_allocated_bytes: u64) -> crate::Result<Self> {
+        Ok(Self::from_writer(Writer::from_file(
+            path,
+            pre_allocated_bytes,
+        )?))
     }
 
-    pub fn create_new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+    /// Opens a journal that is only read from, so it can still be in use by another process.
+    fn from_file_read_only<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let mut journal = Self::from_writer(Writer::open_read_only(path)?);
+        journal.is_read_only = true;
+        Ok(journal)
+    }
+
+    pub fn create_new<P: AsRef<Path>>(path: P, pre_allocated_bytes: u64) -> crate::Result<Self> {
         let path = path.as_ref();
         log::trace!("Creating new journal at {}", path.display());
 
@@ -81,7 +115,7 @@ impl Journal {
             );
         })?;
 
-        let writer = Writer::create_new(path)?;
+        let writer = Writer::create_new(path, pre_allocated_bytes)?;
 
         // IMPORTANT: fsync folder on Unix
         fsync_directory(folder)?;
@@ -89,9 +123,7 @@ impl Journal {
         #[cfg(feature = "__internal_whitebox")]
         crate::drop::increment_drop_counter();
 
-        Ok(Self {
-            writer: Mutex::new(writer),
-        })
+        Ok(Self::from_writer(writer))
     }
 
     /// Hands out write access for the journal.
@@ -105,21 +137,67 @@ impl Journal {
     }
 
     pub fn get_reader(&self) -> crate::Result<JournalBatchReader> {
-        let raw_reader = JournalReader::new(self.path())?;
+        let (path, encryption) = {
+            let writer = self.get_writer();
+            (writer.path.clone(), writer.encryption.clone())
+        };
+
+        let raw_reader = JournalReader::new(path)?.with_encryption(encryption);
         Ok(JournalBatchReader::new(raw_reader))
     }
 
     /// Persists the journal.
+    ///
+    /// Syncs happen after releasing the writer lock, so concurrent callers can share a single sync.
     pub fn persist(&self, mode: PersistMode) -> crate::Result<()> {
-        let mut lock = self.get_writer();
-        lock.persist(mode).map_err(Into::into)
+        if mode == PersistMode::Buffer {
+            let mut lock = self.get_writer();
+            return lock.persist(mode).map_err(Into::into);
+        }
+
+        let sync_ticket = self.get_writer().persist_deferred(mode)?;
+        sync_ticket.wait().map_err(Into::into)
+    }
+
+    /// Sets the interval of background syncs, `None` disables them.
+    pub fn set_sync_interval(&self, interval: Option<Duration>) {
+        self.group_commit.set_sync_interval(interval);
+    }
+
+    /// Sets the amount of written bytes that triggers a background sync, `None` disables it.
+    pub fn set_bytes_per_sync(&self, bytes: Option<u64>) {
+        self.group_commit.set_bytes_per_sync(bytes);
+    }
+
+    /// Blocks until the journal should be synced in the background.
+    ///
+    /// Returns `false` if the background syncer should stop.
+    pub fn wait_for_background_sync(&self) -> bool {
+        self.group_commit.wait_for_background_sync()
+    }
+
+    /// Stops the background syncer.
+    pub fn stop_background_sync(&self) {
+        self.group_commit.stop_background_sync();
     }
 
     pub fn recover<P: AsRef<Path>>(
         path: P,
-        compression: CompressionType,
+        compression: JournalCompression,
         compression_threshold: usize,
+        pre_allocated_bytes: u64,
     ) -> crate::Result<RecoveryResult> {
-        recover_journals(path, compression, compression_threshold)
+        recover_journals(
+            path,
+            compression,
+            compression_threshold,
+            pre_allocated_bytes,
+            false,
+        )
+    }
+
+    /// Recovers the journals without modifying any journal file.
+    pub fn recover_read_only<P: AsRef<Path>>(path: P) -> crate::Result<RecoveryResult> {
+        recover_journals(path, JournalCompression::None, 0, 0, true)
     }
 }
@@ -2,23 +2,64 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use super::entry::Entry;
+use super::{
+    entry::{decode_recycled_prefix, Entry, Tag},
+    error::RecoveryMode,
+    recovery::JournalId,
+};
+use crate::{keyspace::InternalKeyspaceId, EncryptionProvider};
 use std::{
     fs::{File, OpenOptions},
-    io::{BufReader, Seek},
+    io::{BufRead, BufReader, Read, Seek},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
+/// Keeps a copy of all bytes that are read, so entries can be checksummed exactly as they were written
+struct RecordingReader<'a, R: Read> {
+    inner: &'a mut R,
+    buf: &'a mut Vec<u8>,
+}
+
+impl<R: Read> Read for RecordingReader<'_, R> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(out)?;
+        self.buf.extend_from_slice(out.get(..n).unwrap_or_default());
+        Ok(n)
+    }
+}
+
 /// Reads and emits through the entries in a journal file, but doesn't
 /// check the validity of batches
 ///
 /// Will truncate the file to the last valid position to prevent corrupt
 /// bytes at the end of the file, which would jeopardize future writes into the file.
+///
+/// In [`RecoveryMode::AbsoluteConsistency`], undecodable bytes are returned as an error instead.
+///
+/// A read-only reader never truncates, so it can be used on journals that are still in use.
+///
+/// In a recycled journal, every entry is prefixed by the journal ID. An entry with a different
+/// (or without a) journal ID is stale data of the journal file's previous use, and marks the end of the journal.
 #[expect(clippy::module_name_repetitions)]
 pub struct JournalReader {
     pub(crate) path: PathBuf,
     pub(crate) reader: BufReader<File>,
     pub(crate) last_valid_pos: u64,
+    pub(crate) recovery_mode: RecoveryMode,
+    pub(crate) is_read_only: bool,
+
+    /// Keyspace ID of the previous item in the current batch, which compact items may omit
+    prev_keyspace_id: Option<InternalKeyspaceId>,
+
+    /// Raw bytes of the last read entry
+    entry_bytes: Vec<u8>,
+
+    /// Decrypts encrypted items
+    encryption: Option<Arc<dyn EncryptionProvider>>,
+
+    /// Journal ID of a recycled journal, taken from its first entry
+    recycled_journal_id: Option<JournalId>,
 }
 
 impl JournalReader {
@@ -29,16 +70,128 @@ impl JournalReader {
             path: path.as_ref().into(),
             reader: BufReader::new(file),
             last_valid_pos: 0,
+            recovery_mode: RecoveryMode::default(),
+            is_read_only: false,
+            prev_keyspace_id: None,
+            entry_bytes: Vec::new(),
+            encryption: None,
+            recycled_journal_id: None,
+        })
+    }
+
+    /// Opens a reader that does not modify the journal file.
+    pub fn new_read_only<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let file = File::open(&path)?;
+
+        Ok(Self {
+            path: path.as_ref().into(),
+            reader: BufReader::new(file),
+            last_valid_pos: 0,
+            recovery_mode: RecoveryMode::default(),
+            is_read_only: true,
+            prev_keyspace_id: None,
+            entry_bytes: Vec::new(),
+            encryption: None,
+            recycled_journal_id: None,
         })
     }
 
+    /// Continues reading at `pos`, which needs to be the start of an entry.
+    pub fn seek_to(&mut self, pos: u64) -> crate::Result<()> {
+        // NOTE: Take the journal ID from the first entry, because the entry at `pos`
+        // may already be stale data of a recycled journal
+        if self.recycled_journal_id.is_none() && pos > 0 {
+            self.reader.seek(std::io::SeekFrom::Start(0))?;
+
+            if self.reader.fill_buf()?.first() == Some(&u8::from(Tag::Recycled)) {
+                self.recycled_journal_id = Some(decode_recycled_prefix(&mut self.reader)?);
+            }
+        }
+
+        self.reader.seek(std::io::SeekFrom::Start(pos))?;
+        self.last_valid_pos = pos;
+        self.prev_keyspace_id = None;
+        Ok(())
+    }
+
+    /// Returns `true` if the decode error marks the regular end of the journal,
+    /// meaning either the end of the file, or the start of the pre-allocated (zeroed) space.
+    fn is_clean_end(&mut self, error: &crate::Error) -> crate::Result<bool> {
+        let stream_pos = self.reader.stream_position()?;
+
+        Ok(match error {
+            crate::Error::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                stream_pos == self.last_valid_pos
+            }
+            crate::Error::InvalidTag(("JournalMarkerTag", 0)) => {
+                stream_pos == self.last_valid_pos + 1
+            }
+            _ => false,
+        })
+    }
+
+    /// Sets the provider to decrypt encrypted items with.
+    #[must_use]
+    pub fn with_encryption(mut self, encryption: Option<Arc<dyn EncryptionProvider>>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Returns the raw bytes of the last read entry.
+    pub(crate) fn entry_bytes(&self) -> &[u8] {
+        &self.entry_bytes
+    }
+
     fn truncate_file(&mut self, pos: u64) -> crate::Result<()> {
+        if self.is_read_only {
+            return Ok(());
+        }
+
         log::debug!("truncating journal to {pos}");
         self.reader.get_mut().set_len(pos)?;
         self.reader.get_mut().sync_all()?;
         Ok(())
     }
 
+    /// Returns `true` if the entry is stale data of a recycled journal file's previous use.
+    fn is_stale(&mut self, journal_id: Option<JournalId>) -> bool {
+        match (self.recycled_journal_id, journal_id) {
+            (None, journal_id) => {
+                self.recycled_journal_id = journal_id;
+                false
+            }
+            (Some(expected), Some(journal_id)) => expected != journal_id,
+            (Some(_), None) => true,
+        }
+    }
+
+    /// Handles an entry that could not be decoded.
+    fn on_decode_error(&mut self, e: crate::Error) -> Option<crate::Result<Entry>> {
+        if self.recovery_mode == RecoveryMode::AbsoluteConsistency
+            && !fail_iter!(self.is_clean_end(&e))
+        {
+            log::error!(
+                "Invalid journal entry at {} in {}: {e:?}",
+                self.last_valid_pos,
+                self.path.display(),
+            );
+            return Some(Err(e));
+        }
+
+        if let crate::Error::Io(e) = e {
+            match e.kind() {
+                std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::Other => {
+                    fail_iter!(self.maybe_truncate_file_to_last_valid_pos());
+                    None
+                }
+                _ => Some(Err(crate::Error::Io(e))),
+            }
+        } else {
+            fail_iter!(self.maybe_truncate_file_to_last_valid_pos());
+            None
+        }
+    }
+
     fn maybe_truncate_file_to_last_valid_pos(&mut self) -> crate::Result<()> {
         let stream_pos = self.reader.stream_position()?;
 
@@ -54,25 +207,66 @@ impl Iterator for JournalReader {
     type Item = crate::Result<Entry>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match Entry::decode_from(&mut self.reader) {
+        self.entry_bytes.clear();
+
+        let is_prefixed =
+            fail_iter!(self.reader.fill_buf()).first() == Some(&u8::from(Tag::Recycled));
+
+        let journal_id = if is_prefixed {
+            let mut recording_reader = RecordingReader {
+                inner: &mut self.reader,
+                buf: &mut self.entry_bytes,
+            };
+
+            match decode_recycled_prefix(&mut recording_reader) {
+                Ok(journal_id) => Some(journal_id),
+                Err(e) => return self.on_decode_error(e),
+            }
+        } else {
+            None
+        };
+
+        if self.is_stale(journal_id) {
+            log::debug!(
+                "Found stale entry at {} in recycled journal {}, assuming end of journal",
+                self.last_valid_pos,
+                self.path.display(),
+            );
+            fail_iter!(self.truncate_file(self.last_valid_pos));
+            return None;
+        }
+
+        let mut recording_reader = RecordingReader {
+            inner: &mut self.reader,
+            buf: &mut self.entry_bytes,
+        };
+
+        match Entry::decode_from(
+            &mut recording_reader,
+            self.prev_keyspace_id,
+            self.encryption.as_deref(),
+        ) {
             Ok(item) => {
                 self.last_valid_pos = fail_iter!(self.reader.stream_position());
+
+                self.prev_keyspace_id = match &item {
+                    Entry::Item { keyspace_id, .. } => Some(*keyspace_id),
+                    Entry::Start { .. } | Entry::End(_) => None,
+                };
+
                 Some(Ok(item))
             }
-            Err(e) => {
-                if let crate::Error::Io(e) = e {
-                    match e.kind() {
-                        std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::Other => {
-                            fail_iter!(self.maybe_truncate_file_to_last_valid_pos());
-                            None
-                        }
-                        _ => Some(Err(crate::Error::Io(e))),
-                    }
-                } else {
-                    fail_iter!(self.maybe_truncate_file_to_last_valid_pos());
-                    None
-                }
+            // IMPORTANT: Never truncate the journal if an item could not be decrypted,
+            // as the wrong key would otherwise destroy all following data
+            Err(crate::Error::Decrypt) => {
+                log::error!(
+                    "Could not decrypt journal entry at {} in {}",
+                    self.last_valid_pos,
+                    self.path.display(),
+                );
+                Some(Err(crate::Error::Decrypt))
             }
+            Err(e) => self.on_decode_error(e),
         }
     }
 }
@@ -2,23 +2,28 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use super::Journal;
-use lsm_tree::CompressionType;
+use super::{compression::JournalCompression, Journal};
 use std::path::{Path, PathBuf};
 
 pub type JournalId = u64;
 
+/// Suffix of evicted journals that are kept to be reused as new journals
+pub const RECYCLED_JOURNAL_SUFFIX: &str = ".recycle";
+
 #[derive(Debug)]
 pub struct RecoveryResult {
     pub(crate) active: Journal,
+    pub(crate) active_id: JournalId,
     pub(crate) sealed: Vec<(JournalId, PathBuf)>,
     pub(crate) was_active_created: bool,
 }
 
 pub fn recover_journals<P: AsRef<Path>>(
     path: P,
-    compression: CompressionType,
+    compression: JournalCompression,
     compression_threshold: usize,
+    pre_allocated_bytes: u64,
+    read_only: bool,
 ) -> crate::Result<RecoveryResult> {
     let path = path.as_ref();
 
@@ -39,6 +44,17 @@ pub fn recover_journals<P: AsRef<Path>>(
             ));
         };
 
+        // NOTE: Journals that were about to be recycled are not needed anymore
+        if filename.ends_with(RECYCLED_JOURNAL_SUFFIX) {
+            if read_only {
+                continue;
+            }
+
+            log::debug!("Removing unused recycled journal at {}", path.display());
+            std::fs::remove_file(&path)?;
+            continue;
+        }
+
         if !std::path::Path::new(filename)
             .extension()
             .is_some_and(|ext| ext.eq_ignore_ascii_case("jnl"))
@@ -71,17 +87,29 @@ pub fn recover_journals<P: AsRef<Path>>(
     log::trace!("Recovered {journal_fragments:#?}");
 
     Ok(match journal_fragments.pop() {
-        Some((_, active)) => RecoveryResult {
-            active: Journal::from_file(active)?
+        Some((active_id, active)) if read_only => RecoveryResult {
+            active: Journal::from_file_read_only(active)?,
+            active_id,
+            sealed: journal_fragments,
+            was_active_created: false,
+        },
+        None if read_only => {
+            log::error!("No journal found in {}", path.display());
+            return Err(crate::Error::Unrecoverable);
+        }
+        Some((active_id, active)) => RecoveryResult {
+            active: Journal::from_file(active, pre_allocated_bytes)?
                 .with_compression(compression, compression_threshold),
+            active_id,
             sealed: journal_fragments,
             was_active_created: false,
         },
         None => RecoveryResult {
+            active_id: max_journal_id + 1,
             active: {
                 let id: JournalId = max_journal_id + 1;
 
-                Journal::create_new(path.join(id.to_string()))?
+                Journal::create_new(path.join(id.to_string()), pre_allocated_bytes)?
                     .with_compression(compression, compression_threshold)
             },
             sealed: vec![],
@@ -5,6 +5,7 @@ use lsm_tree::ValueType;
 use std::io::Write;
 use tempfile::tempdir;
 use test_log::test;
+use writer::DEFAULT_PRE_ALLOCATED_BYTES;
 
 impl PartialEq<BatchItem> for crate::journal::batch_reader::ReadBatchItem {
     fn eq(&self, other: &BatchItem) -> bool {
@@ -33,7 +34,7 @@ fn journal_rotation() -> crate::Result<()> {
     let next_path = dir2.path().join("1.jnl");
 
     {
-        let journal = Journal::create_new(&path)?;
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let mut writer = journal.get_writer();
 
         writer.write_batch(
@@ -45,7 +46,7 @@ fn journal_rotation() -> crate::Result<()> {
             2,
             0,
         )?;
-        writer.rotate()?;
+        writer.rotate(None)?;
     }
 
     assert!(path.try_exists()?);
@@ -69,7 +70,7 @@ fn journal_recovery_active() -> crate::Result<()> {
     let next_next_path = dir2.path().join("2.jnl");
 
     {
-        let journal = Journal::create_new(&path)?;
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let mut writer = journal.get_writer();
 
         writer.write_batch(
@@ -81,7 +82,7 @@ fn journal_recovery_active() -> crate::Result<()> {
             2,
             0,
         )?;
-        writer.rotate()?;
+        writer.rotate(None)?;
 
         writer.write_batch(
             [
@@ -92,7 +93,7 @@ fn journal_recovery_active() -> crate::Result<()> {
             2,
             1,
         )?;
-        writer.rotate()?;
+        writer.rotate(None)?;
 
         writer.write_batch(
             [
@@ -109,7 +110,12 @@ fn journal_recovery_active() -> crate::Result<()> {
     assert!(next_path.try_exists()?);
     assert!(next_next_path.try_exists()?);
 
-    let journal_recovered = Journal::recover(dir2, CompressionType::None, 0)?;
+    let journal_recovered = Journal::recover(
+        dir2,
+        JournalCompression::None,
+        0,
+        DEFAULT_PRE_ALLOCATED_BYTES,
+    )?;
     assert_eq!(journal_recovered.active.path(), next_next_path);
     assert_eq!(journal_recovered.sealed, &[(0, path), (1, next_path)]);
 
@@ -132,7 +138,8 @@ fn journal_recovery_active_lz4() -> crate::Result<()> {
     let next_next_path = dir2.path().join("2.jnl");
 
     {
-        let journal = Journal::create_new(&path)?.with_compression(CompressionType::Lz4, 1);
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?
+            .with_compression(JournalCompression::Lz4, 1);
         let mut writer = journal.get_writer();
 
         writer.write_batch(
@@ -144,7 +151,7 @@ fn journal_recovery_active_lz4() -> crate::Result<()> {
             2,
             0,
         )?;
-        writer.rotate()?;
+        writer.rotate(None)?;
 
         writer.write_batch(
             [
@@ -155,7 +162,7 @@ fn journal_recovery_active_lz4() -> crate::Result<()> {
             2,
             1,
         )?;
-        writer.rotate()?;
+        writer.rotate(None)?;
 
         writer.write_batch(
             [
@@ -172,7 +179,12 @@ fn journal_recovery_active_lz4() -> crate::Result<()> {
     assert!(next_path.try_exists()?);
     assert!(next_next_path.try_exists()?);
 
-    let journal_recovered = Journal::recover(dir2, CompressionType::None, 0)?;
+    let journal_recovered = Journal::recover(
+        dir2,
+        JournalCompression::None,
+        0,
+        DEFAULT_PRE_ALLOCATED_BYTES,
+    )?;
     assert_eq!(journal_recovered.active.path(), next_next_path);
     assert_eq!(journal_recovered.sealed, &[(0, path), (1, next_path)]);
 
@@ -191,7 +203,7 @@ fn journal_recovery_no_active() -> crate::Result<()> {
     let next_path = dir2.path().join("1.jnl");
 
     {
-        let journal = Journal::create_new(&path)?;
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
 
         {
             let mut writer = journal.get_writer();
@@ -205,7 +217,7 @@ fn journal_recovery_no_active() -> crate::Result<()> {
                 2,
                 0,
             )?;
-            writer.rotate()?;
+            writer.rotate(None)?;
         }
 
         // NOTE: Delete the new, active journal -> old journal will be
@@ -216,7 +228,12 @@ fn journal_recovery_no_active() -> crate::Result<()> {
     assert!(path.try_exists()?);
     assert!(!next_path.try_exists()?);
 
-    let journal_recovered = Journal::recover(dir2, CompressionType::None, 0)?;
+    let journal_recovered = Journal::recover(
+        dir2,
+        JournalCompression::None,
+        0,
+        DEFAULT_PRE_ALLOCATED_BYTES,
+    )?;
     assert_eq!(journal_recovered.active.path(), path);
     assert_eq!(journal_recovered.sealed, &[]);
 
@@ -239,14 +256,14 @@ fn journal_truncation_corrupt_bytes() -> crate::Result<()> {
     ];
 
     {
-        let journal = Journal::create_new(&path)?;
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         journal
             .get_writer()
             .write_batch(values.iter(), values.len(), 0)?;
     }
 
     {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -260,7 +277,7 @@ fn journal_truncation_corrupt_bytes() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -274,7 +291,7 @@ fn journal_truncation_corrupt_bytes() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -299,14 +316,14 @@ fn journal_truncation_repeating_start_marker() -> crate::Result<()> {
     ];
 
     {
-        let journal = Journal::create_new(&path)?;
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         journal
             .get_writer()
             .write_batch(values.iter(), values.len(), 0)?;
     }
 
     {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -324,7 +341,7 @@ fn journal_truncation_repeating_start_marker() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -342,7 +359,7 @@ fn journal_truncation_repeating_start_marker() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -367,14 +384,14 @@ fn journal_truncation_repeating_end_marker() -> crate::Result<()> {
     ];
 
     {
-        let journal = Journal::create_new(&path)?;
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         journal
             .get_writer()
             .write_batch(values.iter(), values.len(), 0)?;
     }
 
     {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -388,7 +405,7 @@ fn journal_truncation_repeating_end_marker() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -402,7 +419,7 @@ fn journal_truncation_repeating_end_marker() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -427,14 +444,14 @@ fn journal_truncation_repeating_item_marker() -> crate::Result<()> {
     ];
 
     {
-        let journal = Journal::create_new(&path)?;
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         journal
             .get_writer()
             .write_batch(values.iter(), values.len(), 0)?;
     }
 
     {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -448,7 +465,7 @@ fn journal_truncation_repeating_item_marker() -> crate::Result<()> {
             key: (*b"zzz").into(),
             value: (*b"").into(),
             value_type: ValueType::Tombstone,
-            compression: lsm_tree::CompressionType::None,
+            compression: JournalCompression::None,
         }
         .encode_into(&mut file)?;
 
@@ -456,7 +473,7 @@ fn journal_truncation_repeating_item_marker() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -470,7 +487,7 @@ fn journal_truncation_repeating_item_marker() -> crate::Result<()> {
             key: (*b"zzz").into(),
             value: (*b"").into(),
             value_type: ValueType::Tombstone,
-            compression: lsm_tree::CompressionType::None,
+            compression: JournalCompression::None,
         }
         .encode_into(&mut file)?;
 
@@ -478,7 +495,7 @@ fn journal_truncation_repeating_item_marker() -> crate::Result<()> {
     }
 
     for _ in 0..10 {
-        let journal = Journal::from_file(&path)?;
+        let journal = Journal::from_file(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
         let reader = journal.get_reader()?;
         let collected = reader.flatten().collect::<Vec<_>>();
         assert_eq!(values.to_vec(), collected.first().unwrap().items);
@@ -486,3 +503,708 @@ fn journal_truncation_repeating_item_marker() -> crate::Result<()> {
 
     Ok(())
 }
+
+/// Encodes a single-item batch, optionally with a mangled checksum
+fn encode_batch(buf: &mut Vec<u8>, seqno: u64, key: &[u8], valid_checksum: bool) {
+    use std::hash::Hasher;
+
+    Entry::Start {
+        item_count: 1,
+        seqno,
+    }
+    .encode_into(buf)
+    .expect("should encode");
+
+    let item = Entry::Item {
+        keyspace_id: 1,
+        key: key.into(),
+        value: key.into(),
+        value_type: ValueType::Value,
+        compression: JournalCompression::None,
+    }
+    .encode_into_vec();
+    buf.extend_from_slice(&item);
+
+    let mut hasher = xxhash_rust::xxh3::Xxh3::default();
+    hasher.update(&item);
+    let checksum = hasher.finish();
+
+    Entry::End(if valid_checksum { checksum } else { !checksum })
+        .encode_into(buf)
+        .expect("should encode");
+}
+
+fn read_seqnos(
+    path: &std::path::Path,
+    mode: error::RecoveryMode,
+) -> crate::Result<Vec<lsm_tree::SeqNo>> {
+    let reader = JournalBatchReader::new(JournalReader::new(path)?).with_recovery_mode(mode);
+    reader.map(|batch| batch.map(|batch| batch.seqno)).collect()
+}
+
+#[test]
+fn journal_recovery_mode_unterminated_tail() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+    let valid_len = bytes.len() as u64;
+
+    Entry::Start {
+        item_count: 1,
+        seqno: 1,
+    }
+    .encode_into(&mut bytes)?;
+
+    std::fs::write(&path, &bytes)?;
+
+    assert!(matches!(
+        read_seqnos(&path, RecoveryMode::AbsoluteConsistency),
+        Err(crate::Error::JournalRecovery(
+            crate::JournalRecoveryError::MissingTerminator
+        )),
+    ));
+    assert_eq!(bytes.len() as u64, path.metadata()?.len());
+
+    assert_eq!(
+        vec![0],
+        read_seqnos(&path, RecoveryMode::TolerateCorruptTail)?
+    );
+    assert_eq!(valid_len, path.metadata()?.len());
+
+    assert_eq!(
+        vec![0],
+        read_seqnos(&path, RecoveryMode::AbsoluteConsistency)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn journal_recovery_mode_corrupt_bytes() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+    bytes.extend_from_slice(b"09pmu35w3a9mp53bao9upw3ab5up");
+    std::fs::write(&path, &bytes)?;
+
+    assert!(read_seqnos(&path, RecoveryMode::AbsoluteConsistency).is_err());
+    assert_eq!(bytes.len() as u64, path.metadata()?.len());
+
+    assert_eq!(
+        vec![0],
+        read_seqnos(&path, RecoveryMode::TolerateCorruptTail)?
+    );
+    assert_eq!(
+        vec![0],
+        read_seqnos(&path, RecoveryMode::AbsoluteConsistency)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn journal_recovery_mode_preallocated_space() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+    encode_batch(&mut bytes, 1, b"b", true);
+    let valid_len = bytes.len() as u64;
+    bytes.resize(bytes.len() + 1_024, 0);
+    std::fs::write(&path, &bytes)?;
+
+    assert_eq!(
+        vec![0, 1],
+        read_seqnos(&path, RecoveryMode::AbsoluteConsistency)?,
+    );
+    assert_eq!(valid_len, path.metadata()?.len());
+
+    Ok(())
+}
+
+#[test]
+fn journal_recovery_mode_checksum_mismatch() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+    encode_batch(&mut bytes, 1, b"b", false);
+    encode_batch(&mut bytes, 2, b"c", true);
+    std::fs::write(&path, &bytes)?;
+
+    for mode in [
+        RecoveryMode::TolerateCorruptTail,
+        RecoveryMode::AbsoluteConsistency,
+    ] {
+        assert!(matches!(
+            read_seqnos(&path, mode),
+            Err(crate::Error::JournalRecovery(
+                crate::JournalRecoveryError::ChecksumMismatch
+            )),
+        ));
+    }
+
+    assert_eq!(
+        vec![0, 2],
+        read_seqnos(&path, RecoveryMode::SkipInvalidBatches)?,
+    );
+    assert_eq!(bytes.len() as u64, path.metadata()?.len());
+
+    Ok(())
+}
+
+#[test]
+fn journal_recovery_mode_skip_stray_markers() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+
+    // Batch without terminator, followed by a new batch
+    Entry::Start {
+        item_count: 5,
+        seqno: 1,
+    }
+    .encode_into(&mut bytes)?;
+    encode_batch(&mut bytes, 2, b"b", true);
+
+    // End marker without start marker
+    Entry::End(5_432).encode_into(&mut bytes)?;
+    encode_batch(&mut bytes, 3, b"c", true);
+
+    std::fs::write(&path, &bytes)?;
+
+    assert!(matches!(
+        read_seqnos(&path, RecoveryMode::AbsoluteConsistency),
+        Err(crate::Error::JournalRecovery(
+            crate::JournalRecoveryError::MissingTerminator
+        )),
+    ));
+
+    assert_eq!(
+        vec![0, 2, 3],
+        read_seqnos(&path, RecoveryMode::SkipInvalidBatches)?,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn journal_inspect_invalid_batches() -> crate::Result<()> {
+    use crate::tools::journal::BatchStatus;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+    let first_len = bytes.len() as u64;
+
+    // Batch without terminator, followed by a new batch
+    Entry::Start {
+        item_count: 5,
+        seqno: 1,
+    }
+    .encode_into(&mut bytes)?;
+    encode_batch(&mut bytes, 2, b"b", true);
+
+    // End marker without start marker
+    Entry::End(5_432).encode_into(&mut bytes)?;
+    encode_batch(&mut bytes, 3, b"c", false);
+
+    // Batch cut off by undecodable bytes
+    Entry::Start {
+        item_count: 1,
+        seqno: 4,
+    }
+    .encode_into(&mut bytes)?;
+    bytes.extend_from_slice(&[255; 8]);
+
+    std::fs::write(&path, &bytes)?;
+
+    let dump = crate::tools::journal::inspect(&path)?;
+
+    assert_eq!(
+        vec![0, 1, 2, 3, 4],
+        dump.batches
+            .iter()
+            .map(|batch| batch.seqno)
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(BatchStatus::Valid, dump.batches[0].status);
+    assert_eq!(BatchStatus::MissingTerminator, dump.batches[1].status);
+    assert_eq!(5, dump.batches[1].item_count);
+    assert_eq!(BatchStatus::Valid, dump.batches[2].status);
+    assert!(matches!(
+        dump.batches[3].status,
+        BatchStatus::ChecksumMismatch { .. },
+    ));
+    assert_eq!(BatchStatus::MissingTerminator, dump.batches[4].status);
+
+    assert_eq!(first_len, dump.valid_len);
+    assert_eq!(Some(first_len), dump.first_corrupt_offset);
+    assert!(dump
+        .error
+        .is_some_and(|e| e.starts_with("undecodable entry")));
+
+    // Inspecting never truncates the journal
+    assert_eq!(bytes, std::fs::read(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_read_only_does_not_truncate() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+    Entry::Start {
+        item_count: 1,
+        seqno: 1,
+    }
+    .encode_into(&mut bytes)?;
+    std::fs::write(&path, &bytes)?;
+
+    let reader = JournalBatchReader::new(JournalReader::new_read_only(&path)?);
+    assert_eq!(1, reader.count());
+    assert_eq!(bytes.len() as u64, path.metadata()?.len());
+
+    Ok(())
+}
+
+#[test]
+fn journal_seqno_limit_truncates() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 0, b"a", true);
+    encode_batch(&mut bytes, 1, b"b", true);
+    let valid_len = bytes.len() as u64;
+    encode_batch(&mut bytes, 2, b"c", true);
+    encode_batch(&mut bytes, 3, b"d", true);
+    std::fs::write(&path, &bytes)?;
+
+    let reader = JournalBatchReader::new(JournalReader::new(&path)?).with_seqno_limit(Some(1));
+    let seqnos = reader
+        .map(|batch| batch.map(|batch| batch.seqno))
+        .collect::<crate::Result<Vec<_>>>()?;
+    assert_eq!(vec![0, 1], seqnos);
+    assert_eq!(valid_len, path.metadata()?.len());
+
+    assert_eq!(
+        vec![0, 1],
+        read_seqnos(&path, RecoveryMode::TolerateCorruptTail)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn journal_background_sync_bytes_per_sync() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let journal = Journal::create_new(dir.path().join("0.jnl"), DEFAULT_PRE_ALLOCATED_BYTES)?;
+    journal.set_bytes_per_sync(Some(100));
+
+    {
+        let mut writer = journal.get_writer();
+        writer.write_raw(0, b"a", &[0; 200], ValueType::Value, 0, None)?;
+    }
+
+    assert!(journal.wait_for_background_sync());
+
+    // Syncing resets the byte counter, so the next wait times out on the interval
+    journal.persist(PersistMode::SyncData)?;
+    journal.set_sync_interval(Some(std::time::Duration::from_millis(50)));
+
+    let start = std::time::Instant::now();
+    assert!(journal.wait_for_background_sync());
+    assert!(start.elapsed() >= std::time::Duration::from_millis(50));
+
+    Ok(())
+}
+
+#[test]
+fn journal_background_sync_stop() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let journal = std::sync::Arc::new(Journal::create_new(
+        dir.path().join("0.jnl"),
+        DEFAULT_PRE_ALLOCATED_BYTES,
+    )?);
+
+    let handle = std::thread::spawn({
+        let journal = journal.clone();
+        move || journal.wait_for_background_sync()
+    });
+
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    journal.stop_background_sync();
+
+    assert!(!handle.join().expect("thread should not panic"));
+
+    Ok(())
+}
+
+#[test]
+fn journal_recovery_stale_recycled_data() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    encode_batch(&mut bytes, 5, b"a", true);
+    encode_batch(&mut bytes, 6, b"b", true);
+    let valid_len = bytes.len() as u64;
+
+    // Stale batches of the previous journal
+    encode_batch(&mut bytes, 2, b"c", true);
+    encode_batch(&mut bytes, 3, b"d", true);
+    std::fs::write(&path, &bytes)?;
+
+    for mode in [
+        RecoveryMode::AbsoluteConsistency,
+        RecoveryMode::TolerateCorruptTail,
+        RecoveryMode::SkipInvalidBatches,
+    ] {
+        assert_eq!(vec![5, 6], read_seqnos(&path, mode)?);
+    }
+    assert_eq!(valid_len, path.metadata()?.len());
+
+    // Incomplete batch, followed by stale data
+    bytes.truncate(valid_len as usize);
+    Entry::Start {
+        item_count: 2,
+        seqno: 7,
+    }
+    .encode_into(&mut bytes)?;
+    encode_batch(&mut bytes, 3, b"d", true);
+    std::fs::write(&path, &bytes)?;
+
+    assert!(matches!(
+        read_seqnos(&path, RecoveryMode::AbsoluteConsistency),
+        Err(crate::Error::JournalRecovery(
+            crate::JournalRecoveryError::MissingTerminator
+        )),
+    ));
+
+    assert_eq!(
+        vec![5, 6],
+        read_seqnos(&path, RecoveryMode::TolerateCorruptTail)?
+    );
+    assert_eq!(valid_len, path.metadata()?.len());
+
+    Ok(())
+}
+
+#[test]
+fn journal_rotate_recycled() -> crate::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+    let recycled_path = dir.path().join("0.jnl.recycle");
+    let next_next_path = dir.path().join("2.jnl");
+
+    let journal = Journal::create_new(&path, 1_024)?;
+
+    {
+        let mut writer = journal.get_writer();
+
+        for seqno in 0..3 {
+            writer.write_raw(0, b"a", &[0; 100], ValueType::Value, seqno, None)?;
+        }
+        writer.rotate(None)?;
+    }
+
+    std::fs::rename(&path, &recycled_path)?;
+
+    {
+        let mut writer = journal.get_writer();
+        writer.rotate(Some(recycled_path.clone()))?;
+        writer.write_raw(0, b"b", b"b", ValueType::Value, 5, None)?;
+        writer.persist(PersistMode::Buffer)?;
+    }
+
+    assert!(!recycled_path.try_exists()?);
+    assert_eq!(journal.path(), next_next_path);
+
+    // Stale batches are never read, even before any new data is written
+    assert_eq!(
+        vec![5],
+        read_seqnos(&next_next_path, error::RecoveryMode::AbsoluteConsistency)?,
+    );
+
+    {
+        let mut writer = journal.get_writer();
+        writer.write_raw(0, b"c", b"c", ValueType::Value, 6, None)?;
+        writer.persist(PersistMode::Buffer)?;
+    }
+
+    assert_eq!(
+        vec![5, 6],
+        read_seqnos(&next_next_path, error::RecoveryMode::AbsoluteConsistency)?,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn journal_recovery_recycled_torn_batch() -> crate::Result<()> {
+    use error::RecoveryMode;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+    let recycled_path = dir.path().join("0.jnl.recycle");
+
+    let journal = Journal::create_new(&path, 1_024)?;
+
+    {
+        let mut writer = journal.get_writer();
+        writer.write_raw(0, b"a", b"a", ValueType::Value, 0, None)?;
+        writer.rotate(None)?;
+    }
+
+    std::fs::rename(&path, &recycled_path)?;
+
+    let valid_len = {
+        let mut writer = journal.get_writer();
+        writer.rotate(Some(recycled_path))?;
+        writer.write_raw(0, b"b", b"b", ValueType::Value, 5, None)?;
+        writer.persist(PersistMode::Buffer)?;
+        writer.pos()?
+    };
+
+    let path = journal.path();
+    drop(journal);
+
+    // A crash interrupted the next batch, which now runs into stale entries of the previous journal
+    let mut bytes = vec![];
+    entry::serialize_recycled_prefix(&mut bytes, 2)?;
+    Entry::Start {
+        item_count: 2,
+        seqno: 6,
+    }
+    .encode_into(&mut bytes)?;
+
+    for journal_id in [2, 1] {
+        entry::serialize_recycled_prefix(&mut bytes, journal_id)?;
+        Entry::Item {
+            keyspace_id: 0,
+            key: (*b"c").into(),
+            value: (*b"c").into(),
+            value_type: ValueType::Value,
+            compression: JournalCompression::None,
+        }
+        .encode_into(&mut bytes)?;
+    }
+
+    entry::serialize_recycled_prefix(&mut bytes, 1)?;
+    Entry::End(0).encode_into(&mut bytes)?;
+
+    {
+        use std::io::{Seek, SeekFrom};
+
+        let mut file = std::fs::OpenOptions::new().write(true).open(&path)?;
+        file.seek(SeekFrom::Start(valid_len))?;
+        file.write_all(&bytes)?;
+    }
+
+    assert!(matches!(
+        read_seqnos(&path, RecoveryMode::AbsoluteConsistency),
+        Err(crate::Error::JournalRecovery(
+            crate::JournalRecoveryError::MissingTerminator
+        )),
+    ));
+
+    assert_eq!(
+        vec![5],
+        read_seqnos(&path, RecoveryMode::TolerateCorruptTail)?
+    );
+    assert_eq!(valid_len, path.metadata()?.len());
+
+    Ok(())
+}
+
+#[test]
+fn journal_compact_items_roundtrip() -> crate::Result<()> {
+    let dir1 = tempdir()?;
+    let db = crate::Database::builder(&dir1).open()?;
+    let keyspace0 = db.keyspace("default", Default::default)?;
+    let keyspace1 = db.keyspace("default1", Default::default)?;
+
+    let dir2 = tempdir()?;
+    let path = dir2.path().join("0.jnl");
+
+    let items = [
+        BatchItem::new(keyspace0.clone(), *b"a", *b"a", ValueType::Value),
+        BatchItem::new(keyspace0.clone(), *b"b", *b"", ValueType::Tombstone),
+        BatchItem::new(keyspace1.clone(), *b"c", *b"c", ValueType::Value),
+        BatchItem::new(keyspace0.clone(), *b"d", *b"", ValueType::WeakTombstone),
+        BatchItem::new(keyspace0, *b"e", *b"e", ValueType::Value),
+    ];
+
+    let bytes_written = {
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?;
+        let mut writer = journal.get_writer();
+        writer.write_batch(items.iter(), items.len(), 0)?
+    };
+
+    let mut legacy_size = 0;
+    for item in &items {
+        let mut buf = vec![];
+        entry::serialize_marker_item_v1(
+            &mut buf,
+            item.keyspace.id,
+            &item.key,
+            &item.value,
+            item.value_type,
+            lsm_tree::CompressionType::None,
+        )?;
+        legacy_size += buf.len();
+    }
+    assert!(bytes_written < legacy_size);
+
+    let mut reader = JournalBatchReader::new(JournalReader::new(&path)?);
+    let batch = reader.next().expect("should have batch")?;
+    assert_eq!(0, batch.seqno);
+    assert_eq!(items.as_slice(), batch.items.as_slice());
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn journal_recovery_legacy_items() -> crate::Result<()> {
+    use std::hash::Hasher;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("0.jnl");
+
+    let mut bytes = vec![];
+    let mut hasher = xxhash_rust::xxh3::Xxh3::default();
+
+    Entry::Start {
+        item_count: 2,
+        seqno: 0,
+    }
+    .encode_into(&mut bytes)?;
+
+    for (keyspace_id, key) in [(1, b"a"), (2, b"b")] {
+        let mut item = vec![];
+        entry::serialize_marker_item_v1(
+            &mut item,
+            keyspace_id,
+            key,
+            key,
+            ValueType::Value,
+            lsm_tree::CompressionType::None,
+        )?;
+        hasher.update(&item);
+        bytes.extend_from_slice(&item);
+    }
+
+    Entry::End(hasher.finish()).encode_into(&mut bytes)?;
+
+    // Followed by a batch in the compact format
+    encode_batch(&mut bytes, 1, b"c", true);
+    std::fs::write(&path, &bytes)?;
+
+    let batches =
+        JournalBatchReader::new(JournalReader::new(&path)?).collect::<crate::Result<Vec<_>>>()?;
+    assert_eq!(2, batches.len());
+
+    let items = batches
+        .iter()
+        .flat_map(|batch| &batch.items)
+        .map(|item| (item.keyspace_id, &*item.key))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![(1, &b"a"[..]), (2, &b"b"[..]), (1, &b"c"[..])], items,);
+
+    Ok(())
+}
+
+#[test]
+fn journal_decode_rejects_indirection() -> crate::Result<()> {
+    let mut compact = vec![];
+    entry::serialize_marker_item(
+        &mut compact,
+        None,
+        0,
+        b"a",
+        b"a",
+        ValueType::Indirection,
+        JournalCompression::None,
+        None,
+    )?;
+
+    let mut legacy = vec![];
+    entry::serialize_marker_item_v1(
+        &mut legacy,
+        0,
+        b"a",
+        b"a",
+        ValueType::Indirection,
+        lsm_tree::CompressionType::None,
+    )?;
+
+    for bytes in [compact, legacy] {
+        assert!(matches!(
+            Entry::decode_from(&mut &bytes[..], None, None),
+            Err(crate::Error::InvalidTag(_)),
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn journal_recovery_zstd() -> crate::Result<()> {
+    let dir1 = tempdir()?;
+    let db = crate::Database::builder(&dir1).open()?;
+    let keyspace = db.keyspace("default", Default::default)?;
+
+    let dir2 = tempdir()?;
+    let path = dir2.path().join("0.jnl");
+
+    let value = b"abcdef".repeat(1_000);
+    let items = [
+        BatchItem::new(keyspace.clone(), *b"a", &*value, ValueType::Value),
+        BatchItem::new(keyspace, *b"b", *b"b", ValueType::Value),
+    ];
+
+    let bytes_written = {
+        let journal = Journal::create_new(&path, DEFAULT_PRE_ALLOCATED_BYTES)?
+            .with_compression(JournalCompression::Zstd(3), 1_024);
+        let mut writer = journal.get_writer();
+        writer.write_batch(items.iter(), items.len(), 0)?
+    };
+    assert!(bytes_written < value.len());
+
+    let mut reader = JournalBatchReader::new(JournalReader::new(&path)?);
+    let batch = reader.next().expect("should have batch")?;
+    assert_eq!(items.as_slice(), batch.items.as_slice());
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
@@ -2,21 +2,28 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use super::entry::{serialize_marker_item, Entry};
+use super::{
+    compression::JournalCompression,
+    entry::{decode_recycled_prefix, serialize_marker_item, serialize_recycled_prefix, Entry, Tag},
+};
 use crate::{
     batch::item::Item as BatchItem, file::fsync_directory, journal::recovery::JournalId,
-    keyspace::InternalKeyspaceId,
+    keyspace::InternalKeyspaceId, EncryptionProvider,
 };
-use lsm_tree::{CompressionType, SeqNo, ValueType};
+use lsm_tree::{SeqNo, ValueType};
 use std::{
     fs::{File, OpenOptions},
     hash::Hasher,
-    io::{BufWriter, Write},
+    io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
-// TODO: this should be a database configuration
-pub const PRE_ALLOCATED_BYTES: u64 = 64 * 1_024 * 1_024;
+pub const DEFAULT_PRE_ALLOCATED_BYTES: u64 = 64 * 1_024 * 1_024;
 
 pub const JOURNAL_BUFFER_BYTES: usize = 8 * 1_024;
 
@@ -26,8 +33,23 @@ pub struct Writer {
     buf: Vec<u8>,
     is_buffer_dirty: bool,
 
-    compression: CompressionType,
+    compression: JournalCompression,
     compression_threshold: usize,
+
+    /// Encrypts items, if set
+    pub(crate) encryption: Option<Arc<dyn EncryptionProvider>>,
+
+    /// Size new journal files are pre-allocated to
+    pre_allocated_bytes: u64,
+
+    /// Set if the journal file was recycled, so every entry is prefixed by the journal ID,
+    /// which tells entries apart from stale entries of the file's previous use
+    recycled_journal_id: Option<JournalId>,
+
+    /// `true` if the journal file may contain stale data behind the current write position
+    has_stale_tail: bool,
+
+    group_commit: Arc<GroupCommit>,
 }
 
 /// The persist mode allows setting the durability guarantee of previous writes
@@ -49,17 +71,322 @@ pub enum PersistMode {
     SyncAll,
 }
 
+/// Lets concurrent writers share a single fsync (group commit)
+///
+/// Every flush of the journal's IO buffers hands out a new ticket.
+/// Writers that need durability wait until their ticket is synced: the first waiter
+/// becomes the leader and syncs the journal file for every ticket handed out so far,
+/// while the other writers wait for the leader, instead of syncing themselves.
+///
+/// Syncing happens without holding the journal writer lock, so other writers
+/// can append to the journal in the meantime.
+///
+/// Also decides when the journal should be synced in the background.
+pub struct GroupCommit {
+    state: Mutex<GroupCommitState>,
+    cond: Condvar,
+
+    /// Wakes up the background syncer
+    syncer_cond: Condvar,
+
+    /// Interval of background syncs in milliseconds, 0 = disabled
+    sync_interval_ms: AtomicU64,
+
+    /// Amount of written bytes that triggers a background sync, 0 = disabled
+    bytes_per_sync: AtomicU64,
+}
+
+struct GroupCommitState {
+    /// Handle to the active journal file
+    file: Arc<File>,
+
+    /// Last ticket that was flushed to OS buffers
+    flushed: u64,
+
+    /// Last ticket that was synced using `fdatasync` (or `fsync`)
+    synced_data: u64,
+
+    /// Last ticket that was synced using `fsync`
+    synced_all: u64,
+
+    /// `true` while a leader is syncing
+    is_syncing: bool,
+
+    /// Set if a sync failed, in which case no future sync can be trusted
+    is_failed: bool,
+
+    /// Bytes written since the last sync, only counted if bytes-per-sync is enabled
+    unsynced_bytes: u64,
+
+    /// Set when the database is dropped, so the background syncer stops
+    is_syncer_stopped: bool,
+}
+
+impl GroupCommitState {
+    fn is_synced(&self, ticket: u64, mode: PersistMode) -> bool {
+        match mode {
+            PersistMode::Buffer => self.flushed >= ticket,
+            PersistMode::SyncData => self.synced_data >= ticket,
+            PersistMode::SyncAll => self.synced_all >= ticket,
+        }
+    }
+
+    fn mark_synced(&mut self, ticket: u64, mode: PersistMode) {
+        match mode {
+            PersistMode::Buffer => {}
+            PersistMode::SyncData => {
+                self.synced_data = self.synced_data.max(ticket);
+                self.unsynced_bytes = 0;
+            }
+            PersistMode::SyncAll => {
+                self.synced_data = self.synced_data.max(ticket);
+                self.synced_all = self.synced_all.max(ticket);
+                self.unsynced_bytes = 0;
+            }
+        }
+    }
+}
+
+impl GroupCommit {
+    fn new(file: Arc<File>) -> Self {
+        Self {
+            state: Mutex::new(GroupCommitState {
+                file,
+                flushed: 0,
+                synced_data: 0,
+                synced_all: 0,
+                is_syncing: false,
+                is_failed: false,
+                unsynced_bytes: 0,
+                is_syncer_stopped: false,
+            }),
+            cond: Condvar::new(),
+            syncer_cond: Condvar::new(),
+            sync_interval_ms: AtomicU64::new(0),
+            bytes_per_sync: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets the interval of background syncs, `None` disables them.
+    pub fn set_sync_interval(&self, interval: Option<Duration>) {
+        let ms = interval.map_or(0, |interval| {
+            u64::try_from(interval.as_millis())
+                .unwrap_or(u64::MAX)
+                .max(1)
+        });
+
+        self.sync_interval_ms.store(ms, Ordering::Release);
+        self.syncer_cond.notify_all();
+    }
+
+    /// Sets the amount of written bytes that triggers a background sync, `None` disables it.
+    pub fn set_bytes_per_sync(&self, bytes: Option<u64>) {
+        self.bytes_per_sync
+            .store(bytes.unwrap_or_default(), Ordering::Release);
+        self.syncer_cond.notify_all();
+    }
+
+    /// Counts written bytes, waking up the background syncer if needed.
+    fn record_write(&self, bytes: usize) {
+        let threshold = self.bytes_per_sync.load(Ordering::Acquire);
+
+        if threshold == 0 {
+            return;
+        }
+
+        let mut state = self.lock();
+        state.unsynced_bytes = state.unsynced_bytes.saturating_add(bytes as u64);
+
+        if state.unsynced_bytes >= threshold {
+            self.syncer_cond.notify_all();
+        }
+    }
+
+    /// Blocks until the journal should be synced in the background.
+    ///
+    /// Returns `false` if the background syncer should stop.
+    pub fn wait_for_background_sync(&self) -> bool {
+        let started_at = Instant::now();
+        let mut state = self.lock();
+
+        loop {
+            if state.is_syncer_stopped {
+                return false;
+            }
+
+            let bytes_per_sync = self.bytes_per_sync.load(Ordering::Acquire);
+
+            if bytes_per_sync > 0 && state.unsynced_bytes >= bytes_per_sync {
+                return true;
+            }
+
+            let interval_ms = self.sync_interval_ms.load(Ordering::Acquire);
+
+            #[expect(clippy::expect_used)]
+            if interval_ms == 0 {
+                state = self.syncer_cond.wait(state).expect("lock is poisoned");
+            } else {
+                let interval = Duration::from_millis(interval_ms);
+
+                let Some(remaining) = interval.checked_sub(started_at.elapsed()) else {
+                    return true;
+                };
+
+                state = self
+                    .syncer_cond
+                    .wait_timeout(state, remaining)
+                    .expect("lock is poisoned")
+                    .0;
+            }
+        }
+    }
+
+    /// Stops the background syncer.
+    pub fn stop_background_sync(&self) {
+        self.lock().is_syncer_stopped = true;
+        self.syncer_cond.notify_all();
+    }
+
+    #[expect(clippy::expect_used)]
+    fn lock(&self) -> std::sync::MutexGuard<'_, GroupCommitState> {
+        self.state.lock().expect("lock is poisoned")
+    }
+
+    /// Waits until the given ticket is persisted with the given mode,
+    /// possibly syncing the journal file as group leader.
+    fn wait(&self, ticket: u64, mode: PersistMode) -> std::io::Result<()> {
+        let mut state = self.lock();
+
+        loop {
+            if state.is_synced(ticket, mode) {
+                return Ok(());
+            }
+
+            if state.is_failed {
+                return Err(std::io::Error::other("a previous journal sync failed"));
+            }
+
+            if state.is_syncing {
+                #[expect(clippy::expect_used)]
+                {
+                    state = self.cond.wait(state).expect("lock is poisoned");
+                }
+                continue;
+            }
+
+            // NOTE: Become the leader, and sync every ticket that has been flushed so far
+            state.is_syncing = true;
+
+            let target = state.flushed;
+            let file = state.file.clone();
+
+            drop(state);
+
+            log::trace!("Syncing journal as group commit leader, ticket={target}, mode={mode:?}");
+
+            let result = match mode {
+                PersistMode::SyncAll => file.sync_all(),
+                PersistMode::SyncData => file.sync_data(),
+                PersistMode::Buffer => Ok(()),
+            };
+
+            state = self.lock();
+            state.is_syncing = false;
+
+            match &result {
+                Ok(()) => state.mark_synced(target, mode),
+                Err(e) => {
+                    log::error!("Failed to sync journal as group commit leader: {e:?}");
+                    state.is_failed = true;
+                }
+            }
+
+            self.cond.notify_all();
+
+            result?;
+        }
+    }
+}
+
+/// A flushed write that is waiting to be synced, see [`GroupCommit`]
+#[must_use]
+pub struct SyncTicket {
+    group_commit: Arc<GroupCommit>,
+    ticket: u64,
+    mode: PersistMode,
+}
+
+impl SyncTicket {
+    /// Waits until the write is persisted.
+    ///
+    /// Must not be called while holding the journal writer lock.
+    pub fn wait(self) -> std::io::Result<()> {
+        self.group_commit.wait(self.ticket, self.mode)
+    }
+}
+
 impl Writer {
-    pub fn set_compression(&mut self, comp: CompressionType, threshold: usize) {
+    fn new(path: PathBuf, file: File) -> crate::Result<Self> {
+        let sync_handle = Arc::new(file.try_clone()?);
+
+        Ok(Self {
+            path,
+            file: BufWriter::with_capacity(JOURNAL_BUFFER_BYTES, file),
+            buf: Vec::new(),
+            is_buffer_dirty: false,
+            compression: JournalCompression::None,
+            compression_threshold: 0,
+            encryption: None,
+            pre_allocated_bytes: DEFAULT_PRE_ALLOCATED_BYTES,
+            recycled_journal_id: None,
+            has_stale_tail: false,
+            group_commit: Arc::new(GroupCommit::new(sync_handle)),
+        })
+    }
+
+    pub(crate) fn group_commit(&self) -> &Arc<GroupCommit> {
+        &self.group_commit
+    }
+
+    pub fn set_encryption(&mut self, encryption: Option<Arc<dyn EncryptionProvider>>) {
+        self.encryption = encryption;
+    }
+
+    pub fn set_compression(&mut self, comp: JournalCompression, threshold: usize) {
         self.compression = comp;
         self.compression_threshold = threshold;
     }
 
+    /// Returns the compression to use for the given value.
+    ///
+    /// The keyspace's compression takes precedence over the journal's compression.
+    fn compression_for(
+        &self,
+        value: &[u8],
+        keyspace_compression: Option<JournalCompression>,
+    ) -> JournalCompression {
+        if self.compression_threshold > 0 && value.len() >= self.compression_threshold {
+            keyspace_compression.unwrap_or(self.compression)
+        } else {
+            JournalCompression::None
+        }
+    }
+
     pub fn len(&self) -> crate::Result<u64> {
         Ok(self.file.get_ref().metadata()?.len())
     }
 
-    pub fn rotate(&mut self) -> crate::Result<(PathBuf, PathBuf)> {
+    /// Returns the write position, which is the end of the last written batch.
+    ///
+    /// Unlike [`Writer::len`], this excludes pre-allocated space.
+    pub fn pos(&mut self) -> crate::Result<u64> {
+        Ok(self.file.stream_position()?)
+    }
+
+    /// Seals the active journal, and continues writing into a new journal.
+    ///
+    /// If given, the recycled journal file is reused instead of creating a new file.
+    pub fn rotate(&mut self, recycled: Option<PathBuf>) -> crate::Result<(PathBuf, PathBuf)> {
         self.persist(PersistMode::SyncAll)?;
 
         log::debug!(
@@ -108,8 +435,22 @@ impl Writer {
 
         let comp = self.compression;
         let compt = self.compression_threshold;
-        *self = Self::create_new(new_path.clone())?;
+        let encryption = self.encryption.clone();
+        let pre_allocated_bytes = self.pre_allocated_bytes;
+        let group_commit = self.group_commit.clone();
+
+        *self = match recycled {
+            Some(recycled) => Self::from_recycled(&recycled, new_path.clone(), journal_id + 1)?,
+            None => Self::create_new(new_path.clone(), pre_allocated_bytes)?,
+        };
+        self.pre_allocated_bytes = pre_allocated_bytes;
         self.set_compression(comp, compt);
+        self.set_encryption(encryption);
+
+        // NOTE: Keep the group commit state, the sealed journal is synced
+        // already, so waiters can only be waiting for the new journal
+        group_commit.lock().file = Arc::new(self.file.get_ref().try_clone()?);
+        self.group_commit = group_commit;
 
         // IMPORTANT: fsync folder on Unix
         fsync_directory(&folder)?;
@@ -117,16 +458,16 @@ impl Writer {
         Ok((prev_path, new_path))
     }
 
-    pub fn create_new<P: Into<PathBuf>>(path: P) -> crate::Result<Self> {
+    pub fn create_new<P: Into<PathBuf>>(path: P, pre_allocated_bytes: u64) -> crate::Result<Self> {
         let path = path.into();
 
         let file = File::create_new(&path).inspect_err(|e| {
             log::error!("Failed to create journal file at {}: {e:?}", path.display());
         })?;
 
-        file.set_len(PRE_ALLOCATED_BYTES).inspect_err(|e| {
+        file.set_len(pre_allocated_bytes).inspect_err(|e| {
             log::error!(
-                "Failed to set journal file size to {PRE_ALLOCATED_BYTES}B at {}: {e:?}",
+                "Failed to set journal file size to {pre_allocated_bytes}B at {}: {e:?}",
                 path.display(),
             );
         })?;
@@ -135,17 +476,71 @@ impl Writer {
             log::error!("Failed to fsync journal file at {}: {e:?}", path.display());
         })?;
 
-        Ok(Self {
-            path,
-            file: BufWriter::new(file),
-            buf: Vec::new(),
-            is_buffer_dirty: false,
-            compression: CompressionType::None,
-            compression_threshold: 0,
-        })
+        let mut writer = Self::new(path, file)?;
+        writer.pre_allocated_bytes = pre_allocated_bytes;
+        Ok(writer)
     }
 
-    pub fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+    /// Reuses an evicted journal file as a new journal.
+    ///
+    /// The old contents are not cleared, instead the start of the file is zeroed before
+    /// it is renamed, so its stale batches can never be recovered as part of the new journal.
+    /// From then on, every flush is followed by a zero byte that marks the end of the journal,
+    /// and every entry is prefixed by the journal ID, in case a crash interrupts a write.
+    fn from_recycled(
+        recycled_path: &Path,
+        path: PathBuf,
+        journal_id: JournalId,
+    ) -> crate::Result<Self> {
+        log::debug!(
+            "Recycling journal file {} as {}",
+            recycled_path.display(),
+            path.display(),
+        );
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(recycled_path)
+            .inspect_err(|e| {
+                log::error!(
+                    "Failed to open recycled journal file at {}: {e:?}",
+                    recycled_path.display(),
+                );
+            })?;
+
+        // IMPORTANT: Mark the journal as empty *before* it gets its new name
+        file.write_all(&[0])?;
+        file.sync_data()?;
+        file.rewind()?;
+
+        std::fs::rename(recycled_path, &path).inspect_err(|e| {
+            log::error!(
+                "Failed to rename recycled journal file {} to {}: {e:?}",
+                recycled_path.display(),
+                path.display(),
+            );
+        })?;
+
+        let mut writer = Self::new(path, file)?;
+        writer.recycled_journal_id = Some(journal_id);
+        writer.has_stale_tail = true;
+        Ok(writer)
+    }
+
+    /// Opens a journal file without write access.
+    ///
+    /// Any attempt to write into the journal fails.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+
+        let file = File::open(path).inspect_err(|e| {
+            log::error!("Failed to open journal file at {}: {e:?}", path.display());
+        })?;
+
+        Self::new(path.into(), file)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P, pre_allocated_bytes: u64) -> crate::Result<Self> {
         let path = path.as_ref();
 
         if !path.try_exists()? {
@@ -157,9 +552,9 @@ impl Writer {
                     log::error!("Failed to create journal file at {}: {e:?}", path.display());
                 })?;
 
-            file.set_len(PRE_ALLOCATED_BYTES).inspect_err(|e| {
+            file.set_len(pre_allocated_bytes).inspect_err(|e| {
                 log::error!(
-                    "Failed to set journal file size to {PRE_ALLOCATED_BYTES}B at {}: {e:?}",
+                    "Failed to set journal file size to {pre_allocated_bytes}B at {}: {e:?}",
                     path.display(),
                 );
             })?;
@@ -168,16 +563,24 @@ impl Writer {
                 log::error!("Failed to fsync journal file at {}: {e:?}", path.display());
             })?;
 
-            return Ok(Self {
-                path: path.into(),
-                file: BufWriter::with_capacity(JOURNAL_BUFFER_BYTES, file),
-                buf: Vec::new(),
-                is_buffer_dirty: false,
-                compression: CompressionType::None,
-                compression_threshold: 0,
-            });
+            let mut writer = Self::new(path.into(), file)?;
+            writer.pre_allocated_bytes = pre_allocated_bytes;
+            return Ok(writer);
         }
 
+        // NOTE: Continue prefixing entries if the journal was recycled
+        //
+        // Its stale tail is truncated when the journal is recovered, before anything is written
+        let recycled_journal_id = {
+            let mut reader = BufReader::new(File::open(path)?);
+
+            if reader.fill_buf()?.first() == Some(&u8::from(Tag::Recycled)) {
+                Some(decode_recycled_prefix(&mut reader)?)
+            } else {
+                None
+            }
+        };
+
         let file = OpenOptions::new()
             .append(true)
             .open(path)
@@ -185,14 +588,10 @@ impl Writer {
                 log::error!("Failed to open journal file at {}: {e:?}", path.display());
             })?;
 
-        Ok(Self {
-            path: path.into(),
-            file: BufWriter::with_capacity(JOURNAL_BUFFER_BYTES, file),
-            buf: Vec::new(),
-            is_buffer_dirty: false,
-            compression: CompressionType::None,
-            compression_threshold: 0,
-        })
+        let mut writer = Self::new(path.into(), file)?;
+        writer.pre_allocated_bytes = pre_allocated_bytes;
+        writer.recycled_journal_id = recycled_journal_id;
+        Ok(writer)
     }
 
     /// Persists the journal file.
@@ -203,13 +602,25 @@ impl Writer {
         );
 
         if self.is_buffer_dirty {
+            // NOTE: A recycled journal contains stale data after the write position,
+            // so terminate the journal, and overwrite the terminator with the next write
+            if self.has_stale_tail {
+                self.file.write_all(&[0])?;
+            }
+
             self.file.flush().inspect_err(|e| {
                 log::error!(
                     "Failed to flush journal IO buffers at {}: {e:?}",
                     self.path.display(),
                 );
             })?;
+
+            if self.has_stale_tail {
+                self.file.seek(SeekFrom::Current(-1))?;
+            }
+
             self.is_buffer_dirty = false;
+            self.group_commit.lock().flushed += 1;
         }
 
         match mode {
@@ -218,133 +629,231 @@ impl Writer {
                     "Failed to fsync journal file at {}: {e:?}",
                     self.path.display(),
                 );
-            }),
+            })?,
             PersistMode::SyncData => self.file.get_mut().sync_data().inspect_err(|e| {
                 log::error!(
                     "Failed to fsyncdata journal file at {}: {e:?}",
                     self.path.display(),
                 );
-            }),
-            PersistMode::Buffer => Ok(()),
+            })?,
+            PersistMode::Buffer => return Ok(()),
         }
-    }
-
-    /// Writes a batch start marker to the journal
-    fn write_start(&mut self, item_count: u32, seqno: SeqNo) -> Result<usize, crate::Error> {
-        debug_assert!(self.buf.is_empty());
 
-        Entry::Start { item_count, seqno }.encode_into(&mut self.buf)?;
+        let mut group_commit = self.group_commit.lock();
+        let ticket = group_commit.flushed;
+        group_commit.mark_synced(ticket, mode);
 
-        self.file.write_all(&self.buf)?;
-
-        Ok(self.buf.len())
+        Ok(())
     }
 
-    /// Writes a batch end marker to the journal
-    fn write_end(&mut self, checksum: u64) -> Result<usize, crate::Error> {
-        debug_assert!(self.buf.is_empty());
+    /// Flushes the journal's IO buffers, and returns a ticket to wait for the sync
+    /// to complete, after the journal writer lock has been released.
+    ///
+    /// Concurrent writers waiting for their tickets share a single sync, see [`GroupCommit`].
+    pub(crate) fn persist_deferred(&mut self, mode: PersistMode) -> std::io::Result<SyncTicket> {
+        self.persist(PersistMode::Buffer)?;
+
+        Ok(SyncTicket {
+            group_commit: self.group_commit.clone(),
+            ticket: self.group_commit.lock().flushed,
+            mode,
+        })
+    }
 
-        Entry::End(checksum).encode_into(&mut self.buf)?;
+    /// Prefixes the next entry by the journal ID, if the journal was recycled
+    fn encode_entry_prefix(&mut self) -> std::io::Result<()> {
+        if let Some(journal_id) = self.recycled_journal_id {
+            serialize_recycled_prefix(&mut self.buf, journal_id)?;
+        }
+        Ok(())
+    }
 
-        self.file.write_all(&self.buf)?;
+    /// Encodes a batch start marker into the buffer
+    fn encode_start(&mut self, item_count: u32, seqno: SeqNo) -> crate::Result<()> {
+        self.encode_entry_prefix()?;
+        Entry::Start { item_count, seqno }.encode_into(&mut self.buf)
+    }
 
-        Ok(self.buf.len())
+    /// Encodes a batch end marker into the buffer
+    fn encode_end(&mut self, checksum: u64) -> crate::Result<()> {
+        self.encode_entry_prefix()?;
+        Entry::End(checksum).encode_into(&mut self.buf)
     }
 
-    pub(crate) fn write_raw(
+    /// Encodes an item into the buffer, and adds it to the batch checksum
+    #[expect(clippy::too_many_arguments)]
+    fn encode_item(
         &mut self,
+        hasher: &mut xxhash_rust::xxh3::Xxh3,
+        prev_keyspace_id: Option<InternalKeyspaceId>,
         keyspace_id: InternalKeyspaceId,
         key: &[u8],
         value: &[u8],
         value_type: ValueType,
-        seqno: u64,
-    ) -> crate::Result<usize> {
-        self.is_buffer_dirty = true;
-
-        let mut hasher = xxhash_rust::xxh3::Xxh3::default();
-        let mut byte_count = 0;
-
-        self.buf.clear();
-        byte_count += self.write_start(1, seqno)?;
-        self.buf.clear();
+        compression: JournalCompression,
+    ) -> crate::Result<()> {
+        let offset = self.buf.len();
 
+        self.encode_entry_prefix()?;
         serialize_marker_item(
             &mut self.buf,
+            prev_keyspace_id,
             keyspace_id,
             key,
             value,
             value_type,
-            if self.compression_threshold > 0 && value.len() >= self.compression_threshold {
-                self.compression
-            } else {
-                CompressionType::None
-            },
+            compression,
+            self.encryption.as_deref(),
         )?;
 
-        self.file.write_all(&self.buf)?;
+        hasher.update(self.buf.get(offset..).unwrap_or_default());
 
-        hasher.update(&self.buf);
-        byte_count += self.buf.len();
+        Ok(())
+    }
+
+    /// Writes the encoded batch to the journal file.
+    ///
+    /// If this fails, part of the batch may have been written, so the journal
+    /// can not be written to anymore.
+    pub(crate) fn write_encoded(&mut self) -> std::io::Result<usize> {
+        self.is_buffer_dirty = true;
 
+        let result = self.file.write_all(&self.buf);
+        let byte_count = self.buf.len();
+
+        // NOTE: Do not keep the memory of large batches around
         self.buf.clear();
-        let checksum = hasher.finish();
-        byte_count += self.write_end(checksum)?;
+        self.buf.shrink_to(JOURNAL_BUFFER_BYTES);
+
+        result?;
+
+        self.group_commit.record_write(byte_count);
 
         Ok(byte_count)
     }
 
-    pub fn write_batch<'a>(
+    /// Writes an empty batch, which marks the seqno the journal starts at.
+    ///
+    /// Every batch in this journal has a seqno at or above the marker,
+    /// which stays known, even after older journals are deleted.
+    pub(crate) fn write_start_marker(&mut self, seqno: SeqNo) -> crate::Result<()> {
+        self.buf.clear();
+
+        self.encode_start(0, seqno)?;
+
+        let checksum = xxhash_rust::xxh3::Xxh3::default().finish();
+        self.encode_end(checksum)?;
+
+        self.write_encoded()?;
+
+        Ok(())
+    }
+
+    /// Encodes a batch of a single item, to be written using [`Writer::write_encoded`].
+    ///
+    /// Nothing is written to the journal file, so if this fails (e.g. because the value could
+    /// not be compressed or encrypted), the journal is left untouched.
+    pub(crate) fn encode_raw(
+        &mut self,
+        keyspace_id: InternalKeyspaceId,
+        key: &[u8],
+        value: &[u8],
+        value_type: ValueType,
+        seqno: u64,
+        compression: Option<JournalCompression>,
+    ) -> crate::Result<()> {
+        self.buf.clear();
+
+        let mut hasher = xxhash_rust::xxh3::Xxh3::default();
+
+        self.encode_start(1, seqno)?;
+
+        let compression = self.compression_for(value, compression);
+        self.encode_item(
+            &mut hasher,
+            None,
+            keyspace_id,
+            key,
+            value,
+            value_type,
+            compression,
+        )?;
+
+        self.encode_end(hasher.finish())
+    }
+
+    /// Encodes a batch, to be written using [`Writer::write_encoded`].
+    ///
+    /// Nothing is written to the journal file, so if this fails (e.g. because a value could
+    /// not be compressed or encrypted), the journal is left untouched.
+    pub(crate) fn encode_batch<'a>(
         &mut self,
         items: impl Iterator<Item = &'a BatchItem>,
         batch_size: usize,
         seqno: SeqNo,
-    ) -> crate::Result<usize> {
+    ) -> crate::Result<()> {
+        self.buf.clear();
+
         if batch_size == 0 {
-            return Ok(0);
+            return Ok(());
         }
 
-        self.is_buffer_dirty = true;
-
-        self.buf.clear();
-
         // NOTE: entries.len() is surely never > u32::MAX
         #[expect(clippy::cast_possible_truncation)]
         let item_count = batch_size as u32;
 
         let mut hasher = xxhash_rust::xxh3::Xxh3::default();
-        let mut byte_count = 0;
 
-        byte_count += self.write_start(item_count, seqno)?;
-        self.buf.clear();
+        self.encode_start(item_count, seqno)?;
+
+        let mut prev_keyspace_id = None;
 
         for item in items {
-            debug_assert!(self.buf.is_empty());
+            let compression =
+                self.compression_for(&item.value, item.keyspace.config.journal_compression);
 
-            serialize_marker_item(
-                &mut self.buf,
+            self.encode_item(
+                &mut hasher,
+                prev_keyspace_id,
                 item.keyspace.id,
                 &item.key,
                 &item.value,
                 item.value_type,
-                if self.compression_threshold > 0 && item.value.len() >= self.compression_threshold
-                {
-                    self.compression
-                } else {
-                    CompressionType::None
-                },
+                compression,
             )?;
 
-            self.file.write_all(&self.buf)?;
+            prev_keyspace_id = Some(item.keyspace.id);
+        }
 
-            hasher.update(&self.buf);
-            byte_count += self.buf.len();
+        self.encode_end(hasher.finish())
+    }
 
-            self.buf.clear();
-        }
+    #[cfg(test)]
+    pub(crate) fn write_raw(
+        &mut self,
+        keyspace_id: InternalKeyspaceId,
+        key: &[u8],
+        value: &[u8],
+        value_type: ValueType,
+        seqno: u64,
+        compression: Option<JournalCompression>,
+    ) -> crate::Result<usize> {
+        self.encode_raw(keyspace_id, key, value, value_type, seqno, compression)?;
+        Ok(self.write_encoded()?)
+    }
 
-        let checksum = hasher.finish();
-        byte_count += self.write_end(checksum)?;
+    #[cfg(test)]
+    pub fn write_batch<'a>(
+        &mut self,
+        items: impl Iterator<Item = &'a BatchItem>,
+        batch_size: usize,
+        seqno: SeqNo,
+    ) -> crate::Result<usize> {
+        if batch_size == 0 {
+            return Ok(0);
+        }
 
-        Ok(byte_count)
+        self.encode_batch(items, batch_size, seqno)?;
+        Ok(self.write_encoded()?)
     }
 }
@@ -8,6 +8,7 @@ pub mod options;
 mod write_delay;
 
 use crate::{
+    change_feed::{ChangeBatch, ChangeItem},
     db::Keyspaces,
     db_config::Config as DatabaseConfig,
     file::{KEYSPACES_FOLDER, LSM_CURRENT_VERSION_MARKER},
@@ -15,10 +16,12 @@ use crate::{
     ingestion::Ingestion,
     journal::{manager::EvictionWatermark, Journal},
     locked_file::LockedFileGuard,
+    publish_queue::PublishSlot,
+    read_only::ReadOnlyView,
     stats::Stats,
     supervisor::Supervisor,
     worker_pool::WorkerMessage,
-    Database, Guard, Iter,
+    Database, Guard, Iter, Snapshot,
 };
 use lsm_tree::{AbstractTree, AnyTree, KvPair, SeqNo, UserKey, UserValue};
 use options::CreateOptions;
@@ -98,6 +101,10 @@ pub struct KeyspaceInner {
 
     #[expect(unused)]
     lock_file: LockedFileGuard,
+
+    /// Keeps the tree folder of a read-only database alive
+    #[expect(unused)]
+    read_only_view: Option<Arc<ReadOnlyView>>,
 }
 
 impl Drop for KeyspaceInner {
@@ -213,6 +220,10 @@ impl Keyspace {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn clear(&self) -> crate::Result<()> {
+        if self.db_config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         let _journal_lock = self.journal.get_writer();
         self.tree.clear()?;
         Ok(())
@@ -239,9 +250,55 @@ impl Keyspace {
     ///
     /// Panics if the input iterator is not sorted in ascending order.
     pub fn start_ingestion(&self) -> crate::Result<Ingestion<'_>> {
+        if self.db_config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         Ingestion::new(self)
     }
 
+    /// Writes every item of the keyspace, as seen by the snapshot, into a portable export.
+    ///
+    /// The export also contains the keyspace's name and options,
+    /// and can be loaded into another database using [`Database::import`].
+    ///
+    /// Returns the number of exported items.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn export<W: std::io::Write>(&self, snapshot: &Snapshot, writer: W) -> crate::Result<u64> {
+        crate::export::export(self, snapshot, writer)
+    }
+
+    /// Makes a single write visible to readers, and emits it to change feed subscribers.
+    ///
+    /// Writes are published in seqno order, so this may wait for preceding writes.
+    /// Needs to be called after releasing the journal writer lock.
+    fn publish_write(
+        &self,
+        publish_slot: PublishSlot<'_>,
+        seqno: SeqNo,
+        change: Option<(UserKey, UserValue)>,
+        value_type: lsm_tree::ValueType,
+    ) {
+        publish_slot.publish(|| {
+            self.supervisor.snapshot_tracker.publish(seqno);
+
+            if let Some((key, value)) = change {
+                self.supervisor.change_feed.publish(ChangeBatch {
+                    seqno,
+                    items: vec![ChangeItem {
+                        keyspace: self.name.clone(),
+                        key,
+                        value,
+                        change_type: value_type.into(),
+                    }],
+                });
+            }
+        });
+    }
+
     pub(crate) fn from_database(
         keyspace_id: InternalKeyspaceId,
         db: &Database,
@@ -263,6 +320,7 @@ impl Keyspace {
             config,
             stats: db.stats.clone(),
             lock_file: db.lock_file.clone(),
+            read_only_view: db.read_only_view.clone(),
         }))
     }
 
@@ -308,6 +366,7 @@ impl Keyspace {
             is_poisoned: db.is_poisoned.clone(),
             stats: db.stats.clone(),
             lock_file: db.lock_file.clone(),
+            read_only_view: db.read_only_view.clone(),
         })))
     }
 
@@ -679,6 +738,10 @@ impl Keyspace {
     /// Returns `true` if the memtable was indeed rotated.
     #[doc(hidden)]
     pub fn rotate_memtable(&self) -> crate::Result<bool> {
+        if self.db_config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         log::debug!("Rotating memtable {:?}", self.name);
 
         log::trace!("acquiring journal lock");
@@ -690,35 +753,40 @@ impl Keyspace {
             return Ok(false);
         };
 
-        log::trace!("acquiring journal manager lock");
-
-        #[expect(clippy::expect_used)]
-        let mut journal_manager = self
-            .supervisor
-            .journal_manager
-            .write()
-            .expect("lock is poisoned");
-
-        let seqno_map = {
-            let keyspaces = self.keyspaces.write().expect("lock is poisoned");
-
-            let mut seqnos = Vec::with_capacity(keyspaces.len());
-
-            for keyspace in keyspaces.values() {
-                if let Some(lsn) = keyspace.tree.get_highest_memtable_seqno() {
-                    seqnos.push(EvictionWatermark {
-                        lsn,
-                        keyspace: keyspace.clone(),
-                    });
+        // NOTE: The journal contains no data of a keyspace without journaling,
+        // so there is no need to rotate it
+        if self.config.journaling {
+            log::trace!("acquiring journal manager lock");
+
+            #[expect(clippy::expect_used)]
+            let mut journal_manager = self
+                .supervisor
+                .journal_manager
+                .write()
+                .expect("lock is poisoned");
+
+            let seqno_map = {
+                let keyspaces = self.keyspaces.write().expect("lock is poisoned");
+
+                let mut seqnos = Vec::with_capacity(keyspaces.len());
+
+                // NOTE: Keyspaces without journaling never hold back journal eviction
+                for keyspace in keyspaces.values().filter(|x| x.config.journaling) {
+                    if let Some(lsn) = keyspace.tree.get_highest_memtable_seqno() {
+                        seqnos.push(EvictionWatermark {
+                            lsn,
+                            keyspace: keyspace.clone(),
+                        });
+                    }
                 }
-            }
 
-            seqnos
-        };
+                seqnos
+            };
 
-        journal_manager.rotate_journal(&mut journal, seqno_map)?;
+            // NOTE: We hold the journal lock, so no write can get a lower seqno anymore
+            journal_manager.rotate_journal(&mut journal, seqno_map, self.supervisor.seqno.get())?;
+        }
 
-        drop(journal_manager);
         drop(journal);
 
         self.supervisor.flush_manager.enqueue(Arc::new(FlushTask {
@@ -866,6 +934,10 @@ impl Keyspace {
     /// Will return `Err` if an IO error occurs.
     #[doc(hidden)]
     pub fn major_compact(&self) -> crate::Result<()> {
+        if self.db_config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         self.tree.major_compact(
             64_000_000,
             self.supervisor.snapshot_tracker.get_seqno_safe_to_gc(),
@@ -913,6 +985,10 @@ impl Keyspace {
             return Err(crate::Error::KeyspaceDeleted);
         }
 
+        if self.db_config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         let key = key.into();
         let value = value.into();
 
@@ -925,24 +1001,50 @@ impl Keyspace {
 
         let seqno = self.supervisor.seqno.next();
 
-        journal_writer.write_raw(self.id, &key, &value, lsm_tree::ValueType::Value, seqno)?;
+        // NOTE: Keyspaces without journaling write straight into the memtable
+        if self.config.journaling {
+            journal_writer.encode_raw(
+                self.id,
+                &key,
+                &value,
+                lsm_tree::ValueType::Value,
+                seqno,
+                self.config.journal_compression,
+            )?;
+
+            journal_writer.write_encoded().map_err(|e| {
+                log::error!(
+                    "write failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                );
+                self.is_poisoned.store(true, Ordering::Relaxed);
+                e
+            })?;
 
-        if !self.config.manual_journal_persist {
-            journal_writer
-                .persist(crate::PersistMode::Buffer)
-                .map_err(|e| {
-                    log::error!("persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}");
-                    self.is_poisoned.store(true, Ordering::Relaxed);
-                    e
-                })?;
+            if !self.config.manual_journal_persist {
+                journal_writer
+                    .persist(crate::PersistMode::Buffer)
+                    .map_err(|e| {
+                        log::error!("persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}");
+                        self.is_poisoned.store(true, Ordering::Relaxed);
+                        e
+                    })?;
+            }
         }
 
+        let change = self
+            .supervisor
+            .change_feed
+            .has_subscribers()
+            .then(|| (key.clone(), value.clone()));
+
         let (item_size, memtable_size) = self.tree.insert(key, value, seqno);
 
-        self.supervisor.snapshot_tracker.publish(seqno);
+        let publish_slot = self.supervisor.publish_queue.reserve();
 
         drop(journal_writer);
 
+        self.publish_write(publish_slot, seqno, change, lsm_tree::ValueType::Value);
+
         self.supervisor.write_buffer_size.allocate(item_size);
         self.maintenance(memtable_size)?;
 
@@ -985,6 +1087,10 @@ impl Keyspace {
             return Err(crate::Error::KeyspaceDeleted);
         }
 
+        if self.db_config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         let key = key.into();
 
         let mut journal_writer = self.journal.get_writer();
@@ -996,24 +1102,50 @@ impl Keyspace {
 
         let seqno = self.supervisor.seqno.next();
 
-        journal_writer.write_raw(self.id, &key, &[], lsm_tree::ValueType::Tombstone, seqno)?;
+        // NOTE: Keyspaces without journaling write straight into the memtable
+        if self.config.journaling {
+            journal_writer.encode_raw(
+                self.id,
+                &key,
+                &[],
+                lsm_tree::ValueType::Tombstone,
+                seqno,
+                self.config.journal_compression,
+            )?;
+
+            journal_writer.write_encoded().map_err(|e| {
+                log::error!(
+                    "write failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                );
+                self.is_poisoned.store(true, Ordering::Relaxed);
+                e
+            })?;
 
-        if !self.config.manual_journal_persist {
-            journal_writer
-                .persist(crate::PersistMode::Buffer)
-                .map_err(|e| {
-                    log::error!("persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}");
-                    self.is_poisoned.store(true, Ordering::Relaxed);
-                    e
-                })?;
+            if !self.config.manual_journal_persist {
+                journal_writer
+                    .persist(crate::PersistMode::Buffer)
+                    .map_err(|e| {
+                        log::error!("persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}");
+                        self.is_poisoned.store(true, Ordering::Relaxed);
+                        e
+                    })?;
+            }
         }
 
+        let change = self
+            .supervisor
+            .change_feed
+            .has_subscribers()
+            .then(|| (key.clone(), UserValue::empty()));
+
         let (item_size, memtable_size) = self.tree.remove(key, seqno);
 
-        self.supervisor.snapshot_tracker.publish(seqno);
+        let publish_slot = self.supervisor.publish_queue.reserve();
 
         drop(journal_writer);
 
+        self.publish_write(publish_slot, seqno, change, lsm_tree::ValueType::Tombstone);
+
         self.supervisor.write_buffer_size.allocate(item_size);
         self.maintenance(memtable_size)?;
 
@@ -1068,6 +1200,10 @@ impl Keyspace {
             return Err(crate::Error::KeyspaceDeleted);
         }
 
+        if self.db_config.read_only {
+            return Err(crate::Error::ReadOnly);
+        }
+
         let key = key.into();
 
         let mut journal_writer = self.journal.get_writer();
@@ -1079,32 +1215,57 @@ impl Keyspace {
 
         let seqno = self.supervisor.seqno.next();
 
-        journal_writer.write_raw(
-            self.id,
-            &key,
-            &[],
-            lsm_tree::ValueType::WeakTombstone,
-            seqno,
-        )?;
+        // NOTE: Keyspaces without journaling write straight into the memtable
+        if self.config.journaling {
+            journal_writer.encode_raw(
+                self.id,
+                &key,
+                &[],
+                lsm_tree::ValueType::WeakTombstone,
+                seqno,
+                self.config.journal_compression,
+            )?;
+
+            journal_writer.write_encoded().map_err(|e| {
+                log::error!(
+                    "write failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                );
+                self.is_poisoned.store(true, Ordering::Relaxed);
+                e
+            })?;
 
-        if !self.config.manual_journal_persist {
-            journal_writer
-                .persist(crate::PersistMode::Buffer)
-                .map_err(|e| {
-                    log::error!(
-                        "persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
-                    );
-                    self.is_poisoned.store(true, Ordering::Relaxed);
-                    e
-                })?;
+            if !self.config.manual_journal_persist {
+                journal_writer
+                    .persist(crate::PersistMode::Buffer)
+                    .map_err(|e| {
+                        log::error!(
+                            "persist failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                        );
+                        self.is_poisoned.store(true, Ordering::Relaxed);
+                        e
+                    })?;
+            }
         }
 
-        let (item_size, memtable_size) = self.tree.remove(key, seqno);
+        let change = self
+            .supervisor
+            .change_feed
+            .has_subscribers()
+            .then(|| (key.clone(), UserValue::empty()));
+
+        let (item_size, memtable_size) = self.tree.remove_weak(key, seqno);
 
-        self.supervisor.snapshot_tracker.publish(seqno);
+        let publish_slot = self.supervisor.publish_queue.reserve();
 
         drop(journal_writer);
 
+        self.publish_write(
+            publish_slot,
+            seqno,
+            change,
+            lsm_tree::ValueType::WeakTombstone,
+        );
+
         self.supervisor.write_buffer_size.allocate(item_size);
         self.maintenance(memtable_size)?;
 
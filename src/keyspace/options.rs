@@ -10,9 +10,10 @@ use crate::{
     },
     keyspace::{config::DecodeConfig, InternalKeyspaceId},
     meta_keyspace::{encode_config_key, MetaKeyspace},
+    JournalCompression,
 };
 use byteorder::ReadBytesExt;
-use lsm_tree::{CompressionType, KvPair, KvSeparationOptions};
+use lsm_tree::{CompressionType, KvPair, KvSeparationOptions, UserValue};
 use std::sync::Arc;
 
 /// Options to configure a keyspace
@@ -69,6 +70,12 @@ pub struct CreateOptions {
 
     pub(crate) manual_journal_persist: bool,
 
+    /// If `false`, writes skip the journal
+    pub(crate) journaling: bool,
+
+    /// Overrides the database's journal compression
+    pub(crate) journal_compression: Option<JournalCompression>,
+
     #[doc(hidden)]
     pub compaction_strategy: Arc<dyn lsm_tree::compaction::CompactionStrategy + Send + Sync>,
 
@@ -83,6 +90,10 @@ impl Default for CreateOptions {
         Self {
             manual_journal_persist: false,
 
+            journaling: true,
+
+            journal_compression: None,
+
             max_memtable_size: /* 64 MiB */ 64 * 1_024 * 1_024,
 
             data_block_hash_ratio_policy: HashRatioPolicy::all(0.0),
@@ -124,6 +135,16 @@ impl Default for CreateOptions {
     }
 }
 
+fn invalid_option(name: &str) -> crate::Error {
+    log::error!("Keyspace option {name:?} is missing or invalid");
+
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("missing or invalid keyspace option: {name}"),
+    )
+    .into()
+}
+
 macro_rules! policy {
     ($keyspace_id:expr, $name:expr, $field:expr) => {{
         let key = encode_config_key($keyspace_id, $name);
@@ -132,106 +153,83 @@ macro_rules! policy {
 }
 
 impl CreateOptions {
-    #[expect(clippy::expect_used, clippy::too_many_lines)]
     pub(crate) fn from_kvs(
         keyspace_id: InternalKeyspaceId,
         meta_keyspace: &MetaKeyspace,
     ) -> crate::Result<Self> {
-        let blob = meta_keyspace.get_kv_for_config(keyspace_id, "blob")?;
+        Self::decode_kvs(|name| meta_keyspace.get_kv_for_config(keyspace_id, name))
+    }
+
+    /// Decodes the options from their config KVs, which are looked up by name.
+    ///
+    /// Missing or invalid options are reported as [`std::io::ErrorKind::InvalidData`].
+    #[expect(clippy::too_many_lines)]
+    pub(crate) fn decode_kvs(
+        get: impl Fn(&str) -> crate::Result<Option<UserValue>>,
+    ) -> crate::Result<Self> {
+        let require = |name: &str| get(name)?.ok_or_else(|| invalid_option(name));
+
+        let blob = get("blob")?;
 
-        let data_block_compression_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "data_block_compression_policy")?
-            .expect("should exist");
+        let data_block_compression_policy = require("data_block_compression_policy")?;
         let data_block_compression_policy =
             CompressionPolicy::decode(&data_block_compression_policy)?;
 
-        let index_block_compression_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "index_block_compression_policy")?
-            .expect("should exist");
+        let index_block_compression_policy = require("index_block_compression_policy")?;
         let index_block_compression_policy =
             CompressionPolicy::decode(&index_block_compression_policy)?;
 
-        let data_block_size_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "data_block_size_policy")?
-            .expect("should exist");
+        let data_block_size_policy = require("data_block_size_policy")?;
         let data_block_size_policy = BlockSizePolicy::decode(&data_block_size_policy)?;
 
-        let filter_block_partitioning_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "filter_block_partitioning_policy")?
-            .expect("should exist");
+        let filter_block_partitioning_policy = require("filter_block_partitioning_policy")?;
         let filter_block_partitioning_policy =
             PinningPolicy::decode(&filter_block_partitioning_policy)?;
 
-        let index_block_partitioning_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "index_block_partitioning_policy")?
-            .expect("should exist");
+        let index_block_partitioning_policy = require("index_block_partitioning_policy")?;
         let index_block_partitioning_policy =
             PinningPolicy::decode(&index_block_partitioning_policy)?;
 
-        let filter_block_pinning_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "filter_block_pinning_policy")?
-            .expect("should exist");
+        let filter_block_pinning_policy = require("filter_block_pinning_policy")?;
         let filter_block_pinning_policy = PinningPolicy::decode(&filter_block_pinning_policy)?;
 
-        let index_block_pinning_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "index_block_pinning_policy")?
-            .expect("should exist");
+        let index_block_pinning_policy = require("index_block_pinning_policy")?;
         let index_block_pinning_policy = PinningPolicy::decode(&index_block_pinning_policy)?;
 
-        let data_block_restart_interval_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "data_block_restart_interval_policy")?
-            .expect("should exist");
+        let data_block_restart_interval_policy = require("data_block_restart_interval_policy")?;
         let data_block_restart_interval_policy =
             RestartIntervalPolicy::decode(&data_block_restart_interval_policy)?;
 
-        let index_block_restart_interval_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "index_block_restart_interval_policy")?
-            .expect("should exist");
+        let index_block_restart_interval_policy = require("index_block_restart_interval_policy")?;
         let index_block_restart_interval_policy =
             RestartIntervalPolicy::decode(&index_block_restart_interval_policy)?;
 
-        let data_block_hash_ratio_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "data_block_hash_ratio_policy")?
-            .expect("should exist");
+        let data_block_hash_ratio_policy = require("data_block_hash_ratio_policy")?;
         let data_block_hash_ratio_policy = HashRatioPolicy::decode(&data_block_hash_ratio_policy)?;
 
-        let expect_point_read_hits = meta_keyspace
-            .get_kv_for_config(keyspace_id, "expect_point_read_hits")?
-            .expect("should exist");
+        let expect_point_read_hits = require("expect_point_read_hits")?;
         let expect_point_read_hits = expect_point_read_hits == [1];
 
-        let filter_policy = meta_keyspace
-            .get_kv_for_config(keyspace_id, "filter_policy")?
-            .expect("should exist");
+        let filter_policy = require("filter_policy")?;
         let filter_policy = FilterPolicy::decode(&filter_policy)?;
 
         let blob_opts = blob.map(|_| {
             use byteorder::LE;
             use lsm_tree::coding::Decode;
 
-            let blob_age_cutoff = meta_keyspace
-                .get_kv_for_config(keyspace_id, "blob_age_cutoff")?
-                .expect("blob_age_cutoff should be defined");
+            let blob_age_cutoff = require("blob_age_cutoff")?;
             let blob_age_cutoff = (&mut &blob_age_cutoff[..]).read_f32::<LE>()?;
 
-            let blob_compression = meta_keyspace
-                .get_kv_for_config(keyspace_id, "blob_compression")?
-                .expect("blob_compression should be defined");
+            let blob_compression = require("blob_compression")?;
             let blob_compression = CompressionType::decode_from(&mut &blob_compression[..])?;
 
-            let file_target_size = meta_keyspace
-                .get_kv_for_config(keyspace_id, "blob_file_target_size")?
-                .expect("blob_file_target_size should be defined");
+            let file_target_size = require("blob_file_target_size")?;
             let file_target_size = (&mut &file_target_size[..]).read_u64::<LE>()?;
 
-            let separation_threshold = meta_keyspace
-                .get_kv_for_config(keyspace_id, "blob_separation_threshold")?
-                .expect("blob_separation_threshold should be defined");
+            let separation_threshold = require("blob_separation_threshold")?;
             let separation_threshold = (&mut &separation_threshold[..]).read_u32::<LE>()?;
 
-            let staleness_threshold = meta_keyspace
-                .get_kv_for_config(keyspace_id, "blob_staleness_threshold")?
-                .expect("blob_staleness_threshold should be defined");
+            let staleness_threshold = require("blob_staleness_threshold")?;
             let staleness_threshold = (&mut &staleness_threshold[..]).read_f32::<LE>()?;
 
             Ok::<_, crate::Error>(
@@ -244,30 +242,22 @@ impl CreateOptions {
             )
         });
 
-        let compaction_strategy_name = meta_keyspace
-            .get_kv_for_config(keyspace_id, "compaction_strategy")?
-            .expect("compaction_strategy should be defined");
+        let compaction_strategy_name = require("compaction_strategy")?;
 
         let compaction_strategy_name = std::str::from_utf8(&compaction_strategy_name)
-            .expect("compaction_strategy should be UTF-8");
+            .map_err(|_| invalid_option("compaction_strategy"))?;
 
         let compaction_strategy = match compaction_strategy_name {
             lsm_tree::compaction::LEVELED_COMPACTION_NAME => {
                 use byteorder::LE;
 
-                let l0_threshold = meta_keyspace
-                    .get_kv_for_config(keyspace_id, "leveled_l0_threshold")?
-                    .expect("leveled_l0_threshold should be defined");
+                let l0_threshold = require("leveled_l0_threshold")?;
                 let l0_threshold = (&mut &l0_threshold[..]).read_u8()?;
 
-                let target_size = meta_keyspace
-                    .get_kv_for_config(keyspace_id, "leveled_target_size")?
-                    .expect("leveled_target_size should be defined");
+                let target_size = require("leveled_target_size")?;
                 let target_size = (&mut &target_size[..]).read_u64::<LE>()?;
 
-                let level_ratio_policy_bytes = meta_keyspace
-                    .get_kv_for_config(keyspace_id, "leveled_level_ratio_policy")?
-                    .expect("leveled_level_ratio_policy should be defined");
+                let level_ratio_policy_bytes = require("leveled_level_ratio_policy")?;
                 let level_ratio_policy_bytes = &mut &level_ratio_policy_bytes[..];
 
                 let level_ratio_policy_len = level_ratio_policy_bytes.read_u8()?;
@@ -288,20 +278,13 @@ impl CreateOptions {
             lsm_tree::compaction::FIFO_COMPACTION_NAME => {
                 use byteorder::LE;
 
-                let fifo_limit = meta_keyspace
-                    .get_kv_for_config(keyspace_id, "fifo_limit")?
-                    .expect("fifo_limit should be defined");
+                let fifo_limit = require("fifo_limit")?;
                 let fifo_limit = (&mut &fifo_limit[..]).read_u64::<LE>()?;
 
-                let has_ttl = meta_keyspace
-                    .get_kv_for_config(keyspace_id, "fifo_ttl")?
-                    .expect("fifo_ttl should be defined")
-                    == [1];
+                let has_ttl = require("fifo_ttl")? == [1];
 
                 let ttl_seconds = if has_ttl {
-                    let fifo_ttl_seconds = meta_keyspace
-                        .get_kv_for_config(keyspace_id, "fifo_ttl_seconds")?
-                        .expect("fifo_ttl_seconds should be defined");
+                    let fifo_ttl_seconds = require("fifo_ttl_seconds")?;
                     let fifo_ttl_seconds = (&mut &fifo_ttl_seconds[..]).read_u64::<LE>()?;
 
                     Some(fifo_ttl_seconds)
@@ -312,18 +295,30 @@ impl CreateOptions {
                 Arc::new(crate::compaction::Fifo::new(fifo_limit, ttl_seconds))
             }
             name => {
-                panic!("Invalid/unsupported compaction strategy: {name:?}");
+                log::error!("Invalid/unsupported compaction strategy: {name:?}");
+                return Err(invalid_option("compaction_strategy"));
             }
         };
 
-        let manual_journal_persist = meta_keyspace
-            .get_kv_for_config(keyspace_id, "manual_journal_persist")?
-            .expect("should exist")
-            == [1];
+        let manual_journal_persist = require("manual_journal_persist")? == [1];
+
+        // NOTE: Keyspaces created before journaling could be disabled have no flag
+        let journaling = get("journaling")?.is_none_or(|v| v == [1]);
+
+        let journal_compression = get("journal_compression")?
+            .and_then(|v| {
+                let compression = JournalCompression::decode_config(&v);
 
-        let max_memtable_size = meta_keyspace
-            .get_kv_for_config(keyspace_id, "max_memtable_size")?
-            .expect("should exist");
+                if compression.is_none() {
+                    log::warn!(
+                        "Journal compression of keyspace is not supported, using the database's journal compression"
+                    );
+                }
+
+                compression
+            });
+
+        let max_memtable_size = require("max_memtable_size")?;
         let max_memtable_size = (&mut &max_memtable_size[..]).read_u64::<byteorder::LE>()?;
 
         Ok(Self {
@@ -350,6 +345,10 @@ impl CreateOptions {
 
             manual_journal_persist,
 
+            journaling,
+
+            journal_compression,
+
             max_memtable_size,
 
             compaction_strategy,
@@ -430,6 +429,10 @@ impl CreateOptions {
                 "index_block_restart_interval_policy",
                 self.index_block_restart_interval_policy
             ),
+            {
+                let key = encode_config_key(keyspace_id, "journaling");
+                (key, [u8::from(self.journaling)].into())
+            },
             {
                 let key = encode_config_key(keyspace_id, "level_count");
                 (key, [self.level_count].into())
@@ -462,6 +465,11 @@ impl CreateOptions {
             }
         }
 
+        if let Some(journal_compression) = self.journal_compression {
+            let key = encode_config_key(keyspace_id, "journal_compression");
+            kvs.push((key, journal_compression.encode_config().into()));
+        }
+
         if let Some(blob_opts) = &self.kv_separation_opts {
             kvs.extend([
                 {
@@ -630,6 +638,39 @@ impl CreateOptions {
         self
     }
 
+    /// If `false`, writes to this keyspace skip the journal, and go straight to the memtable.
+    ///
+    /// Useful for data that can be rebuilt, such as caches, because writes are cheaper
+    /// and do not count towards the database's `max_journaling_size`.
+    ///
+    /// Data that has not been flushed to disk is **lost** when the database is reopened,
+    /// even after a clean shutdown. Write batches can not write into keyspaces
+    /// with and without journaling at the same time.
+    /// Writes to this keyspace are not returned by `Database::changes_since`.
+    ///
+    /// Once set for a keyspace, this property is not considered in the future.
+    ///
+    /// Default = true
+    #[must_use]
+    pub fn journaling(mut self, flag: bool) -> Self {
+        self.journaling = flag;
+        self
+    }
+
+    /// Sets the compression type to use for large values of this keyspace that are written into the journal.
+    ///
+    /// Overrides the database's journal compression, e.g. to not waste CPU time on compressing
+    /// values that are incompressible, using [`JournalCompression::None`].
+    ///
+    /// Values smaller than the database's `journal_compression_threshold` are never compressed.
+    ///
+    /// Default = same as the database
+    #[must_use]
+    pub fn journal_compression<C: Into<JournalCompression>>(mut self, comp: C) -> Self {
+        self.journal_compression = Some(comp.into());
+        self
+    }
+
     /// Sets the maximum memtable size.
     ///
     /// Default = 64 MiB
@@ -91,8 +91,11 @@ macro_rules! fail_iter {
 }
 
 mod backpressure;
+mod backup;
 mod batch;
 mod builder;
+mod change_feed;
+mod checkpoint;
 
 /// Contains compaction strategies
 pub mod compaction;
@@ -108,7 +111,9 @@ mod db;
 #[cfg(test)]
 mod db_test;
 
+mod encryption;
 mod error;
+mod export;
 mod file;
 mod flush;
 mod guard;
@@ -118,16 +123,32 @@ mod journal;
 mod keyspace;
 mod locked_file;
 mod meta_keyspace;
+
+/// Exports database metrics for monitoring systems
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub mod metrics;
+
 mod path;
 mod poison_dart;
+mod publish_queue;
+mod read_only;
 mod readable;
 mod recovery;
+mod repair;
+mod restore;
+mod secondary;
 mod snapshot;
 mod snapshot_nonce;
 mod snapshot_tracker;
 mod stats;
 mod supervisor;
+
+/// Offline tools for inspecting database files
+pub mod tools;
+
 mod tx;
+mod verify;
 mod version;
 mod worker_pool;
 mod write_buffer_manager;
@@ -144,20 +165,35 @@ pub mod config {
 }
 
 pub use {
+    backup::BackupId,
     batch::WriteBatch as OwnedWriteBatch,
     builder::Builder as DatabaseBuilder,
+    change_feed::{ChangeBatch, ChangeItem, ChangeIter, ChangeType, Subscription},
     db::Database,
     db_config::Config,
+    encryption::EncryptionProvider,
     error::{Error, Result},
     guard::Guard,
     iter::Iter,
-    journal::{error::RecoveryError as JournalRecoveryError, writer::PersistMode},
+    journal::{
+        archive::{DirectoryArchiver, JournalArchiver},
+        compression::JournalCompression,
+        error::{RecoveryError as JournalRecoveryError, RecoveryMode},
+        writer::PersistMode,
+    },
     keyspace::{options::CreateOptions as KeyspaceCreateOptions, Keyspace},
     readable::Readable,
+    repair::RepairReport,
+    secondary::SecondaryDatabase,
     snapshot::Snapshot,
+    stats::{DatabaseStats, KeyspaceStats},
+    verify::{VerifyError, VerifyErrorKind, VerifyOptions, VerifyReport},
     version::FormatVersion,
 };
 
+#[cfg(feature = "encryption")]
+pub use encryption::ChaCha20Poly1305Provider;
+
 pub use tx::single_writer::{
     SingleWriterTxKeyspace, TxDatabase as SingleWriterTxDatabase,
     WriteTransaction as SingleWriterWriteTx,
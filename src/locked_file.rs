@@ -4,14 +4,18 @@
 
 use std::{fs::File, path::Path, sync::Arc};
 
-struct LockedFileGuardInner(File);
+/// The locked file, or `None` if no lock is held (read-only databases)
+struct LockedFileGuardInner(Option<File>);
 
 impl Drop for LockedFileGuardInner {
     fn drop(&mut self) {
+        let Some(file) = &self.0 else {
+            return;
+        };
+
         log::debug!("Unlocking database lock");
 
-        self.0
-            .unlock()
+        file.unlock()
             .inspect_err(|e| {
                 log::warn!("Failed to unlock database lock: {e:?}");
             })
@@ -37,7 +41,15 @@ impl LockedFileGuard {
             std::fs::TryLockError::WouldBlock => crate::Error::Locked,
         })?;
 
-        Ok(Self(Arc::new(LockedFileGuardInner(file))))
+        Ok(Self(Arc::new(LockedFileGuardInner(Some(file)))))
+    }
+
+    /// Returns a guard that does not lock anything.
+    ///
+    /// Read-only databases do not take the lock, so they can be opened while
+    /// another process writes to the database.
+    pub fn unlocked() -> Self {
+        Self(Arc::new(LockedFileGuardInner(None)))
     }
 
     pub fn try_acquire(path: &Path) -> crate::Result<Self> {
@@ -67,6 +79,6 @@ impl LockedFileGuard {
             }
         }
 
-        Ok(Self(Arc::new(LockedFileGuardInner(file))))
+        Ok(Self(Arc::new(LockedFileGuardInner(Some(file)))))
     }
 }
@@ -5,7 +5,10 @@
 use crate::{db::Keyspaces, keyspace::InternalKeyspaceId, Keyspace};
 use byteview::StrView;
 use lsm_tree::{AbstractTree, AnyTree, SeqNo, SequenceNumberCounter, UserValue};
-use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock, RwLockWriteGuard},
+};
 
 pub fn encode_config_key(
     keyspace_id: InternalKeyspaceId,
@@ -19,34 +22,52 @@ pub fn encode_config_key(
     key.into()
 }
 
+/// Encodes the key of the ID -> name mapping of a keyspace.
+pub fn encode_name_key(keyspace_id: InternalKeyspaceId) -> crate::UserKey {
+    let mut key: Vec<u8> = Vec::with_capacity(std::mem::size_of::<InternalKeyspaceId>() + 1);
+    key.push(b'n');
+    key.extend(keyspace_id.to_be_bytes());
+    key.into()
+}
+
+/// Returns the tree config of the meta keyspace at `path`.
+pub fn meta_tree_config(
+    path: PathBuf,
+    seqno: SequenceNumberCounter,
+    visible_seqno: SequenceNumberCounter,
+) -> lsm_tree::Config {
+    lsm_tree::Config::new(path, seqno, visible_seqno)
+        .expect_point_read_hits(true)
+        .data_block_size_policy(crate::config::BlockSizePolicy::all(4_096))
+        .data_block_hash_ratio_policy(crate::config::HashRatioPolicy::all(8.0))
+        .data_block_compression_policy(crate::config::CompressionPolicy::disabled())
+        .data_block_restart_interval_policy(crate::config::RestartIntervalPolicy::all(1))
+        .index_block_compression_policy(crate::config::CompressionPolicy::disabled())
+        .filter_policy(crate::config::FilterPolicy::new([
+            lsm_tree::config::FilterPolicyEntry::Bloom(
+                lsm_tree::config::BloomConstructionPolicy::FalsePositiveRate(0.0001),
+            ),
+            lsm_tree::config::FilterPolicyEntry::Bloom(
+                lsm_tree::config::BloomConstructionPolicy::FalsePositiveRate(0.01),
+            ),
+        ]))
+}
+
 /// The meta keyspace keeps mappings of keyspace names to their internal IDs and configurations
 ///
 /// The meta keyspace is always keyspace #0.
 #[derive(Clone)]
 pub struct MetaKeyspace {
-    inner: AnyTree,
+    pub(crate) inner: AnyTree,
 
     /// Dictionary of all keyspaces
     #[doc(hidden)]
     pub keyspaces: Arc<RwLock<Keyspaces>>,
-
-    seqno_generator: SequenceNumberCounter,
-    visible_seqno: SequenceNumberCounter,
 }
 
 impl MetaKeyspace {
-    pub(crate) fn new(
-        inner: AnyTree,
-        keyspaces: Arc<RwLock<Keyspaces>>,
-        seqno_generator: SequenceNumberCounter,
-        visible_seqno: SequenceNumberCounter,
-    ) -> Self {
-        Self {
-            inner,
-            keyspaces,
-            seqno_generator,
-            visible_seqno,
-        }
+    pub(crate) fn new(inner: AnyTree, keyspaces: Arc<RwLock<Keyspaces>>) -> Self {
+        Self { inner, keyspaces }
     }
 
     #[cfg(test)]
@@ -85,16 +106,10 @@ impl MetaKeyspace {
     ) -> crate::Result<()> {
         let mut kvs = keyspace.config.encode_kvs(keyspace_id);
 
-        kvs.push({
-            let mut key: Vec<u8> =
-                Vec::with_capacity(std::mem::size_of::<InternalKeyspaceId>() + 1);
-            key.push(b'n');
-            key.extend(keyspace_id.to_be_bytes());
-
-            let value = UserValue::new(name.as_bytes());
-
-            (key.into(), value)
-        });
+        kvs.push((
+            encode_name_key(keyspace_id),
+            UserValue::new(name.as_bytes()),
+        ));
 
         kvs.sort_by(|(a, _), (b, _)| a.cmp(b));
 
@@ -132,8 +147,6 @@ impl MetaKeyspace {
             return Ok(());
         };
 
-        let seqno = self.seqno_generator.next();
-
         let mut ingestion = self.inner.ingestion()?;
         {
             // Remove all config KVs
@@ -152,16 +165,12 @@ impl MetaKeyspace {
             }
 
             // Remove ID -> name mapping
-            let mut key: Vec<u8> =
-                Vec::with_capacity(std::mem::size_of::<InternalKeyspaceId>() + 1);
-            key.push(b'n');
-            key.extend(keyspace.id.to_be_bytes());
-            ingestion.write_tombstone(key)?;
+            ingestion.write_tombstone(encode_name_key(keyspace.id))?;
         }
+        // NOTE: The ingestion makes the tombstones visible, so the visible seqno is not
+        // advanced here, which could make writes visible that are not yet persisted
         ingestion.finish()?;
 
-        self.visible_seqno.fetch_max(seqno + 1);
-
         lock.remove(name);
 
         self.maintenance()
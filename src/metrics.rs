@@ -0,0 +1,346 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{Database, KeyspaceStats};
+use lsm_tree::{AbstractTree, Metrics};
+use std::{fmt::Display, io::Write, sync::Arc};
+
+type KeyspaceMetric = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&KeyspaceStats) -> u64,
+);
+
+/// Per-keyspace families, taken from [`KeyspaceStats`]
+const KEYSPACE_STATS: &[KeyspaceMetric] = &[
+    (
+        "fjall_keyspace_l0_runs",
+        "Number of sorted runs in L0",
+        "gauge",
+        |stats| stats.l0_run_count as u64,
+    ),
+    (
+        "fjall_keyspace_l0_tables",
+        "Number of tables in L0",
+        "gauge",
+        |stats| stats.l0_table_count as u64,
+    ),
+    (
+        "fjall_keyspace_tables",
+        "Number of tables",
+        "gauge",
+        |stats| stats.table_count as u64,
+    ),
+    (
+        "fjall_keyspace_blob_files",
+        "Number of blob files",
+        "gauge",
+        |stats| stats.blob_file_count as u64,
+    ),
+    (
+        "fjall_keyspace_active_memtable_size_bytes",
+        "Size of the active memtable",
+        "gauge",
+        |stats| stats.active_memtable_size,
+    ),
+    (
+        "fjall_keyspace_sealed_memtables",
+        "Number of sealed memtables waiting to be flushed",
+        "gauge",
+        |stats| stats.sealed_memtable_count as u64,
+    ),
+    (
+        "fjall_keyspace_approximate_items",
+        "Approximate number of items",
+        "gauge",
+        |stats| stats.approximate_len as u64,
+    ),
+    (
+        "fjall_keyspace_disk_space_bytes",
+        "Disk space used by tables and blob files",
+        "gauge",
+        |stats| stats.disk_space,
+    ),
+];
+
+type TreeMetric = (&'static str, &'static str, fn(&Metrics) -> u64);
+
+/// Per-keyspace counters, taken from the LSM-tree's [`Metrics`]
+const TREE_METRICS: &[TreeMetric] = &[
+    (
+        "fjall_keyspace_filter_queries_total",
+        "Number of filter queries",
+        |metrics| metrics.filter_queries() as u64,
+    ),
+    (
+        "fjall_keyspace_filter_io_skipped_total",
+        "Number of block loads that were skipped, because of a filter",
+        |metrics| metrics.io_skipped_by_filter() as u64,
+    ),
+];
+
+/// Block types, with their number of cached block loads, total block loads and IO bytes
+type BlockMetric = (
+    &'static str,
+    fn(&Metrics) -> usize,
+    fn(&Metrics) -> usize,
+    fn(&Metrics) -> u64,
+);
+
+const BLOCK_METRICS: &[BlockMetric] = &[
+    (
+        "data",
+        Metrics::data_block_load_cached_count,
+        Metrics::data_block_load_count,
+        Metrics::data_block_io,
+    ),
+    (
+        "index",
+        Metrics::index_block_load_cached_count,
+        Metrics::index_block_load_count,
+        Metrics::index_block_io,
+    ),
+    (
+        "filter",
+        Metrics::filter_block_load_cached_count,
+        Metrics::filter_block_load_count,
+        Metrics::filter_block_io,
+    ),
+];
+
+/// Escapes a label value, see <https://prometheus.io/docs/instrumenting/exposition_formats/#text-format-details>
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn write_family<W: Write>(
+    writer: &mut W,
+    name: &str,
+    help: &str,
+    kind: &str,
+) -> std::io::Result<()> {
+    writeln!(writer, "# HELP {name} {help}")?;
+    writeln!(writer, "# TYPE {name} {kind}")
+}
+
+fn write_sample<W: Write>(
+    writer: &mut W,
+    name: &str,
+    labels: &[(&str, &str)],
+    value: impl Display,
+) -> std::io::Result<()> {
+    write!(writer, "{name}")?;
+
+    for (idx, (key, value)) in labels.iter().enumerate() {
+        let sep = if idx == 0 { '{' } else { ',' };
+        write!(writer, "{sep}{key}=\"{}\"", escape_label(value))?;
+    }
+
+    if !labels.is_empty() {
+        write!(writer, "}}")?;
+    }
+
+    writeln!(writer, " {value}")
+}
+
+fn write_metric<W: Write>(
+    writer: &mut W,
+    name: &str,
+    help: &str,
+    kind: &str,
+    value: impl Display,
+) -> std::io::Result<()> {
+    write_family(writer, name, help, kind)?;
+    write_sample(writer, name, &[], value)
+}
+
+/// Writes the metrics of the database and its keyspaces to `writer`,
+/// in the Prometheus text exposition format.
+///
+/// Database metrics are taken from [`Database::stats`].
+/// Keyspace metrics additionally include block loads and filter queries of the underlying LSM-tree,
+/// and are labelled by the keyspace name (`keyspace`).
+///
+/// Counters reset when the database is reopened.
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Database, KeyspaceCreateOptions};
+/// #
+/// # let folder = tempfile::tempdir()?;
+/// # let db = Database::builder(folder).open()?;
+/// let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+/// tree.insert("a", "abc")?;
+///
+/// let mut buf = vec![];
+/// fjall::metrics::encode_prometheus(&db, &mut buf)?;
+///
+/// let text = String::from_utf8(buf).unwrap();
+/// assert!(text.contains("fjall_keyspace_approximate_items{keyspace=\"default\"} 1"));
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Will return `Err` if an IO error occurs.
+#[expect(clippy::missing_panics_doc, clippy::too_many_lines)]
+pub fn encode_prometheus<W: Write>(db: &Database, writer: &mut W) -> crate::Result<()> {
+    let stats = db.stats()?;
+
+    // NOTE: Keyspaces that were created after gathering the stats are skipped
+    let trees = {
+        #[expect(clippy::expect_used)]
+        let keyspaces = db.keyspaces.read().expect("lock is poisoned");
+
+        stats
+            .keyspaces
+            .keys()
+            .filter_map(|name| {
+                keyspaces
+                    .get(name.as_str())
+                    .map(|keyspace| (name.as_str(), keyspace.tree.metrics().clone()))
+            })
+            .collect::<Vec<(&str, Arc<Metrics>)>>()
+    };
+
+    write_metric(
+        writer,
+        "fjall_write_buffer_size_bytes",
+        "Size of all active and sealed memtables",
+        "gauge",
+        stats.write_buffer_size,
+    )?;
+    write_metric(
+        writer,
+        "fjall_outstanding_flushes",
+        "Number of queued memtable flushes",
+        "gauge",
+        stats.outstanding_flushes,
+    )?;
+    write_metric(
+        writer,
+        "fjall_active_compactions",
+        "Number of running compactions",
+        "gauge",
+        stats.active_compactions,
+    )?;
+    write_metric(
+        writer,
+        "fjall_compactions_completed_total",
+        "Number of completed compactions",
+        "counter",
+        stats.compactions_completed,
+    )?;
+    write_metric(
+        writer,
+        "fjall_compaction_time_seconds_total",
+        "Time all compactions took",
+        "counter",
+        stats.time_compacting.as_secs_f64(),
+    )?;
+    write_metric(
+        writer,
+        "fjall_journals",
+        "Number of journals on disk",
+        "gauge",
+        stats.journal_count,
+    )?;
+    write_metric(
+        writer,
+        "fjall_journal_disk_space_bytes",
+        "Disk space used by journals",
+        "gauge",
+        stats.journal_disk_space,
+    )?;
+    write_metric(
+        writer,
+        "fjall_open_snapshots",
+        "Number of open snapshots",
+        "gauge",
+        stats.open_snapshots,
+    )?;
+    write_metric(
+        writer,
+        "fjall_poisoned",
+        "1 if the database is poisoned, and rejects writes",
+        "gauge",
+        u8::from(stats.is_poisoned),
+    )?;
+
+    for (name, help, kind, get) in KEYSPACE_STATS {
+        write_family(writer, name, help, kind)?;
+
+        for (keyspace, keyspace_stats) in &stats.keyspaces {
+            write_sample(writer, name, &[("keyspace", keyspace)], get(keyspace_stats))?;
+        }
+    }
+
+    for (name, help, get) in TREE_METRICS {
+        write_family(writer, name, help, "counter")?;
+
+        for (keyspace, metrics) in &trees {
+            write_sample(writer, name, &[("keyspace", keyspace)], get(metrics))?;
+        }
+    }
+
+    write_family(
+        writer,
+        "fjall_keyspace_block_loads_total",
+        "Number of block loads, from the block cache or from disk",
+        "counter",
+    )?;
+
+    for (keyspace, metrics) in &trees {
+        for (block_type, cached, total, _) in BLOCK_METRICS {
+            let cached = cached(metrics);
+
+            write_sample(
+                writer,
+                "fjall_keyspace_block_loads_total",
+                &[
+                    ("keyspace", keyspace),
+                    ("block_type", block_type),
+                    ("source", "cache"),
+                ],
+                cached,
+            )?;
+            write_sample(
+                writer,
+                "fjall_keyspace_block_loads_total",
+                &[
+                    ("keyspace", keyspace),
+                    ("block_type", block_type),
+                    ("source", "io"),
+                ],
+                total(metrics).saturating_sub(cached),
+            )?;
+        }
+    }
+
+    write_family(
+        writer,
+        "fjall_keyspace_block_io_bytes_total",
+        "Number of block bytes read from disk or OS page cache",
+        "counter",
+    )?;
+
+    for (keyspace, metrics) in &trees {
+        for (block_type, _, _, io_bytes) in BLOCK_METRICS {
+            write_sample(
+                writer,
+                "fjall_keyspace_block_io_bytes_total",
+                &[("keyspace", keyspace), ("block_type", block_type)],
+                io_bytes(metrics),
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Publishes writes to readers and change feed subscribers in seqno order
+///
+/// Writers reserve a slot while holding the journal writer lock, so slots are handed out
+/// in seqno order. A write that waits for its journal sync publishes after the sync,
+/// without holding the journal writer lock. Later writes wait for it, so no write becomes
+/// visible before the writes that precede it.
+///
+/// This means a write that does not wait for a sync may still wait for an earlier write's sync
+/// before it becomes visible.
+///
+/// If a write is not published (because its sync failed), no later write is published either,
+/// as publishing a higher seqno would make the failed write visible.
+/// Note that `lsm-tree` also advances the visible seqno when a tree's version changes
+/// (flushes, compactions and ingestions), which this queue does not control.
+#[derive(Default)]
+pub struct PublishQueue {
+    state: Mutex<PublishQueueState>,
+    cond: Condvar,
+}
+
+#[derive(Default)]
+struct PublishQueueState {
+    /// Next slot to hand out
+    next: u64,
+
+    /// Slot whose turn it is to publish
+    current: u64,
+
+    /// Set once a slot was not published, after which nothing is published anymore
+    is_failed: bool,
+}
+
+impl PublishQueue {
+    #[expect(clippy::expect_used)]
+    fn lock(&self) -> MutexGuard<'_, PublishQueueState> {
+        self.state.lock().expect("lock is poisoned")
+    }
+
+    /// Reserves the next slot.
+    ///
+    /// Needs to be called while holding the journal writer lock.
+    pub fn reserve(&self) -> PublishSlot<'_> {
+        let mut state = self.lock();
+        let slot = state.next;
+        state.next += 1;
+
+        PublishSlot {
+            queue: self,
+            slot,
+            is_done: false,
+        }
+    }
+
+    /// Waits for the slot's turn, runs `f` (unless a previous slot failed), and lets the next slot publish.
+    ///
+    /// If `f` is `None`, the slot failed.
+    fn complete(&self, slot: u64, f: Option<impl FnOnce()>) {
+        let mut state = self.lock();
+
+        while state.current != slot {
+            #[expect(clippy::expect_used)]
+            {
+                state = self.cond.wait(state).expect("lock is poisoned");
+            }
+        }
+
+        match f {
+            Some(f) if !state.is_failed => f(),
+            Some(_) => {
+                log::trace!("Not publishing slot {slot}, because a previous slot failed");
+            }
+            None => state.is_failed = true,
+        }
+
+        state.current += 1;
+        self.cond.notify_all();
+    }
+}
+
+/// A reserved position in the [`PublishQueue`]
+///
+/// If the slot is dropped without publishing (e.g. because the write's sync failed),
+/// the write is not published, and neither is any later write, but later writes are not blocked.
+#[must_use]
+pub struct PublishSlot<'a> {
+    queue: &'a PublishQueue,
+    slot: u64,
+    is_done: bool,
+}
+
+impl PublishSlot<'_> {
+    /// Waits until all preceding slots are published, then publishes the write using `f`.
+    ///
+    /// Must not be called while holding the journal writer lock.
+    pub fn publish(mut self, f: impl FnOnce()) {
+        self.is_done = true;
+        self.queue.complete(self.slot, Some(f));
+    }
+}
+
+impl Drop for PublishSlot<'_> {
+    fn drop(&mut self) {
+        if !self.is_done {
+            self.queue.complete(self.slot, None::<fn()>);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn publish_queue_failed_slot() {
+        let queue = PublishQueue::default();
+        let published = AtomicU64::default();
+
+        let a = queue.reserve();
+        let b = queue.reserve();
+        let c = queue.reserve();
+
+        a.publish(|| {
+            published.fetch_add(1, Ordering::Relaxed);
+        });
+        drop(b);
+        c.publish(|| {
+            published.fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert_eq!(1, published.load(Ordering::Relaxed));
+
+        // Later slots are not blocked
+        queue.reserve().publish(|| {});
+    }
+}
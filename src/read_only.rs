@@ -0,0 +1,334 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    checkpoint::link_or_copy,
+    file::{KEYSPACES_FOLDER, LOCK_FILE, LSM_CURRENT_VERSION_MARKER},
+    journal::recovery::JournalId,
+    locked_file::LockedFileGuard,
+    HashMap,
+};
+use lsm_tree::file::{BLOBS_FOLDER, TABLES_FOLDER};
+use std::{
+    ffi::OsString,
+    fs::{File, TryLockError},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Prefix of the folders that read-only databases link their files into
+pub const READ_ONLY_VIEW_PREFIX: &str = ".read_only";
+
+/// Contains the linked journals inside the view
+const JOURNALS_FOLDER: &str = "journals";
+
+/// Private copy of the journals and keyspace folders, which a read-only database opens
+///
+/// Opening a tree deletes files that are not referenced by its current version,
+/// which would include tables that a concurrent writer is just writing.
+/// So instead, the tree folders are rebuilt using hard links, and the
+/// trees are opened from there.
+///
+/// Journals are linked as well, so they can still be read after the writer deleted them.
+/// While views exist, the writer does not reuse recycled journals, which would overwrite them.
+///
+/// The folder is deleted once the view is dropped. The view is locked while it is in use,
+/// so the views of crashed processes can be removed, see [`remove_stale_views`].
+pub struct ReadOnlyView {
+    /// Held while the view is in use
+    ///
+    /// Declared before the folder, so it is unlocked before the folder is deleted.
+    #[expect(unused)]
+    lock: LockedFileGuard,
+
+    folder: tempfile::TempDir,
+
+    /// Version markers of the linked trees, `None` if a tree was not initialized yet
+    versions: HashMap<OsString, Option<Vec<u8>>>,
+
+    /// ID of the newest linked journal, which is the active journal of the view
+    active_journal_id: Option<JournalId>,
+
+    /// Position in the active journal, up to which batches were applied
+    journal_pos: AtomicU64,
+}
+
+impl ReadOnlyView {
+    /// Links the journals, and the current version of every tree into a new view.
+    ///
+    /// The view is created inside the database folder, so files can be hard linked.
+    pub fn create(db_path: &Path, journal_path: &Path) -> crate::Result<Self> {
+        let folder = tempfile::Builder::new()
+            .prefix(READ_ONLY_VIEW_PREFIX)
+            .tempdir_in(db_path)?;
+
+        log::debug!("Creating read-only view at {}", folder.path().display());
+
+        let lock = LockedFileGuard::create_new(&folder.path().join(LOCK_FILE))?;
+
+        let journals_folder = folder.path().join(JOURNALS_FOLDER);
+        std::fs::create_dir(&journals_folder)?;
+
+        let mut active_journal_id = None;
+
+        // IMPORTANT: Link the journals before the trees, a journal is only deleted
+        // after its data was flushed, so its data is contained in one or the other
+        for dirent in std::fs::read_dir(journal_path)? {
+            let dirent = dirent?;
+            let file_name = dirent.file_name();
+
+            let Some(journal_id) = parse_journal_id(&file_name) else {
+                continue;
+            };
+
+            match link_or_copy(&dirent.path(), &journals_folder.join(&file_name), true) {
+                Ok(()) => {
+                    active_journal_id = active_journal_id.max(Some(journal_id));
+                }
+                Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let src_keyspaces_folder = db_path.join(KEYSPACES_FOLDER);
+        let keyspaces_folder = folder.path().join(KEYSPACES_FOLDER);
+
+        std::fs::create_dir(&keyspaces_folder)?;
+
+        let mut versions = HashMap::default();
+
+        for dirent in std::fs::read_dir(&src_keyspaces_folder)? {
+            let dirent = dirent?;
+
+            if !dirent.file_type()?.is_dir() {
+                continue;
+            }
+
+            let version = link_tree(&dirent.path(), &keyspaces_folder.join(dirent.file_name()))?;
+            versions.insert(dirent.file_name(), version);
+        }
+
+        Ok(Self {
+            lock,
+            folder,
+            versions,
+            active_journal_id,
+            journal_pos: AtomicU64::default(),
+        })
+    }
+
+    /// Returns `true` if the database changed in a way that can not be
+    /// followed by reading the active journal.
+    ///
+    /// This is the case if the writer rotated its journal, or if the version of any tree changed.
+    pub fn is_stale(&self, db_path: &Path, journal_path: &Path) -> crate::Result<bool> {
+        let mut newest_journal_id = None;
+
+        for dirent in std::fs::read_dir(journal_path)? {
+            newest_journal_id = newest_journal_id.max(parse_journal_id(&dirent?.file_name()));
+        }
+
+        if newest_journal_id != self.active_journal_id {
+            log::trace!(
+                "Active journal changed from {:?} to {newest_journal_id:?}",
+                self.active_journal_id,
+            );
+            return Ok(true);
+        }
+
+        let mut tree_count = 0;
+
+        for dirent in std::fs::read_dir(db_path.join(KEYSPACES_FOLDER))? {
+            let dirent = dirent?;
+
+            if !dirent.file_type()?.is_dir() {
+                continue;
+            }
+
+            let Some(version) = self.versions.get(&dirent.file_name()) else {
+                log::trace!("Found new tree at {}", dirent.path().display());
+                return Ok(true);
+            };
+
+            if read_version_marker(&dirent.path())? != *version {
+                log::trace!("Version of tree at {} changed", dirent.path().display());
+                return Ok(true);
+            }
+
+            tree_count += 1;
+        }
+
+        // NOTE: A tree was deleted
+        Ok(tree_count != self.versions.len())
+    }
+
+    /// Returns the position in the active journal, up to which batches were applied.
+    pub fn journal_pos(&self) -> u64 {
+        self.journal_pos.load(Ordering::Acquire)
+    }
+
+    /// Sets the position in the active journal, up to which batches were applied.
+    pub fn set_journal_pos(&self, pos: u64) {
+        self.journal_pos.store(pos, Ordering::Release);
+    }
+
+    /// Returns the folder the journals are linked into.
+    pub fn journals_folder(&self) -> PathBuf {
+        self.folder.path().join(JOURNALS_FOLDER)
+    }
+
+    /// Returns the folder the trees are linked into.
+    pub fn keyspaces_folder(&self) -> PathBuf {
+        self.folder.path().join(KEYSPACES_FOLDER)
+    }
+}
+
+/// Removes the views of read-only databases that were not deleted, because their process crashed.
+///
+/// Views that are still in use are locked, and are kept.
+pub fn remove_stale_views(db_path: &Path) -> crate::Result<()> {
+    for dirent in std::fs::read_dir(db_path)? {
+        let dirent = dirent?;
+
+        if !dirent
+            .file_name()
+            .to_string_lossy()
+            .starts_with(READ_ONLY_VIEW_PREFIX)
+        {
+            continue;
+        }
+
+        let lock = match File::open(dirent.path().join(LOCK_FILE)) {
+            Ok(lock) => lock,
+
+            // NOTE: The view is just being created
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+
+            Err(e) => return Err(e.into()),
+        };
+
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => continue,
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        log::info!(
+            "Removing stale read-only view at {}",
+            dirent.path().display()
+        );
+
+        // NOTE: Close the lock first, open files can not be deleted on Windows
+        drop(lock);
+
+        std::fs::remove_dir_all(dirent.path())?;
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the database folder contains views of read-only databases,
+/// including stale views, see [`remove_stale_views`].
+pub fn has_views(db_path: &Path) -> crate::Result<bool> {
+    for dirent in std::fs::read_dir(db_path)? {
+        if dirent?
+            .file_name()
+            .to_string_lossy()
+            .starts_with(READ_ONLY_VIEW_PREFIX)
+        {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Parses the ID of a journal from its file name.
+fn parse_journal_id(file_name: &OsString) -> Option<JournalId> {
+    file_name.to_str()?.strip_suffix(".jnl")?.parse().ok()
+}
+
+/// Reads the version marker of a tree, returning `None` if the tree is not initialized.
+fn read_version_marker(path: &Path) -> crate::Result<Option<Vec<u8>>> {
+    match std::fs::read(path.join(LSM_CURRENT_VERSION_MARKER)) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Links the current version of a tree, returning the linked version marker.
+///
+/// If the tree is not initialized, nothing is linked.
+fn link_tree(src: &Path, dest: &Path) -> crate::Result<Option<Vec<u8>>> {
+    loop {
+        let Some(current_file) = read_version_marker(src)? else {
+            return Ok(None);
+        };
+
+        let Some(version_id) = current_file
+            .get(..std::mem::size_of::<u64>())
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+        else {
+            log::error!("Invalid version pointer in {}", src.display());
+            return Err(crate::Error::Unrecoverable);
+        };
+
+        if dest.try_exists()? {
+            std::fs::remove_dir_all(dest)?;
+        }
+        std::fs::create_dir(dest)?;
+
+        let version_file_name = format!("v{version_id}");
+
+        // NOTE: The writer deletes old versions, once a newer version is persisted
+        match link_or_copy(
+            &src.join(&version_file_name),
+            &dest.join(&version_file_name),
+            true,
+        ) {
+            Ok(()) => {}
+            Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+
+        for folder_name in [TABLES_FOLDER, BLOBS_FOLDER] {
+            let src_folder = src.join(folder_name);
+
+            if !src_folder.try_exists()? {
+                continue;
+            }
+
+            let dest_folder = dest.join(folder_name);
+            std::fs::create_dir(&dest_folder)?;
+
+            for dirent in std::fs::read_dir(&src_folder)? {
+                let dirent = dirent?;
+
+                // NOTE: Files that are deleted in the meantime belong to an old version,
+                // which is detected below
+                match link_or_copy(&dirent.path(), &dest_folder.join(dirent.file_name()), true) {
+                    Ok(()) => {}
+                    Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        // IMPORTANT: Files of a version are only deleted after a newer version was persisted,
+        // so if the version did not change, every file of it was linked
+        if read_version_marker(src)?.as_ref() != Some(&current_file) {
+            log::trace!(
+                "Version of tree at {} changed while linking, retrying",
+                src.display(),
+            );
+            continue;
+        }
+
+        std::fs::write(dest.join(LSM_CURRENT_VERSION_MARKER), &current_file)?;
+
+        return Ok(Some(current_file));
+    }
+}
@@ -3,7 +3,7 @@
 // (found in the LICENSE-* files in the repository)
 
 use crate::{
-    file::{KEYSPACES_FOLDER, LSM_CURRENT_VERSION_MARKER},
+    file::LSM_CURRENT_VERSION_MARKER,
     journal::{
         batch_reader::JournalBatchReader, manager::EvictionWatermark, reader::JournalReader,
     },
@@ -14,12 +14,20 @@ use crate::{
     Database, HashMap, Keyspace,
 };
 use lsm_tree::AbstractTree;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 /// Recovers keyspaces
-pub fn recover_keyspaces(db: &Database, meta_keyspace: &MetaKeyspace) -> crate::Result<()> {
-    let keyspaces_folder = db.config.path.join(KEYSPACES_FOLDER);
-
+///
+/// The keyspaces are opened from `keyspaces_folder`, which is the database's keyspaces folder,
+/// unless the database is read-only.
+pub fn recover_keyspaces(
+    db: &Database,
+    meta_keyspace: &MetaKeyspace,
+    keyspaces_folder: &Path,
+) -> crate::Result<()> {
     log::trace!("Recovering keyspaces in {}", keyspaces_folder.display());
 
     #[expect(clippy::expect_used)]
@@ -27,7 +35,7 @@ pub fn recover_keyspaces(db: &Database, meta_keyspace: &MetaKeyspace) -> crate::
 
     let mut highest_id = 1;
 
-    for dirent in std::fs::read_dir(&keyspaces_folder)? {
+    for dirent in std::fs::read_dir(keyspaces_folder)? {
         let dirent = dirent?;
         let keyspace_path = dirent.path();
 
@@ -54,8 +62,12 @@ pub fn recover_keyspaces(db: &Database, meta_keyspace: &MetaKeyspace) -> crate::
         highest_id = highest_id.max(keyspace_id);
 
         let Some(keyspace_name) = meta_keyspace.resolve_id(keyspace_id)? else {
-            log::debug!("Deleting unreferenced keyspace id={keyspace_id}");
-            std::fs::remove_dir_all(keyspace_path)?;
+            if db.config.delete_unreferenced_keyspaces {
+                log::debug!("Deleting unreferenced keyspace id={keyspace_id}");
+                std::fs::remove_dir_all(keyspace_path)?;
+            } else {
+                log::warn!("Skipping unreferenced keyspace id={keyspace_id}");
+            }
             continue;
         };
 
@@ -66,8 +78,12 @@ pub fn recover_keyspaces(db: &Database, meta_keyspace: &MetaKeyspace) -> crate::
             .join(LSM_CURRENT_VERSION_MARKER)
             .try_exists()?
         {
-            log::debug!("Deleting uninitialized keyspace {keyspace_name:?}");
-            std::fs::remove_dir_all(keyspace_path)?;
+            if db.config.delete_unreferenced_keyspaces {
+                log::debug!("Deleting uninitialized keyspace {keyspace_name:?}");
+                std::fs::remove_dir_all(keyspace_path)?;
+            } else {
+                log::warn!("Skipping uninitialized keyspace {keyspace_name:?}");
+            }
             continue;
         }
 
@@ -127,8 +143,15 @@ pub fn recover_sealed_memtables(
 
         log::debug!("Reading sealed journal at {}", journal_path.display());
 
-        let raw_reader = JournalReader::new(journal_path)?;
-        let reader = JournalBatchReader::new(raw_reader);
+        let raw_reader = if db.config.read_only {
+            JournalReader::new_read_only(journal_path)?
+        } else {
+            JournalReader::new(journal_path)?
+        }
+        .with_encryption(db.config.journal_encryption.clone());
+        let reader = JournalBatchReader::new(raw_reader)
+            .with_recovery_mode(db.config.journal_recovery_mode)
+            .with_seqno_limit(db.config.recover_until_seqno);
 
         let mut watermarks: HashMap<InternalKeyspaceId, EvictionWatermark> = HashMap::default();
 
@@ -0,0 +1,247 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    file::{
+        fsync_directory, KEYSPACES_FOLDER, LOCK_FILE, LSM_CURRENT_VERSION_MARKER, QUARANTINE_FOLDER,
+    },
+    keyspace::{options::CreateOptions, InternalKeyspaceId},
+    locked_file::LockedFileGuard,
+    meta_keyspace::{encode_name_key, meta_tree_config, MetaKeyspace},
+    path::absolute_path,
+    Database,
+};
+use lsm_tree::{
+    file::BLOBS_FOLDER, AbstractTree, AnyTree, KvSeparationOptions, SeqNo, SequenceNumberCounter,
+    UserValue,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Result of [`Database::repair`]
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct RepairReport {
+    /// New locations of the folders that were moved into the quarantine folder
+    pub quarantined: Vec<PathBuf>,
+
+    /// Names of the keyspaces whose name mapping or configuration was rebuilt
+    pub rebuilt: Vec<String>,
+
+    /// `true` if the meta keyspace could not be opened, and was recreated
+    pub meta_keyspace_recreated: bool,
+}
+
+/// Moves `folder` into the quarantine folder of the database.
+fn quarantine(db_path: &Path, folder: &Path, report: &mut RepairReport) -> crate::Result<()> {
+    let quarantine_folder = db_path.join(QUARANTINE_FOLDER);
+    std::fs::create_dir_all(&quarantine_folder)?;
+
+    let name = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut dest = quarantine_folder.join(&name);
+    let mut idx = 1;
+
+    while dest.try_exists()? {
+        dest = quarantine_folder.join(format!("{name}.{idx}"));
+        idx += 1;
+    }
+
+    log::warn!(
+        "Moving {} to quarantine at {}",
+        folder.display(),
+        dest.display(),
+    );
+
+    std::fs::rename(folder, &dest)?;
+    fsync_directory(&quarantine_folder)?;
+
+    report.quarantined.push(dest);
+
+    Ok(())
+}
+
+/// Opens the meta keyspace, or recreates it, if it is damaged.
+fn open_meta_tree(
+    db_path: &Path,
+    keyspaces_folder: &Path,
+    seqno: &SequenceNumberCounter,
+    visible_seqno: &SequenceNumberCounter,
+    report: &mut RepairReport,
+) -> crate::Result<AnyTree> {
+    let path = keyspaces_folder.join("0");
+
+    let config = || meta_tree_config(path.clone(), seqno.clone(), visible_seqno.clone());
+
+    if path.join(LSM_CURRENT_VERSION_MARKER).try_exists()? {
+        match config().open() {
+            Ok(tree) => return Ok(tree),
+            Err(e) => log::error!("Failed to open meta keyspace: {e:?}"),
+        }
+    } else {
+        log::error!("Meta keyspace has no version");
+    }
+
+    if path.try_exists()? {
+        quarantine(db_path, &path, report)?;
+    }
+
+    report.meta_keyspace_recreated = true;
+
+    Ok(config().open()?)
+}
+
+/// Returns the config KVs of the keyspace that are missing in the meta keyspace.
+///
+/// Missing options are set to their defaults.
+fn missing_config_kvs(
+    meta_keyspace: &MetaKeyspace,
+    keyspace_id: InternalKeyspaceId,
+    keyspace_path: &Path,
+) -> crate::Result<Vec<(crate::UserKey, UserValue)>> {
+    let mut options = CreateOptions::default();
+
+    // NOTE: Without its config, a keyspace with key-value separation can only be recognized by its blob files
+    if keyspace_path.join(BLOBS_FOLDER).try_exists()? {
+        options = options.with_kv_separation(Some(KvSeparationOptions::default()));
+    }
+
+    let mut missing = vec![];
+
+    for (key, value) in options.encode_kvs(keyspace_id) {
+        if meta_keyspace.inner.get(&key, SeqNo::MAX)?.is_none() {
+            missing.push((key, value));
+        }
+    }
+
+    Ok(missing)
+}
+
+/// Repairs the database at `path`, see [`Database::repair`].
+pub fn repair(path: &Path) -> crate::Result<RepairReport> {
+    let path = absolute_path(path);
+
+    Database::check_version(&path)?;
+
+    let _lock = LockedFileGuard::try_acquire(&path.join(LOCK_FILE))?;
+
+    log::info!("Repairing database at {}", path.display());
+
+    let mut report = RepairReport::default();
+
+    let keyspaces_folder = path.join(KEYSPACES_FOLDER);
+
+    let seqno = SequenceNumberCounter::default();
+    let visible_seqno = SequenceNumberCounter::default();
+
+    let meta_tree = open_meta_tree(
+        &path,
+        &keyspaces_folder,
+        &seqno,
+        &visible_seqno,
+        &mut report,
+    )?;
+
+    // NOTE: Rebuilt entries need to shadow existing (deleted) entries
+    let next_seqno = meta_tree.get_highest_seqno().map_or(0, |x| x + 1);
+    seqno.fetch_max(next_seqno);
+    visible_seqno.fetch_max(next_seqno);
+
+    let meta_keyspace = MetaKeyspace::new(meta_tree, Arc::default());
+
+    let mut kvs = vec![];
+
+    for dirent in std::fs::read_dir(&keyspaces_folder)?.collect::<Result<Vec<_>, _>>()? {
+        let keyspace_path = dirent.path();
+
+        if dirent.file_type()?.is_file() {
+            log::warn!(
+                "Found stray file {} in keyspaces folder",
+                keyspace_path.display(),
+            );
+            continue;
+        }
+
+        let Some(keyspace_id) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<InternalKeyspaceId>().ok())
+        else {
+            quarantine(&path, &keyspace_path, &mut report)?;
+            continue;
+        };
+
+        if keyspace_id == 0 {
+            continue;
+        }
+
+        if !keyspace_path
+            .join(LSM_CURRENT_VERSION_MARKER)
+            .try_exists()?
+        {
+            log::warn!("Keyspace id={keyspace_id} is not initialized");
+            quarantine(&path, &keyspace_path, &mut report)?;
+            continue;
+        }
+
+        let (name, is_renamed) = match meta_keyspace.resolve_id(keyspace_id)? {
+            Some(name) => (name.to_string(), false),
+
+            // NOTE: If the meta keyspace was lost, the keyspace is still intact,
+            // so it is registered under a new name
+            None if report.meta_keyspace_recreated => {
+                let name = format!("recovered_{keyspace_id}");
+                kvs.push((
+                    encode_name_key(keyspace_id),
+                    UserValue::new(name.as_bytes()),
+                ));
+                (name, true)
+            }
+
+            // NOTE: Otherwise, it is most likely a deleted keyspace
+            None => {
+                log::warn!("Keyspace id={keyspace_id} is not referenced");
+                quarantine(&path, &keyspace_path, &mut report)?;
+                continue;
+            }
+        };
+
+        let missing = missing_config_kvs(&meta_keyspace, keyspace_id, &keyspace_path)?;
+
+        if is_renamed || !missing.is_empty() {
+            log::warn!("Rebuilding name mapping and configuration of keyspace {name:?}");
+            kvs.extend(missing);
+            report.rebuilt.push(name);
+        }
+    }
+
+    if !kvs.is_empty() {
+        kvs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut ingestion = meta_keyspace.inner.ingestion()?;
+
+        for (key, value) in kvs {
+            ingestion.write(key, value)?;
+        }
+
+        ingestion.finish()?;
+    }
+
+    fsync_directory(&keyspaces_folder)?;
+    fsync_directory(&path)?;
+
+    log::info!(
+        "Repaired database at {}, quarantined {} folders, rebuilt {} keyspaces",
+        path.display(),
+        report.quarantined.len(),
+        report.rebuilt.len(),
+    );
+
+    Ok(report)
+}
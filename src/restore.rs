@@ -0,0 +1,405 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    checkpoint::{link_or_copy, write_synced},
+    file::{
+        fsync_directory, FJALL_MARKER, JOURNAL_PATH_MARKER, KEYSPACES_FOLDER, LOCK_FILE,
+        LSM_CURRENT_VERSION_MARKER,
+    },
+    keyspace::{apply_to_base_config, options::CreateOptions, InternalKeyspaceId},
+    locked_file::LockedFileGuard,
+    meta_keyspace::{meta_tree_config, MetaKeyspace},
+    path::absolute_path,
+    verify::{file_checksum, Throttle},
+    Database,
+};
+use lsm_tree::{
+    file::{BLOBS_FOLDER, TABLES_FOLDER},
+    AbstractTree, AnyTree, Checksum, SequenceNumberCounter,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Locks the database at `path`, so it can not be opened while it is restored from or into.
+///
+/// Returns `None` if there is no lock file.
+fn lock_if_exists(path: &Path) -> crate::Result<Option<LockedFileGuard>> {
+    match LockedFileGuard::try_acquire(&path.join(LOCK_FILE)) {
+        Ok(guard) => Ok(Some(guard)),
+        Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn check_file_checksum(path: &Path, expected: Checksum) -> crate::Result<()> {
+    let got = file_checksum(path, &mut Throttle::new(None))?;
+
+    if got != expected {
+        log::error!(
+            "Checksum mismatch of {}, expected {expected:?}, got {got:?}",
+            path.display(),
+        );
+        return Err(lsm_tree::Error::ChecksumMismatch { got, expected }.into());
+    }
+
+    Ok(())
+}
+
+/// Checks the version file of a tree, and all tables and blob files referenced by it.
+fn verify_tree(tree: &AnyTree) -> crate::Result<()> {
+    let folder = &tree.tree_config().path;
+
+    let current_file = std::fs::read(folder.join(LSM_CURRENT_VERSION_MARKER))?;
+
+    // NOTE: The version pointer contains the version ID, followed by the checksum of the version file
+    let Some((version_id, checksum)) = current_file
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .zip(
+            current_file
+                .get(8..24)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u128::from_le_bytes),
+        )
+    else {
+        log::error!("Invalid version pointer in {}", folder.display());
+        return Err(crate::Error::Unrecoverable);
+    };
+
+    check_file_checksum(
+        &folder.join(format!("v{version_id}")),
+        Checksum::from_raw(checksum),
+    )?;
+
+    let version = tree.current_version();
+
+    for table in version.iter_tables() {
+        check_file_checksum(&table.path, table.checksum())?;
+    }
+
+    for blob_file in version.blob_files.iter() {
+        check_file_checksum(blob_file.path(), blob_file.checksum())?;
+    }
+
+    log::debug!("Verified tree at {}", folder.display());
+
+    Ok(())
+}
+
+/// Opens every keyspace in the keyspaces folder, and verifies its files.
+///
+/// Opening a tree fails if any file of its current version is missing.
+fn verify_keyspaces(keyspaces_folder: &Path) -> crate::Result<()> {
+    let seqno = SequenceNumberCounter::default();
+    let visible_seqno = SequenceNumberCounter::default();
+
+    let meta_tree = meta_tree_config(
+        keyspaces_folder.join("0"),
+        seqno.clone(),
+        visible_seqno.clone(),
+    )
+    .open()?;
+
+    verify_tree(&meta_tree)?;
+
+    let meta_keyspace = MetaKeyspace::new(meta_tree, Arc::default());
+
+    for dirent in std::fs::read_dir(keyspaces_folder)? {
+        let dirent = dirent?;
+
+        let Some(keyspace_id) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<InternalKeyspaceId>().ok())
+        else {
+            log::warn!(
+                "Found stray file {} in keyspaces folder",
+                dirent.path().display()
+            );
+            continue;
+        };
+
+        // NOTE: Deleted and uninitialized keyspaces are cleaned up when opening the database
+        if keyspace_id == 0
+            || meta_keyspace.resolve_id(keyspace_id)?.is_none()
+            || !dirent
+                .path()
+                .join(LSM_CURRENT_VERSION_MARKER)
+                .try_exists()?
+        {
+            continue;
+        }
+
+        let options = CreateOptions::from_kvs(keyspace_id, &meta_keyspace)?;
+
+        let tree = apply_to_base_config(
+            lsm_tree::Config::new(dirent.path(), seqno.clone(), visible_seqno.clone()),
+            &options,
+        )
+        .open()?;
+
+        verify_tree(&tree)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the external journal folder of the database at `path`, if it has one.
+fn recorded_journal_path(path: &Path) -> crate::Result<Option<PathBuf>> {
+    match std::fs::read(path.join(JOURNAL_PATH_MARKER)) {
+        Ok(bytes) => Ok(Some(PathBuf::from(
+            String::from_utf8_lossy(&bytes).as_ref(),
+        ))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn is_journal(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("jnl"))
+}
+
+/// Copies the checkpoint into a new database folder, and its journals into `journal_folder`.
+///
+/// Tables and blob files are immutable, so they are hard linked if possible.
+fn copy_checkpoint(checkpoint: &Path, target: &Path, journal_folder: &Path) -> crate::Result<()> {
+    std::fs::create_dir(target)?;
+
+    // NOTE: A database can be restored from as well, which may use an external journal folder
+    let checkpoint_journals = recorded_journal_path(checkpoint)?;
+
+    // Journals
+    for dirent in std::fs::read_dir(checkpoint_journals.as_deref().unwrap_or(checkpoint))? {
+        let dirent = dirent?;
+
+        if is_journal(&dirent.path()) {
+            link_or_copy(
+                &dirent.path(),
+                &journal_folder.join(dirent.file_name()),
+                false,
+            )?;
+        }
+    }
+
+    fsync_directory(journal_folder)?;
+
+    let keyspaces_folder = target.join(KEYSPACES_FOLDER);
+    std::fs::create_dir(&keyspaces_folder)?;
+
+    for dirent in std::fs::read_dir(checkpoint.join(KEYSPACES_FOLDER))? {
+        let dirent = dirent?;
+
+        if !dirent.file_type()?.is_dir() {
+            continue;
+        }
+
+        let tree_folder = keyspaces_folder.join(dirent.file_name());
+        std::fs::create_dir(&tree_folder)?;
+
+        for dirent in std::fs::read_dir(dirent.path())? {
+            let dirent = dirent?;
+            let dest = tree_folder.join(dirent.file_name());
+
+            if dirent.file_type()?.is_file() {
+                link_or_copy(&dirent.path(), &dest, false)?;
+                continue;
+            }
+
+            if dirent.file_name() != TABLES_FOLDER && dirent.file_name() != BLOBS_FOLDER {
+                continue;
+            }
+
+            std::fs::create_dir(&dest)?;
+
+            for dirent in std::fs::read_dir(dirent.path())? {
+                let dirent = dirent?;
+                link_or_copy(&dirent.path(), &dest.join(dirent.file_name()), true)?;
+            }
+
+            fsync_directory(&dest)?;
+        }
+
+        fsync_directory(&tree_folder)?;
+    }
+
+    fsync_directory(&keyspaces_folder)?;
+
+    std::fs::File::create_new(target.join(LOCK_FILE))?.sync_all()?;
+
+    Ok(())
+}
+
+/// Replaces the journals in the external journal folder by the staged journals.
+fn replace_journals(journal_path: &Path, staged_journals: &Path) -> crate::Result<()> {
+    for dirent in std::fs::read_dir(journal_path)? {
+        let dirent = dirent?;
+
+        if is_journal(&dirent.path()) {
+            std::fs::remove_file(dirent.path())?;
+        }
+    }
+
+    for dirent in std::fs::read_dir(staged_journals)? {
+        let dirent = dirent?;
+        std::fs::rename(dirent.path(), journal_path.join(dirent.file_name()))?;
+    }
+
+    fsync_directory(journal_path)?;
+
+    Ok(())
+}
+
+/// Restores the checkpoint into the database folder at `target`.
+///
+/// The checkpoint is copied into a temporary folder next to `target` and verified,
+/// before it replaces `target`.
+///
+/// If the replaced database uses an external journal folder, the restored database
+/// keeps using it, and the checkpoint's journals replace the journals in it.
+pub fn restore_from(checkpoint: &Path, target: &Path, force: bool) -> crate::Result<()> {
+    let checkpoint = absolute_path(checkpoint);
+    let target = absolute_path(target);
+
+    Database::check_version(&checkpoint)?;
+
+    // NOTE: Restoring from a database that is in use would not be consistent
+    let _checkpoint_lock = lock_if_exists(&checkpoint)?;
+
+    let target_exists = target.try_exists()?;
+
+    let target_lock = if target_exists && std::fs::read_dir(&target)?.next().is_some() {
+        if !force {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("restore target {} is not empty", target.display()),
+            )
+            .into());
+        }
+
+        // IMPORTANT: Never delete a folder that is not a database
+        //
+        // An interrupted restore into a database with an external journal folder
+        // leaves a database without version marker, which can be restored into again
+        if !target.join(FJALL_MARKER).try_exists()?
+            && !target.join(JOURNAL_PATH_MARKER).try_exists()?
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("restore target {} is not a database", target.display()),
+            )
+            .into());
+        }
+
+        if std::fs::canonicalize(&target)? == std::fs::canonicalize(&checkpoint)? {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "cannot restore checkpoint into itself",
+            )
+            .into());
+        }
+
+        lock_if_exists(&target)?
+    } else {
+        None
+    };
+
+    // NOTE: Keep using the external journal folder of the replaced database
+    let journal_path = if target_exists {
+        recorded_journal_path(&target)?
+    } else {
+        None
+    };
+
+    log::info!(
+        "Restoring checkpoint {} to {}",
+        checkpoint.display(),
+        target.display(),
+    );
+
+    #[expect(clippy::expect_used, reason = "absolute path is never root")]
+    let parent = target.parent().expect("should have parent");
+    std::fs::create_dir_all(parent)?;
+
+    let staging = tempfile::Builder::new()
+        .prefix(".restore")
+        .tempdir_in(parent)?;
+    let staged = staging.path().join("db");
+
+    // NOTE: The journals are staged inside the external journal folder,
+    // so they can be moved into place
+    let journal_staging = journal_path
+        .as_ref()
+        .map(|journal_path| {
+            std::fs::create_dir_all(journal_path)?;
+
+            tempfile::Builder::new()
+                .prefix(".restore")
+                .tempdir_in(journal_path)
+        })
+        .transpose()?;
+
+    copy_checkpoint(
+        &checkpoint,
+        &staged,
+        journal_staging
+            .as_ref()
+            .map_or(staged.as_path(), |staging| staging.path()),
+    )?;
+    verify_keyspaces(&staged.join(KEYSPACES_FOLDER))?;
+
+    if let Some(journal_path) = &journal_path {
+        write_synced(
+            &staged.join(JOURNAL_PATH_MARKER),
+            journal_path.to_string_lossy().as_bytes(),
+        )?;
+    }
+
+    let version_marker = std::fs::read(checkpoint.join(FJALL_MARKER))?;
+
+    // NOTE: Lastly, write the version marker, so the database is only recovered once complete
+    //
+    // With an external journal folder, that is only after its journals were replaced
+    if journal_path.is_none() {
+        write_synced(&staged.join(FJALL_MARKER), &version_marker)?;
+    }
+    fsync_directory(&staged)?;
+
+    // NOTE: Folders can not be renamed while a file inside them is open on Windows,
+    // so the lock is released right before the swap
+    drop(target_lock);
+
+    if target_exists {
+        // NOTE: Move the old database out of the way first, so it is only deleted
+        // once the restored database is in place
+        let old = tempfile::Builder::new()
+            .prefix(".restore")
+            .tempdir_in(parent)?;
+
+        std::fs::rename(&target, old.path().join("db"))?;
+        std::fs::rename(&staged, &target)?;
+        fsync_directory(parent)?;
+
+        drop(old);
+    } else {
+        std::fs::rename(&staged, &target)?;
+        fsync_directory(parent)?;
+    }
+
+    if let (Some(journal_path), Some(journal_staging)) = (&journal_path, journal_staging) {
+        replace_journals(journal_path, journal_staging.path())?;
+        drop(journal_staging);
+
+        write_synced(&target.join(FJALL_MARKER), &version_marker)?;
+        fsync_directory(&target)?;
+    }
+
+    log::info!("Restored checkpoint to {}", target.display());
+
+    Ok(())
+}
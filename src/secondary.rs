@@ -0,0 +1,191 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    journal::{batch_reader::JournalBatchReader, reader::JournalReader},
+    read_only::ReadOnlyView,
+    tx::single_writer::Openable,
+    Config, Database, Keyspace, KeyspaceCreateOptions, Snapshot,
+};
+use lsm_tree::{AbstractTree, ValueType};
+use std::{
+    path::Path,
+    sync::{Mutex, RwLock},
+};
+
+/// Read-only database that follows a database written to by another process (the primary)
+///
+/// The secondary opens the database in [read-only mode](crate::DatabaseBuilder::read_only),
+/// and catches up with the primary by calling [`SecondaryDatabase::try_catch_up`], e.g. periodically.
+pub struct SecondaryDatabase {
+    config: Config,
+    db: RwLock<Database>,
+    catch_up_lock: Mutex<()>,
+}
+
+impl Openable for SecondaryDatabase {
+    fn open(mut config: Config) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        config.read_only = true;
+
+        Ok(Self {
+            db: RwLock::new(Database::open(config.clone())?),
+            config,
+            catch_up_lock: Mutex::default(),
+        })
+    }
+}
+
+impl SecondaryDatabase {
+    /// Creates a new database builder to open the database at `path` as a secondary.
+    ///
+    /// The database needs to exist.
+    pub fn builder(path: impl AsRef<Path>) -> crate::DatabaseBuilder<Self> {
+        crate::DatabaseBuilder::new(path.as_ref())
+    }
+
+    /// Returns the current instance of the read-only database.
+    ///
+    /// The instance is replaced when [`SecondaryDatabase::try_catch_up`] reopens the database.
+    #[must_use]
+    #[expect(clippy::missing_panics_doc)]
+    pub fn database(&self) -> Database {
+        #[expect(clippy::expect_used)]
+        self.db.read().expect("lock is poisoned").clone()
+    }
+
+    /// Returns a handle to an existing keyspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ReadOnly`](crate::Error::ReadOnly), if the keyspace does not exist.
+    pub fn keyspace(&self, name: &str) -> crate::Result<Keyspace> {
+        self.database()
+            .keyspace(name, KeyspaceCreateOptions::default)
+    }
+
+    /// Opens a cross-keyspace snapshot of the current instance.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot {
+        self.database().snapshot()
+    }
+
+    /// Catches up with the primary.
+    ///
+    /// If the primary only appended to its active journal, the new batches are read from it
+    /// and applied to the memtables, so they become visible through existing keyspace handles.
+    ///
+    /// Otherwise, if the primary rotated its journal, or the version of any keyspace changed
+    /// (e.g. after a flush, compaction or creation of a keyspace), the database is reopened.
+    ///
+    /// Returns `true` if the database was reopened, in which case handles to keyspaces of
+    /// the previous instance do not receive any updates anymore, and need to be reacquired.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occurred.
+    #[expect(clippy::missing_panics_doc)]
+    pub fn try_catch_up(&self) -> crate::Result<bool> {
+        #[expect(clippy::expect_used)]
+        let _lock = self.catch_up_lock.lock().expect("lock is poisoned");
+
+        let db = self.database();
+
+        #[expect(clippy::expect_used)]
+        let view = db
+            .read_only_view
+            .as_ref()
+            .expect("secondary should be read-only");
+
+        if !view.is_stale(&self.config.path, &self.config.journal_path)?
+            && tail_active_journal(&db, view)?
+        {
+            return Ok(false);
+        }
+
+        log::debug!(
+            "Reopening secondary database at {}",
+            self.config.path.display()
+        );
+
+        let new_db = Database::open(self.config.clone())?;
+
+        #[expect(clippy::expect_used)]
+        let mut lock = self.db.write().expect("lock is poisoned");
+        *lock = new_db;
+
+        Ok(true)
+    }
+}
+
+/// Applies the batches that were appended to the active journal since it was last read.
+///
+/// Returns `false` if a batch belongs to a keyspace that is not known to the database,
+/// so the database needs to be reopened.
+fn tail_active_journal(db: &Database, view: &ReadOnlyView) -> crate::Result<bool> {
+    let mut reader = JournalReader::new_read_only(db.journal.path())?
+        .with_encryption(db.config.journal_encryption.clone());
+
+    reader.seek_to(view.journal_pos())?;
+
+    let mut reader = JournalBatchReader::new(reader)
+        .with_recovery_mode(db.config.journal_recovery_mode)
+        .with_last_seqno(db.supervisor.snapshot_tracker.get().saturating_sub(1));
+
+    #[expect(clippy::expect_used)]
+    let keyspaces = db.keyspaces.read().expect("lock is poisoned");
+
+    let mut batch_count = 0;
+
+    for batch in reader.by_ref() {
+        let batch = batch?;
+
+        // NOTE: Resolve all keyspaces first, so a batch is either applied completely, or not at all
+        let mut items = Vec::with_capacity(batch.items.len());
+
+        for item in batch.items {
+            let Some(keyspace) = db
+                .meta_keyspace
+                .resolve_id(item.keyspace_id)?
+                .and_then(|name| keyspaces.get(&name))
+            else {
+                log::debug!(
+                    "Batch with seqno={} belongs to unknown keyspace ID {}",
+                    batch.seqno,
+                    item.keyspace_id,
+                );
+                return Ok(false);
+            };
+
+            items.push((keyspace, item));
+        }
+
+        let mut batch_size = 0;
+
+        for (keyspace, item) in items {
+            let (item_size, _) = match item.value_type {
+                ValueType::Value => keyspace.tree.insert(item.key, item.value, batch.seqno),
+                ValueType::Tombstone => keyspace.tree.remove(item.key, batch.seqno),
+                ValueType::WeakTombstone => keyspace.tree.remove_weak(item.key, batch.seqno),
+                ValueType::Indirection => unreachable!(),
+            };
+
+            batch_size += item_size;
+        }
+
+        db.supervisor.write_buffer_size.allocate(batch_size);
+        db.supervisor.seqno.fetch_max(batch.seqno + 1);
+        db.supervisor.snapshot_tracker.publish(batch.seqno);
+
+        batch_count += 1;
+    }
+
+    view.set_journal_pos(reader.last_valid_pos());
+
+    log::trace!("Applied {batch_count} batches from active journal");
+
+    Ok(true)
+}
@@ -2,7 +2,11 @@
 // This source code is licensed under both the Apache 2.0 and MIT License
 // (found in the LICENSE-* files in the repository)
 
-use std::sync::atomic::{AtomicU64, AtomicUsize};
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicU64, AtomicUsize},
+    time::Duration,
+};
 
 /// Ephemeral, runtime stats
 #[derive(Default)]
@@ -18,3 +22,69 @@ pub struct Stats {
 }
 
 impl Stats {}
+
+/// Statistics of a keyspace, see [`DatabaseStats`]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct KeyspaceStats {
+    /// Number of sorted runs in L0
+    pub l0_run_count: usize,
+
+    /// Number of tables in L0
+    pub l0_table_count: usize,
+
+    /// Number of tables
+    pub table_count: usize,
+
+    /// Number of blob files
+    pub blob_file_count: usize,
+
+    /// Size of the active memtable in bytes
+    pub active_memtable_size: u64,
+
+    /// Number of sealed memtables that are waiting to be flushed
+    pub sealed_memtable_count: usize,
+
+    /// Approximate number of items
+    pub approximate_len: usize,
+
+    /// Disk space used by tables and blob files in bytes
+    pub disk_space: u64,
+}
+
+/// Point-in-time statistics of a database, see [`Database::stats`](crate::Database::stats)
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct DatabaseStats {
+    /// Size of all active and sealed memtables in bytes
+    pub write_buffer_size: u64,
+
+    /// Number of queued memtable flushes
+    pub outstanding_flushes: usize,
+
+    /// Number of running compactions
+    pub active_compactions: usize,
+
+    /// Number of completed compactions
+    pub compactions_completed: usize,
+
+    /// Time all compactions took
+    pub time_compacting: Duration,
+
+    /// Number of journals on disk
+    pub journal_count: usize,
+
+    /// Disk space used by journals in bytes
+    pub journal_disk_space: u64,
+
+    /// Number of open snapshots
+    pub open_snapshots: usize,
+
+    /// `true` if the database is poisoned, and rejects writes
+    pub is_poisoned: bool,
+
+    /// Statistics of every keyspace, by name
+    pub keyspaces: BTreeMap<String, KeyspaceStats>,
+}
@@ -5,8 +5,9 @@
 use lsm_tree::SequenceNumberCounter;
 
 use crate::{
-    flush::manager::FlushManager, journal::manager::JournalManager,
-    snapshot_tracker::SnapshotTracker, write_buffer_manager::WriteBufferManager,
+    change_feed::ChangeFeed, flush::manager::FlushManager, journal::manager::JournalManager,
+    publish_queue::PublishQueue, snapshot_tracker::SnapshotTracker,
+    write_buffer_manager::WriteBufferManager,
 };
 use std::sync::{Arc, Mutex, RwLock};
 
@@ -22,6 +23,12 @@ pub struct SupervisorInner {
     pub(crate) journal_manager: Arc<RwLock<JournalManager>>,
 
     pub(crate) backpressure_lock: Mutex<()>,
+
+    /// Subscribers to committed write batches
+    pub(crate) change_feed: ChangeFeed,
+
+    /// Publishes writes in seqno order, after they are persisted
+    pub(crate) publish_queue: PublishQueue,
 }
 
 #[derive(Clone)]
@@ -0,0 +1,333 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    file::{KEYSPACES_FOLDER, LOCK_FILE},
+    journal::{batch_reader::JournalBatchReader, reader::JournalReader},
+    keyspace::InternalKeyspaceId,
+    locked_file::LockedFileGuard,
+    meta_keyspace::{encode_name_key, meta_tree_config},
+    ChangeType, EncryptionProvider, JournalCompression, JournalRecoveryError, SeqNo,
+};
+use lsm_tree::{AbstractTree, SequenceNumberCounter};
+use std::{
+    collections::BTreeMap,
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub use crate::journal::batch_reader::BatchStatus;
+
+/// An item of a journaled batch
+#[derive(Clone, Debug)]
+pub struct ItemInfo {
+    /// Internal ID of the keyspace the item was written to
+    pub keyspace_id: InternalKeyspaceId,
+
+    /// Name of the keyspace, see [`JournalDump::resolve_keyspace_names`]
+    pub keyspace_name: Option<String>,
+
+    /// Type of write
+    pub change_type: ChangeType,
+
+    /// Compression the value was journaled with
+    pub compression: JournalCompression,
+
+    /// Key size in bytes
+    pub key_size: usize,
+
+    /// (Uncompressed) value size in bytes
+    pub value_size: usize,
+}
+
+/// A journaled batch
+#[derive(Clone, Debug)]
+pub struct BatchInfo {
+    /// File offset of the batch's start marker
+    pub offset: u64,
+
+    /// Sequence number of the batch
+    pub seqno: SeqNo,
+
+    /// Item count announced by the batch's start marker
+    pub item_count: u32,
+
+    /// Items that were read
+    pub items: Vec<ItemInfo>,
+
+    /// Whether the batch is intact
+    pub status: BatchStatus,
+}
+
+/// Contents of a journal file, see [`inspect`]
+#[derive(Clone, Debug)]
+pub struct JournalDump {
+    /// Path of the journal file
+    pub path: PathBuf,
+
+    /// Size of the journal file, including pre-allocated space
+    pub file_len: u64,
+
+    /// End of the last valid batch before any corruption
+    pub valid_len: u64,
+
+    /// All batches, including invalid ones
+    pub batches: Vec<BatchInfo>,
+
+    /// Offset of the first byte that is not part of a valid batch, if the journal is corrupt
+    ///
+    /// Truncating the journal at this offset (see [`truncate`]) keeps all batches before it.
+    pub first_corrupt_offset: Option<u64>,
+
+    /// Describes why reading stopped early, e.g. undecodable bytes
+    pub error: Option<String>,
+}
+
+impl JournalDump {
+    fn mark_corrupt(&mut self, offset: u64) {
+        self.first_corrupt_offset = Some(
+            self.first_corrupt_offset
+                .map_or(offset, |first| first.min(offset)),
+        );
+    }
+
+    fn push(&mut self, batch: BatchInfo) {
+        if batch.status != BatchStatus::Valid {
+            self.mark_corrupt(batch.offset);
+        }
+        self.batches.push(batch);
+    }
+
+    /// Resolves the keyspace names of all items using the meta keyspace of the database at `db_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Locked`](crate::Error::Locked) if the database is currently open.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn resolve_keyspace_names<P: AsRef<Path>>(&mut self, db_path: P) -> crate::Result<()> {
+        let db_path = db_path.as_ref();
+
+        // NOTE: Opening the meta keyspace must not race with an open database
+        let _lock = LockedFileGuard::try_acquire(&db_path.join(LOCK_FILE))?;
+
+        let meta_path = db_path.join(KEYSPACES_FOLDER).join("0");
+
+        if !meta_path.try_exists()? {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no meta keyspace found at {}", meta_path.display()),
+            )
+            .into());
+        }
+
+        let meta_tree = meta_tree_config(
+            meta_path,
+            SequenceNumberCounter::default(),
+            SequenceNumberCounter::default(),
+        )
+        .open()?;
+
+        let mut names = BTreeMap::<InternalKeyspaceId, Option<String>>::new();
+
+        for item in self.batches.iter_mut().flat_map(|batch| &mut batch.items) {
+            let name = if let Some(name) = names.get(&item.keyspace_id) {
+                name.clone()
+            } else {
+                let name = meta_tree
+                    .get(encode_name_key(item.keyspace_id), SeqNo::MAX)?
+                    .map(|name| String::from_utf8_lossy(&name).into_owned());
+
+                names.insert(item.keyspace_id, name.clone());
+                name
+            };
+
+            item.keyspace_name = name;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for JournalDump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "journal {}: {} batches, {}B valid, {}B on disk",
+            self.path.display(),
+            self.batches.len(),
+            self.valid_len,
+            self.file_len,
+        )?;
+
+        for batch in &self.batches {
+            let status = match batch.status {
+                BatchStatus::Valid => "ok".into(),
+                BatchStatus::ChecksumMismatch { expected, got } => {
+                    format!("CHECKSUM MISMATCH (expected={expected}, got={got})")
+                }
+                BatchStatus::InsufficientLength => "INSUFFICIENT LENGTH".into(),
+                BatchStatus::TooManyItems => "TOO MANY ITEMS".into(),
+                BatchStatus::MissingTerminator => "MISSING TERMINATOR".into(),
+            };
+
+            writeln!(
+                f,
+                "@{} batch seqno={} items={} {status}",
+                batch.offset, batch.seqno, batch.item_count,
+            )?;
+
+            for item in &batch.items {
+                write!(f, "  keyspace={}", item.keyspace_id)?;
+
+                if let Some(name) = &item.keyspace_name {
+                    write!(f, " ({name:?})")?;
+                }
+
+                writeln!(
+                    f,
+                    " {:?} key={}B value={}B compression={:?}",
+                    item.change_type, item.key_size, item.value_size, item.compression,
+                )?;
+            }
+        }
+
+        if let Some(offset) = self.first_corrupt_offset {
+            write!(f, "first corrupt offset: {offset}")?;
+
+            if let Some(error) = &self.error {
+                write!(f, " ({error})")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads all batches of a journal file, without modifying it.
+///
+/// Unlike recovery, reading continues after invalid batches, so their contents
+/// can be inspected. Stale data behind the end of a recycled journal is not read.
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Database, KeyspaceCreateOptions};
+/// # let folder = tempfile::tempdir()?;
+/// {
+///     let db = Database::builder(&folder).open()?;
+///     let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+///     tree.insert("a", "abc")?;
+/// }
+///
+/// let mut dump = fjall::tools::journal::inspect(folder.path().join("0.jnl"))?;
+/// dump.resolve_keyspace_names(&folder)?;
+///
+/// assert!(dump.first_corrupt_offset.is_none());
+/// println!("{dump}");
+/// #
+/// # Ok::<_, fjall::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Will return `Err` if an IO error occurs.
+pub fn inspect<P: AsRef<Path>>(path: P) -> crate::Result<JournalDump> {
+    inspect_with_encryption(path, None)
+}
+
+/// Reads the journal file at the given path, decrypting items using the given provider.
+///
+/// Without a provider, encrypted items are reported as undecodable.
+/// See [`inspect`] for details.
+///
+/// # Errors
+///
+/// Will return `Err` if an IO error occurs.
+pub fn inspect_with_encryption<P: AsRef<Path>>(
+    path: P,
+    encryption: Option<Arc<dyn EncryptionProvider>>,
+) -> crate::Result<JournalDump> {
+    let path = path.as_ref();
+
+    let reader = JournalReader::new_read_only(path)?.with_encryption(encryption);
+    let mut reader = JournalBatchReader::new(reader).with_invalid_batches();
+
+    let mut dump = JournalDump {
+        path: path.into(),
+        file_len: path.metadata()?.len(),
+        valid_len: 0,
+        batches: vec![],
+        first_corrupt_offset: None,
+        error: None,
+    };
+
+    loop {
+        let batch = match reader.next() {
+            Some(Ok(batch)) => batch,
+            Some(Err(crate::Error::JournalRecovery(JournalRecoveryError::MissingStart))) => {
+                dump.mark_corrupt(reader.last_entry_pos());
+                dump.error
+                    .get_or_insert_with(|| "item or end marker without start marker".into());
+                continue;
+            }
+            Some(Err(e)) => {
+                dump.mark_corrupt(reader.last_entry_pos());
+                dump.error = Some(format!("undecodable entry: {e:?}"));
+                break;
+            }
+            None => break,
+        };
+
+        if batch.status == BatchStatus::Valid && dump.first_corrupt_offset.is_none() {
+            dump.valid_len = reader.last_valid_pos();
+        }
+
+        dump.push(BatchInfo {
+            offset: batch.offset,
+            seqno: batch.seqno,
+            item_count: batch.item_count,
+            items: batch
+                .items
+                .into_iter()
+                .map(|item| ItemInfo {
+                    keyspace_id: item.keyspace_id,
+                    keyspace_name: None,
+                    change_type: item.value_type.into(),
+                    compression: item.compression,
+                    key_size: item.key.len(),
+                    value_size: item.value.len(),
+                })
+                .collect(),
+            status: batch.status,
+        });
+    }
+
+    Ok(dump)
+}
+
+/// Truncates a journal file at `offset`, discarding everything after it.
+///
+/// Use [`JournalDump::first_corrupt_offset`] to discard the first corrupt batch and everything after it,
+/// so the database can be opened again. Discarded batches are lost.
+///
+/// Must not be used on a journal of an open database.
+///
+/// # Errors
+///
+/// Will return `Err` if an IO error occurs.
+pub fn truncate<P: AsRef<Path>>(path: P, offset: u64) -> crate::Result<()> {
+    let path = path.as_ref();
+
+    log::info!("Truncating journal {} to {offset}B", path.display());
+
+    let file = OpenOptions::new().write(true).open(path)?;
+    file.set_len(offset)?;
+    file.sync_all()?;
+
+    Ok(())
+}
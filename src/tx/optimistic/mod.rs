@@ -33,7 +33,7 @@ impl Openable for OptimisticTxDatabase {
         Self: Sized,
     {
         let inner = Database::create_or_recover(config)?;
-        // inner.start_background_threads()?;
+        inner.start_background_threads()?;
 
         Ok(Self {
             oracle: Arc::new(Oracle {
@@ -163,6 +163,8 @@ impl OptimisticTxDatabase {
 
     /// Returns the current write buffer size (active + sealed memtables).
     #[must_use]
+    #[deprecated(note = "use `inner().stats()` instead")]
+    #[expect(deprecated)]
     pub fn write_buffer_size(&self) -> u64 {
         self.inner.write_buffer_size()
     }
@@ -154,6 +154,8 @@ impl TxDatabase {
 
     /// Returns the current write buffer size (active + sealed memtables).
     #[must_use]
+    #[deprecated(note = "use `inner().stats()` instead")]
+    #[expect(deprecated)]
     pub fn write_buffer_size(&self) -> u64 {
         self.inner.write_buffer_size()
     }
@@ -180,6 +182,7 @@ impl TxDatabase {
     /// Returns error, if an IO error occurred.
     pub fn open(config: Config) -> crate::Result<Self> {
         let inner = Database::create_or_recover(config)?;
+        inner.start_background_threads()?;
 
         Ok(Self {
             inner,
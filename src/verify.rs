@@ -0,0 +1,522 @@
+// Copyright (c) 2024-present, fjall-rs
+// This source code is licensed under both the Apache 2.0 and MIT License
+// (found in the LICENSE-* files in the repository)
+
+use crate::{
+    journal::{
+        batch_reader::JournalBatchReader, error::RecoveryMode, reader::JournalReader,
+        writer::PersistMode,
+    },
+    keyspace::InternalKeyspaceId,
+    Database,
+};
+use lsm_tree::{
+    table::{filter::standard_bloom::Builder as BloomBuilder, Table},
+    AbstractTree, AnyTree, Checksum, InternalValue, SeqNo, UserKey,
+};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Options for [`Database::verify`]
+#[derive(Clone, Debug)]
+pub struct VerifyOptions {
+    max_bytes_per_second: Option<u64>,
+    check_index: bool,
+    check_journals: bool,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            max_bytes_per_second: None,
+            check_index: true,
+            check_journals: true,
+        }
+    }
+}
+
+impl VerifyOptions {
+    /// Limits how many bytes are read per second, so verification does not
+    /// compete with foreground reads and writes for disk bandwidth.
+    ///
+    /// Default = unlimited
+    #[must_use]
+    pub fn max_bytes_per_second(mut self, bytes: Option<u64>) -> Self {
+        self.max_bytes_per_second = bytes;
+        self
+    }
+
+    /// If enabled, every key of a table is looked up through the table's
+    /// index and filter, and needs to be found.
+    ///
+    /// Default = enabled
+    #[must_use]
+    pub fn check_index(mut self, enabled: bool) -> Self {
+        self.check_index = enabled;
+        self
+    }
+
+    /// If enabled, every journal is read and its batches are validated,
+    /// without applying them.
+    ///
+    /// Default = enabled
+    #[must_use]
+    pub fn check_journals(mut self, enabled: bool) -> Self {
+        self.check_journals = enabled;
+        self
+    }
+}
+
+/// Kind of a [`VerifyError`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum VerifyErrorKind {
+    /// The checksum of the file does not match the checksum stored in the version
+    ChecksumMismatch {
+        /// Checksum stored in the version
+        expected: u128,
+
+        /// Checksum of the file on disk
+        got: u128,
+    },
+
+    /// The file does not exist
+    Missing,
+
+    /// The file could not be read or decoded
+    Corrupted(crate::Error),
+
+    /// The items of the table are not sorted
+    UnsortedItems,
+
+    /// A key of the table could not be found through its index or filter
+    IndexMismatch {
+        /// The key that was not found
+        key: UserKey,
+    },
+
+    /// The journal contains an invalid batch
+    Journal(crate::Error),
+
+    /// The keyspace folder has no name mapping in the meta keyspace
+    UnreferencedKeyspace,
+}
+
+impl std::fmt::Display for VerifyErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ChecksumMismatch { expected, got } => {
+                write!(f, "checksum mismatch, expected {expected:x}, got {got:x}")
+            }
+            Self::Missing => write!(f, "file is missing"),
+            Self::Corrupted(e) => write!(f, "corrupted: {e}"),
+            Self::UnsortedItems => write!(f, "items are not sorted"),
+            Self::IndexMismatch { key } => write!(f, "key {key:?} not found through index"),
+            Self::Journal(e) => write!(f, "invalid journal: {e}"),
+            Self::UnreferencedKeyspace => write!(f, "keyspace is not referenced"),
+        }
+    }
+}
+
+/// Integrity error of a single file or folder, found by [`Database::verify`]
+#[derive(Debug)]
+pub struct VerifyError {
+    /// Path of the affected file or folder
+    pub path: PathBuf,
+
+    /// What is wrong with it
+    pub kind: VerifyErrorKind,
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.kind)
+    }
+}
+
+/// Result of [`Database::verify`]
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct VerifyReport {
+    /// Number of verified tables
+    pub table_count: usize,
+
+    /// Number of verified blob files
+    pub blob_file_count: usize,
+
+    /// Number of verified journals
+    pub journal_count: usize,
+
+    /// Number of bytes read
+    pub bytes_read: u64,
+
+    /// Errors that were found
+    pub errors: Vec<VerifyError>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if no errors were found.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn push(&mut self, path: impl Into<PathBuf>, kind: VerifyErrorKind) {
+        let error = VerifyError {
+            path: path.into(),
+            kind,
+        };
+        log::error!("Verification failed: {error}");
+        self.errors.push(error);
+    }
+}
+
+/// Limits the read throughput
+pub struct Throttle {
+    max_bytes_per_second: Option<u64>,
+    start: Instant,
+
+    /// Bytes that were processed, including bytes that were read again
+    bytes: u64,
+
+    /// Bytes of files that were read
+    bytes_read: u64,
+}
+
+impl Throttle {
+    pub fn new(max_bytes_per_second: Option<u64>) -> Self {
+        Self {
+            max_bytes_per_second,
+            start: Instant::now(),
+            bytes: 0,
+            bytes_read: 0,
+        }
+    }
+
+    /// Accounts for `bytes` that were read, and sleeps if reading too fast.
+    pub fn consume(&mut self, bytes: u64) {
+        self.bytes_read += bytes;
+        self.pace(bytes);
+    }
+
+    /// Accounts for `bytes` that were read again (e.g. to decode a file after checksumming it),
+    /// and sleeps if reading too fast.
+    ///
+    /// Unlike [`Throttle::consume`], the bytes are not counted as read.
+    #[expect(clippy::cast_precision_loss)]
+    pub fn pace(&mut self, bytes: u64) {
+        self.bytes += bytes;
+
+        let Some(max) = self.max_bytes_per_second else {
+            return;
+        };
+
+        let target = Duration::from_secs_f64(self.bytes as f64 / max.max(1) as f64);
+        let elapsed = self.start.elapsed();
+
+        if let Some(delay) = target.checked_sub(elapsed) {
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+/// Calculates the checksum of a whole file, like it is stored in the version.
+pub fn file_checksum(path: &Path, throttle: &mut Throttle) -> crate::Result<Checksum> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    let mut buf = vec![0; 64_000];
+
+    loop {
+        let n = file.read(&mut buf)?;
+
+        if n == 0 {
+            break;
+        }
+
+        hasher.update(buf.get(..n).unwrap_or_default());
+        throttle.consume(n as u64);
+    }
+
+    Ok(Checksum::from_raw(hasher.digest128()))
+}
+
+/// Returns `false` if the checksum of the file does not match.
+fn verify_file_checksum(
+    path: &Path,
+    expected: Checksum,
+    throttle: &mut Throttle,
+    report: &mut VerifyReport,
+) -> bool {
+    match file_checksum(path, throttle) {
+        Ok(got) if got == expected => true,
+        Ok(got) => {
+            report.push(
+                path,
+                VerifyErrorKind::ChecksumMismatch {
+                    expected: expected.into_u128(),
+                    got: got.into_u128(),
+                },
+            );
+            false
+        }
+        Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            report.push(path, VerifyErrorKind::Missing);
+            false
+        }
+        Err(e) => {
+            report.push(path, VerifyErrorKind::Corrupted(e));
+            false
+        }
+    }
+}
+
+/// Decodes every block of the table, and looks up its keys through the index and filter.
+fn verify_table_items(
+    table: &Table,
+    options: &VerifyOptions,
+    report: &mut VerifyReport,
+) -> crate::Result<()> {
+    let mut prev: Option<InternalValue> = None;
+
+    for item in table.scan()? {
+        let item = item?;
+
+        let is_new_key = match &prev {
+            Some(prev) if prev.key >= item.key => {
+                report.push(&*table.path, VerifyErrorKind::UnsortedItems);
+                return Ok(());
+            }
+            Some(prev) => prev.key.user_key != item.key.user_key,
+            None => true,
+        };
+
+        // NOTE: Only the newest version of a key is reachable through a point read
+        if options.check_index && is_new_key {
+            let hash = BloomBuilder::get_hash(&item.key.user_key);
+
+            if table
+                .get(&item.key.user_key, SeqNo::MAX, hash)?
+                .is_none_or(|found| found.key.user_key != item.key.user_key)
+            {
+                report.push(
+                    &*table.path,
+                    VerifyErrorKind::IndexMismatch {
+                        key: item.key.user_key,
+                    },
+                );
+                return Ok(());
+            }
+        }
+
+        prev = Some(item);
+    }
+
+    Ok(())
+}
+
+fn verify_tree(
+    tree: &AnyTree,
+    options: &VerifyOptions,
+    throttle: &mut Throttle,
+    report: &mut VerifyReport,
+) {
+    // NOTE: Holding the version keeps its files from being deleted by compactions
+    let version = tree.current_version();
+
+    for table in version.iter_tables() {
+        report.table_count += 1;
+
+        // NOTE: If the file is corrupt, decoding it would only produce more errors
+        if !verify_file_checksum(&table.path, table.checksum(), throttle, report) {
+            continue;
+        }
+
+        if let Err(e) = verify_table_items(table, options, report) {
+            report.push(&*table.path, VerifyErrorKind::Corrupted(e));
+        }
+
+        // NOTE: The table was already counted when checksumming it
+        if let Ok(metadata) = table.path.metadata() {
+            throttle.pace(metadata.len());
+        }
+    }
+
+    for blob_file in version.blob_files.iter() {
+        report.blob_file_count += 1;
+        verify_file_checksum(blob_file.path(), blob_file.checksum(), throttle, report);
+    }
+
+    log::debug!("Verified tree at {}", tree.tree_config().path.display());
+}
+
+/// Checks that every keyspace folder is referenced by the meta keyspace.
+fn verify_keyspace_folders(db: &Database, report: &mut VerifyReport) -> crate::Result<()> {
+    #[expect(clippy::expect_used)]
+    let keyspaces_folder = db
+        .meta_keyspace
+        .inner
+        .tree_config()
+        .path
+        .parent()
+        .expect("meta keyspace should be in keyspaces folder")
+        .to_path_buf();
+
+    // NOTE: Hold the lock, so no keyspace is created in the meantime
+    #[expect(clippy::expect_used)]
+    let _keyspaces = db.keyspaces.read().expect("lock is poisoned");
+
+    for dirent in std::fs::read_dir(&keyspaces_folder)? {
+        let dirent = dirent?;
+
+        let Some(keyspace_id) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<InternalKeyspaceId>().ok())
+        else {
+            log::warn!(
+                "Found stray file {} in keyspaces folder",
+                dirent.path().display()
+            );
+            continue;
+        };
+
+        if keyspace_id != 0 && db.meta_keyspace.resolve_id(keyspace_id)?.is_none() {
+            report.push(dirent.path(), VerifyErrorKind::UnreferencedKeyspace);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads every batch of the journal, without applying it.
+///
+/// If `end` is set, an invalid batch at or after `end` is ignored, because
+/// it may be written concurrently.
+fn verify_journal(
+    db: &Database,
+    path: &Path,
+    end: Option<u64>,
+    throttle: &mut Throttle,
+    report: &mut VerifyReport,
+) -> crate::Result<()> {
+    let reader = match JournalReader::new_read_only(path) {
+        Ok(reader) => reader,
+
+        // NOTE: The journal was evicted in the meantime
+        Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+
+        Err(e) => return Err(e),
+    };
+
+    let mut reader =
+        JournalBatchReader::new(reader.with_encryption(db.config.journal_encryption.clone()))
+            .with_recovery_mode(RecoveryMode::AbsoluteConsistency);
+
+    report.journal_count += 1;
+
+    while let Some(batch) = reader.next() {
+        let Err(e) = batch else {
+            continue;
+        };
+
+        if end.is_some_and(|end| reader.last_valid_pos() >= end) {
+            break;
+        }
+
+        // NOTE: The journal may have been evicted (and recycled) while it was read
+        #[expect(clippy::expect_used)]
+        let is_evicted = end.is_none()
+            && !db
+                .supervisor
+                .journal_manager
+                .read()
+                .expect("lock is poisoned")
+                .sealed_journal_paths()
+                .any(|sealed| sealed == path);
+
+        if !is_evicted {
+            report.push(path, VerifyErrorKind::Journal(e));
+        }
+
+        break;
+    }
+
+    throttle.consume(reader.last_valid_pos());
+
+    Ok(())
+}
+
+fn verify_journals(
+    db: &Database,
+    throttle: &mut Throttle,
+    report: &mut VerifyReport,
+) -> crate::Result<()> {
+    let (sealed, active, end) = {
+        let mut journal_writer = db.journal.get_writer();
+
+        // NOTE: Flush buffered writes, so every batch up to the write position can be read
+        journal_writer.persist(PersistMode::Buffer)?;
+
+        #[expect(clippy::expect_used)]
+        let sealed = db
+            .supervisor
+            .journal_manager
+            .read()
+            .expect("lock is poisoned")
+            .sealed_journal_paths()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        (sealed, journal_writer.path.clone(), journal_writer.pos()?)
+    };
+
+    for path in &sealed {
+        verify_journal(db, path, None, throttle, report)?;
+    }
+
+    verify_journal(db, &active, Some(end), throttle, report)
+}
+
+/// Verifies the integrity of the database, see [`Database::verify`].
+pub fn verify(db: &Database, options: &VerifyOptions) -> crate::Result<VerifyReport> {
+    log::info!("Verifying database at {}", db.config.path.display());
+
+    let start = Instant::now();
+    let mut throttle = Throttle::new(options.max_bytes_per_second);
+    let mut report = VerifyReport::default();
+
+    verify_keyspace_folders(db, &mut report)?;
+
+    let trees = {
+        #[expect(clippy::expect_used)]
+        let keyspaces = db.keyspaces.read().expect("lock is poisoned");
+
+        std::iter::once(db.meta_keyspace.inner.clone())
+            .chain(keyspaces.values().map(|keyspace| keyspace.tree.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    for tree in &trees {
+        verify_tree(tree, options, &mut throttle, &mut report);
+    }
+
+    if options.check_journals {
+        verify_journals(db, &mut throttle, &mut report)?;
+    }
+
+    report.bytes_read = throttle.bytes_read;
+
+    log::info!(
+        "Verified {} tables, {} blob files and {} journals in {:?}, found {} errors",
+        report.table_count,
+        report.blob_file_count,
+        report.journal_count,
+        start.elapsed(),
+        report.errors.len(),
+    );
+
+    Ok(report)
+}
@@ -15,6 +15,11 @@ pub enum FormatVersion {
 
     /// Version for 3.x.x releases
     V3,
+
+    /// Version for 3.x.x releases, using the compact and recyclable journal format
+    ///
+    /// V3 databases are upgraded to V4 when opened writable.
+    V4,
 }
 
 impl std::fmt::Display for FormatVersion {
@@ -29,6 +34,7 @@ impl From<FormatVersion> for u8 {
             FormatVersion::V1 => 1,
             FormatVersion::V2 => 2,
             FormatVersion::V3 => 3,
+            FormatVersion::V4 => 4,
         }
     }
 }
@@ -41,6 +47,7 @@ impl TryFrom<u8> for FormatVersion {
             1 => Ok(Self::V1),
             2 => Ok(Self::V2),
             3 => Ok(Self::V3),
+            4 => Ok(Self::V4),
             _ => Err(()),
         }
     }
@@ -92,6 +99,14 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn version_serialize_4() -> crate::Result<()> {
+        let mut bytes = vec![];
+        FormatVersion::V4.write_file_header(&mut bytes)?;
+        assert_eq!(bytes, &[b'F', b'J', b'L', 4]);
+        Ok(())
+    }
+
     #[test]
     pub fn version_deserialize_success() {
         let version = FormatVersion::parse_file_header(&[b'F', b'J', b'L', 1]);
@@ -104,6 +119,12 @@ mod tests {
         assert_eq!(version, Some(FormatVersion::V2));
     }
 
+    #[test]
+    pub fn version_deserialize_success_4() {
+        let version = FormatVersion::parse_file_header(&[b'F', b'J', b'L', 4]);
+        assert_eq!(version, Some(FormatVersion::V4));
+    }
+
     #[test]
     pub fn version_deserialize_fail() {
         let version = FormatVersion::parse_file_header(&[b'F', b'J', b'X', 1]);
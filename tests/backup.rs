@@ -0,0 +1,177 @@
+use fjall::{Database, KeyspaceCreateOptions, KvSeparationOptions};
+use std::path::Path;
+use test_log::test;
+
+fn backup_file_count(path: &Path) -> std::io::Result<usize> {
+    fn count(path: &Path) -> std::io::Result<usize> {
+        let mut n = 0;
+
+        for dirent in std::fs::read_dir(path)? {
+            let dirent = dirent?;
+
+            if dirent.file_type()?.is_dir() {
+                n += count(&dirent.path())?;
+            } else {
+                n += 1;
+            }
+        }
+
+        Ok(n)
+    }
+
+    count(&path.join("files"))
+}
+
+#[test]
+fn backup_incremental() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let backups = folder.path().join("backups");
+
+    let db = Database::builder(folder.path().join("db")).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("blobs", || {
+        KeyspaceCreateOptions::default()
+            .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    })?;
+
+    tree.insert("a", "a")?;
+    blobs.insert("a", "a".repeat(1_000))?;
+    tree.rotate_memtable_and_wait()?;
+    blobs.rotate_memtable_and_wait()?;
+
+    let full = db.backup(&backups)?;
+    assert_eq!(1, full);
+    assert_eq!(3, backup_file_count(&backups)?);
+
+    tree.insert("b", "b")?;
+    tree.rotate_memtable_and_wait()?;
+
+    // Only in journal
+    tree.insert("c", "c")?;
+
+    let incremental = db.backup_incremental(&backups, full)?;
+    assert_eq!(2, incremental);
+
+    // Only the new table was copied
+    assert_eq!(4, backup_file_count(&backups)?);
+
+    tree.insert("d", "d")?;
+
+    assert_eq!(vec![1, 2], Database::list_backups(&backups)?);
+
+    {
+        Database::restore_backup(&backups, full, folder.path().join("full"))?;
+
+        let db = Database::builder(folder.path().join("full")).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let blobs = db.keyspace("blobs", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(1, tree.len()?);
+        assert_eq!(1, blobs.len()?);
+        assert_eq!(Some("a".repeat(1_000).as_bytes().into()), blobs.get("a")?);
+    }
+
+    {
+        Database::restore_backup(&backups, incremental, folder.path().join("incremental"))?;
+
+        let db = Database::builder(folder.path().join("incremental")).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let blobs = db.keyspace("blobs", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(3, tree.len()?);
+        assert!(!tree.contains_key("d")?);
+        assert_eq!(1, blobs.len()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn backup_restore_after_compaction() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let backups = folder.path().join("backups");
+
+    let db = Database::builder(folder.path().join("db")).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    for key in ["a", "b", "c"] {
+        tree.insert(key, key)?;
+        tree.rotate_memtable_and_wait()?;
+    }
+
+    let full = db.backup(&backups)?;
+
+    tree.remove("a")?;
+    tree.rotate_memtable_and_wait()?;
+    tree.major_compact()?;
+    assert_eq!(1, tree.table_count());
+
+    let incremental = db.backup_incremental(&backups, full)?;
+
+    Database::restore_backup(&backups, full, folder.path().join("full"))?;
+    Database::restore_backup(&backups, incremental, folder.path().join("incremental"))?;
+
+    {
+        let db = Database::builder(folder.path().join("full")).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        assert_eq!(3, tree.len()?);
+        assert_eq!(3, tree.table_count());
+    }
+
+    {
+        let db = Database::builder(folder.path().join("incremental")).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        assert_eq!(2, tree.len()?);
+        assert_eq!(1, tree.table_count());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn backup_not_found() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let backups = folder.path().join("backups");
+
+    let db = Database::builder(folder.path().join("db")).open()?;
+
+    assert!(matches!(
+        db.backup_incremental(&backups, 1),
+        Err(fjall::Error::BackupNotFound(1)),
+    ));
+
+    assert!(matches!(
+        Database::restore_backup(&backups, 1, folder.path().join("restored")),
+        Err(fjall::Error::BackupNotFound(1)),
+    ));
+
+    assert!(Database::list_backups(&backups)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn backup_concurrent() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let backups = folder.path().join("backups");
+
+    let db = Database::builder(folder.path().join("db")).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "a")?;
+
+    let mut ids = std::thread::scope(|s| {
+        let handles = (0..4)
+            .map(|_| s.spawn(|| db.backup(&backups)))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("should join"))
+            .collect::<fjall::Result<Vec<_>>>()
+    })?;
+
+    ids.sort_unstable();
+    assert_eq!(vec![1, 2, 3, 4], ids);
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use fjall::{Database, KeyspaceCreateOptions, PersistMode};
+use test_log::test;
+
+const THREADS: usize = 8;
+const BATCHES_PER_THREAD: usize = 50;
+
+#[test]
+fn batch_group_commit_concurrent() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        std::thread::scope(|s| {
+            let handles = (0..THREADS)
+                .map(|t| {
+                    let db = &db;
+                    let tree = &tree;
+
+                    s.spawn(move || -> fjall::Result<()> {
+                        for i in 0..BATCHES_PER_THREAD {
+                            let mode = if i % 2 == 0 {
+                                PersistMode::SyncData
+                            } else {
+                                PersistMode::SyncAll
+                            };
+
+                            let mut batch = db.batch().durability(Some(mode));
+                            batch.insert(tree, format!("{t}:{i}:a"), "a");
+                            batch.insert(tree, format!("{t}:{i}:b"), "b");
+                            batch.commit()?;
+
+                            // Every commit is visible once it returns
+                            assert!(tree.contains_key(format!("{t}:{i}:b"))?);
+                        }
+                        Ok(())
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for handle in handles {
+                handle.join().expect("thread should not panic")?;
+            }
+
+            Ok::<_, fjall::Error>(())
+        })?;
+
+        assert_eq!(THREADS * BATCHES_PER_THREAD * 2, tree.len()?);
+
+        db.persist(PersistMode::SyncAll)?;
+    }
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(THREADS * BATCHES_PER_THREAD * 2, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn persist_concurrent() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    std::thread::scope(|s| {
+        let handles = (0..THREADS)
+            .map(|t| {
+                let db = &db;
+                let tree = &tree;
+
+                s.spawn(move || -> fjall::Result<()> {
+                    for i in 0..BATCHES_PER_THREAD {
+                        tree.insert(format!("{t}:{i}"), "a")?;
+                        db.persist(PersistMode::SyncData)?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().expect("thread should not panic")?;
+        }
+
+        Ok::<_, fjall::Error>(())
+    })?;
+
+    assert_eq!(THREADS * BATCHES_PER_THREAD, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn batch_group_commit_change_feed_order() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    let changes = db.subscribe();
+
+    std::thread::scope(|s| {
+        let handles = (0..THREADS)
+            .map(|t| {
+                let db = &db;
+                let tree = &tree;
+
+                s.spawn(move || -> fjall::Result<()> {
+                    for i in 0..BATCHES_PER_THREAD {
+                        // NOTE: Mix synced batches with writes that are not synced,
+                        // which must not overtake the synced batches
+                        if t % 2 == 0 {
+                            let mut batch = db.batch().durability(Some(PersistMode::SyncData));
+                            batch.insert(tree, format!("{t}:{i}"), "a");
+                            batch.commit()?;
+                        } else {
+                            tree.insert(format!("{t}:{i}"), "a")?;
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().expect("thread should not panic")?;
+        }
+
+        Ok::<_, fjall::Error>(())
+    })?;
+
+    let seqnos = std::iter::from_fn(|| changes.try_recv())
+        .map(|batch| batch.seqno)
+        .collect::<Vec<_>>();
+
+    assert_eq!(THREADS * BATCHES_PER_THREAD, seqnos.len());
+    assert!(seqnos.is_sorted());
+    assert_eq!(
+        db.visible_seqno(),
+        seqnos.last().copied().unwrap_or_default() + 1
+    );
+
+    Ok(())
+}
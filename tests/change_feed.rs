@@ -0,0 +1,109 @@
+use fjall::{ChangeType, Database, KeyspaceCreateOptions, SingleWriterTxDatabase};
+use test_log::test;
+
+#[test]
+fn change_feed_single_writes() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    tree.insert("a", "before subscribe")?;
+
+    let changes = db.subscribe();
+
+    tree.insert("a", "abc")?;
+    tree.remove("a")?;
+
+    let batch = changes.try_recv().expect("should exist");
+    assert_eq!(1, batch.items.len());
+    assert_eq!("default", &*batch.items[0].keyspace);
+    assert_eq!(b"a", &*batch.items[0].key);
+    assert_eq!(b"abc", &*batch.items[0].value);
+    assert_eq!(ChangeType::Insert, batch.items[0].change_type);
+
+    let next = changes.try_recv().expect("should exist");
+    assert_eq!(batch.seqno + 1, next.seqno);
+    assert_eq!(ChangeType::Remove, next.items[0].change_type);
+    assert!(next.items[0].value.is_empty());
+
+    assert!(changes.try_recv().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn change_feed_batch() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+    let changes = db.subscribe();
+    let changes2 = db.subscribe();
+
+    let mut batch = db.batch();
+    batch.insert(&tree, "a", "a");
+    batch.remove(&tree2, "b");
+    batch.commit()?;
+
+    for changes in [&changes, &changes2] {
+        let batch = changes.try_recv().expect("should exist");
+        assert_eq!(2, batch.items.len());
+        assert_eq!("default", &*batch.items[0].keyspace);
+        assert_eq!(ChangeType::Insert, batch.items[0].change_type);
+        assert_eq!("default2", &*batch.items[1].keyspace);
+        assert_eq!(ChangeType::Remove, batch.items[1].change_type);
+    }
+
+    // Unsubscribe
+    drop(changes2);
+    tree.insert("c", "c")?;
+    assert_eq!(1, changes.len());
+
+    Ok(())
+}
+
+#[test]
+fn change_feed_tx() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = SingleWriterTxDatabase::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    let changes = db.inner().subscribe();
+
+    let mut tx = db.write_tx();
+    tx.insert(&tree, "a", "a");
+    tx.insert(&tree, "b", "b");
+    tx.commit()?;
+
+    let batch = changes.try_recv().expect("should exist");
+    assert_eq!(2, batch.items.len());
+
+    Ok(())
+}
+
+#[test]
+fn change_feed_ends_on_drop() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let changes = {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let changes = db.subscribe();
+
+        for x in 0_u32..10 {
+            tree.insert(x.to_be_bytes(), "")?;
+        }
+
+        changes
+    };
+
+    let seqnos = changes.map(|batch| batch.seqno).collect::<Vec<_>>();
+    assert_eq!(10, seqnos.len());
+    assert!(seqnos.windows(2).all(|w| w[0] < w[1]));
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use fjall::{ChangeType, Database, KeyspaceCreateOptions};
+use test_log::test;
+
+fn wait_for_journal_eviction(db: &Database) {
+    for _ in 0..1_000 {
+        if db.journal_count() == 1 {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    panic!("journal was not evicted");
+}
+
+#[test]
+fn changes_since_active_and_sealed() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+    tree.insert("a", "a")?;
+    tree2.remove("b")?;
+
+    // Rotate the journal without flushing, so the first batches are in a sealed journal
+    tree2.rotate_memtable()?;
+
+    let mut batch = db.batch();
+    batch.insert(&tree, "c", "c");
+    batch.insert(&tree2, "d", "d");
+    batch.commit()?;
+
+    let changes = db.changes_since(0)?.collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(3, changes.len());
+    assert!(changes.windows(2).all(|w| w[0].seqno < w[1].seqno));
+
+    assert_eq!("default", &*changes[0].items[0].keyspace);
+    assert_eq!(ChangeType::Insert, changes[0].items[0].change_type);
+    assert_eq!("default2", &*changes[1].items[0].keyspace);
+    assert_eq!(ChangeType::Remove, changes[1].items[0].change_type);
+    assert_eq!(2, changes[2].items.len());
+
+    let tail = db
+        .changes_since(changes[1].seqno)?
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(&changes[1..], &tail);
+
+    // Writes after creating the iterator are not returned
+    let iter = db.changes_since(0)?;
+    tree.insert("e", "e")?;
+    assert_eq!(3, iter.count());
+
+    Ok(())
+}
+
+#[test]
+fn changes_since_after_restart() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+        tree.insert("b", "b")?;
+    }
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("c", "c")?;
+
+    let keys = db
+        .changes_since(0)?
+        .map(|batch| batch.map(|batch| batch.items[0].key.clone()))
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(3, keys.len());
+    assert_eq!(b"c", &*keys[2]);
+
+    Ok(())
+}
+
+#[test]
+fn changes_since_evicted() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let next_seqno = {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a")?;
+        let first = db.changes_since(0)?.next().expect("should exist")?;
+
+        tree.rotate_memtable_and_wait()?;
+        wait_for_journal_eviction(&db);
+
+        assert!(matches!(
+            db.changes_since(first.seqno),
+            Err(fjall::Error::ChangesEvicted(_)),
+        ));
+
+        // Nothing was lost after the evicted batch
+        assert_eq!(0, db.changes_since(first.seqno + 1)?.count());
+
+        tree.insert("b", "b")?;
+        assert_eq!(1, db.changes_since(first.seqno + 1)?.count());
+
+        first.seqno + 1
+    };
+
+    let db = Database::builder(&folder).open()?;
+
+    assert!(matches!(
+        db.changes_since(0),
+        Err(fjall::Error::ChangesEvicted(_)),
+    ));
+    assert_eq!(1, db.changes_since(next_seqno)?.count());
+
+    Ok(())
+}
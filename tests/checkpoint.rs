@@ -0,0 +1,158 @@
+use fjall::{Database, KeyspaceCreateOptions, KvSeparationOptions};
+use test_log::test;
+
+#[test]
+fn checkpoint_simple() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let checkpoint_path = folder.path().join("checkpoint");
+
+    {
+        let db = Database::builder(folder.path().join("db")).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let blobs = db.keyspace("blobs", || {
+            KeyspaceCreateOptions::default()
+                .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+        })?;
+
+        tree.insert("a", "a")?;
+        blobs.insert("a", "a".repeat(1_000))?;
+        tree.rotate_memtable_and_wait()?;
+        blobs.rotate_memtable_and_wait()?;
+
+        // Sealed, but not flushed
+        tree.insert("b", "b")?;
+        tree.rotate_memtable()?;
+
+        // Only in active journal
+        tree.insert("c", "c")?;
+        blobs.insert("c", "c".repeat(1_000))?;
+
+        db.checkpoint(&checkpoint_path)?;
+
+        tree.insert("d", "d")?;
+        tree.remove("a")?;
+    }
+
+    // Tables are hard linked
+    #[cfg(unix)]
+    for dirent in std::fs::read_dir(checkpoint_path.join("keyspaces/1/tables"))? {
+        use std::os::unix::fs::MetadataExt;
+
+        assert_eq!(2, dirent?.metadata()?.nlink());
+    }
+
+    let db = Database::builder(&checkpoint_path).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("blobs", KeyspaceCreateOptions::default)?;
+
+    assert_eq!(3, tree.len()?);
+    assert!(tree.contains_key("a")?);
+    assert!(tree.contains_key("b")?);
+    assert!(tree.contains_key("c")?);
+    assert!(!tree.contains_key("d")?);
+
+    assert_eq!(2, blobs.len()?);
+    assert_eq!(Some("c".repeat(1_000).as_bytes().into()), blobs.get("c")?);
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_concurrent_writes() -> fjall::Result<()> {
+    const ITEMS: usize = 5_000;
+
+    let folder = tempfile::tempdir()?;
+    let checkpoint_path = folder.path().join("checkpoint");
+
+    {
+        let db = Database::builder(folder.path().join("db")).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+        std::thread::scope(|s| {
+            let writer = s.spawn(|| -> fjall::Result<()> {
+                for i in 0..ITEMS {
+                    let key = format!("{i:0>10}");
+
+                    let mut batch = db.batch();
+                    batch.insert(&tree, &key, "a".repeat(100));
+                    batch.insert(&tree2, &key, "a".repeat(100));
+                    batch.commit()?;
+
+                    if i % 500 == 0 {
+                        tree.rotate_memtable()?;
+                    }
+                    if i % 1_000 == 0 {
+                        tree2.rotate_memtable()?;
+                    }
+                }
+                Ok(())
+            });
+
+            // NOTE: Creating the keyspaces consumed some seqnos as well
+            while db.seqno() < 1_100 {
+                std::thread::yield_now();
+            }
+            db.checkpoint(&checkpoint_path)?;
+
+            writer.join().expect("thread should not panic")
+        })?;
+    }
+
+    let db = Database::builder(&checkpoint_path).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+    let len = tree.len()?;
+    assert!(len >= 1_000);
+
+    // Batches are either contained completely, or not at all
+    assert_eq!(len, tree2.len()?);
+
+    // No batch is missing
+    for (idx, kv) in tree.iter().enumerate() {
+        assert_eq!(format!("{idx:0>10}").as_bytes(), &*kv.key()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_separate_journal_path() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let checkpoint_path = folder.path().join("checkpoint");
+
+    {
+        let db = Database::builder(folder.path().join("db"))
+            .journal_path(folder.path().join("wal"))
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a")?;
+
+        db.checkpoint(&checkpoint_path)?;
+    }
+
+    // NOTE: Journals are stored inside the checkpoint
+    let db = Database::builder(&checkpoint_path).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert!(tree.contains_key("a")?);
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_path_exists() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let checkpoint_path = folder.path().join("checkpoint");
+    std::fs::create_dir(&checkpoint_path)?;
+
+    let db = Database::builder(folder.path().join("db")).open()?;
+
+    assert!(matches!(
+        db.checkpoint(&checkpoint_path),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::AlreadyExists,
+    ));
+
+    Ok(())
+}
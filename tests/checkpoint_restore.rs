@@ -0,0 +1,206 @@
+use fjall::{Database, KeyspaceCreateOptions, KvSeparationOptions};
+use test_log::test;
+
+fn create_checkpoint(folder: &std::path::Path) -> fjall::Result<()> {
+    let db = Database::builder(folder.join("db")).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("blobs", || {
+        KeyspaceCreateOptions::default()
+            .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    })?;
+
+    for x in 0..100u32 {
+        tree.insert(x.to_be_bytes(), "a")?;
+    }
+    blobs.insert("a", "a".repeat(1_000))?;
+    tree.rotate_memtable_and_wait()?;
+    blobs.rotate_memtable_and_wait()?;
+
+    // Only in journal
+    tree.insert("b", "b")?;
+
+    db.checkpoint(folder.join("checkpoint"))?;
+
+    // Not in checkpoint
+    tree.insert("c", "c")?;
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_restore_new() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    create_checkpoint(folder.path())?;
+
+    let target = folder.path().join("restored");
+    Database::restore_from(folder.path().join("checkpoint"), &target, false)?;
+
+    assert!(target.join("lock").try_exists()?);
+
+    let db = Database::builder(&target).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("blobs", KeyspaceCreateOptions::default)?;
+
+    assert_eq!(101, tree.len()?);
+    assert!(!tree.contains_key("c")?);
+    assert_eq!(Some("a".repeat(1_000).as_bytes().into()), blobs.get("a")?);
+
+    // The checkpoint is not affected by writes to the restored database
+    tree.insert("d", "d")?;
+    tree.rotate_memtable_and_wait()?;
+    tree.major_compact()?;
+    drop(tree);
+    drop(blobs);
+    drop(db);
+
+    let checkpoint = Database::builder(folder.path().join("checkpoint")).open()?;
+    let tree = checkpoint.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(101, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_restore_in_place() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    create_checkpoint(folder.path())?;
+
+    let checkpoint = folder.path().join("checkpoint");
+    let target = folder.path().join("db");
+
+    assert!(matches!(
+        Database::restore_from(&checkpoint, &target, false),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::AlreadyExists,
+    ));
+
+    {
+        let _db = Database::builder(&target).open()?;
+
+        assert!(matches!(
+            Database::restore_from(&checkpoint, &target, true),
+            Err(fjall::Error::Locked),
+        ));
+    }
+
+    Database::restore_from(&checkpoint, &target, true)?;
+
+    let db = Database::builder(&target).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(101, tree.len()?);
+    assert!(!tree.contains_key("c")?);
+
+    // Only the checkpoint and the restored database are left behind
+    assert_eq!(2, std::fs::read_dir(folder.path())?.count());
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_restore_journal_path() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    create_checkpoint(folder.path())?;
+
+    let checkpoint = folder.path().join("checkpoint");
+    let target = folder.path().join("target");
+    let journal_path = folder.path().join("wal");
+
+    {
+        let db = Database::builder(&target)
+            .journal_path(&journal_path)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        // Only in the journal of the replaced database
+        tree.insert("x", "x")?;
+    }
+
+    Database::restore_from(&checkpoint, &target, true)?;
+
+    // The journals are restored into the external journal folder
+    assert!(!std::fs::read_dir(&target)?
+        .filter_map(Result::ok)
+        .any(|dirent| dirent.path().extension().is_some_and(|ext| ext == "jnl")));
+
+    // Only the restored journals are left, the staging folder is removed
+    assert!(std::fs::read_dir(&journal_path)?
+        .filter_map(Result::ok)
+        .all(|dirent| dirent.path().extension().is_some_and(|ext| ext == "jnl")));
+
+    assert!(matches!(
+        Database::builder(&target).open(),
+        Err(fjall::Error::JournalPathMismatch(_)),
+    ));
+
+    let db = Database::builder(&target)
+        .journal_path(&journal_path)
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    assert_eq!(101, tree.len()?);
+    assert!(tree.contains_key("b")?);
+    assert!(!tree.contains_key("c")?);
+    assert!(!tree.contains_key("x")?);
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_restore_not_database() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    create_checkpoint(folder.path())?;
+
+    let target = folder.path().join("other");
+    std::fs::create_dir(&target)?;
+    std::fs::write(target.join("file"), "abc")?;
+
+    assert!(Database::restore_from(folder.path().join("checkpoint"), &target, true).is_err());
+    assert_eq!("abc", std::fs::read_to_string(target.join("file"))?);
+
+    assert!(matches!(
+        Database::restore_from(&target, folder.path().join("restored"), false),
+        Err(fjall::Error::Io(_)),
+    ));
+    assert!(!folder.path().join("restored").try_exists()?);
+
+    Ok(())
+}
+
+#[test]
+fn checkpoint_restore_corrupted() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    create_checkpoint(folder.path())?;
+
+    let checkpoint = folder.path().join("checkpoint");
+    let target = folder.path().join("db");
+
+    // Corrupt a table of the checkpoint, without touching the hard linked table of the database
+    let tables_folder = checkpoint.join("keyspaces").join("1").join("tables");
+    let table_path = std::fs::read_dir(&tables_folder)?
+        .next()
+        .expect("should have table")?
+        .path();
+
+    let mut bytes = std::fs::read(&table_path)?;
+    bytes[10] ^= 0xFF;
+    std::fs::remove_file(&table_path)?;
+    std::fs::write(&table_path, bytes)?;
+
+    assert!(matches!(
+        Database::restore_from(&checkpoint, &target, true),
+        Err(fjall::Error::Storage(
+            fjall::LsmError::ChecksumMismatch { .. }
+        )),
+    ));
+
+    // Target is untouched
+    let db = Database::builder(&target).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(102, tree.len()?);
+
+    // Missing table
+    std::fs::remove_file(&table_path)?;
+    assert!(Database::restore_from(&checkpoint, folder.path().join("restored"), false).is_err());
+    assert_eq!(2, std::fs::read_dir(folder.path())?.count());
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use fjall::{Database, KeyspaceCreateOptions};
+use test_log::test;
+
+const VERSION_MARKER: &str = "version";
+
+#[test]
+fn db_format_upgrade_v3() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let marker = folder.path().join(VERSION_MARKER);
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+        tree.rotate_memtable_and_wait()?;
+    }
+
+    // New databases are created as V4
+    assert_eq!(b"FJL\x04", &*std::fs::read(&marker)?);
+
+    // Pretend the database was created by an older 3.x.x release
+    std::fs::write(&marker, b"FJL\x03")?;
+
+    {
+        let db = Database::builder(&folder).read_only(true).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        assert!(tree.contains_key("a")?);
+    }
+
+    // Read-only opens do not write
+    assert_eq!(b"FJL\x03", &*std::fs::read(&marker)?);
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        assert!(tree.contains_key("a")?);
+    }
+
+    assert_eq!(b"FJL\x04", &*std::fs::read(&marker)?);
+
+    Ok(())
+}
+
+#[test]
+fn db_format_from_the_future() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let _db = Database::builder(&folder).open()?;
+    }
+
+    std::fs::write(folder.path().join(VERSION_MARKER), b"FJL\x05")?;
+
+    assert!(matches!(
+        Database::builder(&folder).open(),
+        Err(fjall::Error::InvalidVersion(None))
+    ));
+
+    Ok(())
+}
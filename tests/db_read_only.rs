@@ -0,0 +1,197 @@
+use fjall::{Database, KeyspaceCreateOptions, KvSeparationOptions, PersistMode};
+use test_log::test;
+
+fn read_only_view_count(path: &std::path::Path) -> std::io::Result<usize> {
+    Ok(std::fs::read_dir(path)?
+        .filter_map(Result::ok)
+        .filter(|dirent| {
+            dirent
+                .file_name()
+                .to_string_lossy()
+                .starts_with(".read_only")
+        })
+        .count())
+}
+
+#[test]
+fn db_read_only_concurrent_writer() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("blobs", || {
+        KeyspaceCreateOptions::default()
+            .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    })?;
+
+    tree.insert("a", "a")?;
+    blobs.insert("a", "a".repeat(1_000))?;
+    tree.rotate_memtable_and_wait()?;
+    blobs.rotate_memtable_and_wait()?;
+
+    // Sealed, but not flushed
+    tree.insert("b", "b")?;
+    tree.rotate_memtable()?;
+
+    // Only in active journal
+    tree.insert("c", "c")?;
+    db.persist(PersistMode::Buffer)?;
+
+    {
+        let read_only = Database::builder(&folder).read_only(true).open()?;
+        assert_eq!(1, read_only_view_count(folder.path())?);
+
+        let ro_tree = read_only.keyspace("default", KeyspaceCreateOptions::default)?;
+        let ro_blobs = read_only.keyspace("blobs", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(3, ro_tree.len()?);
+        assert_eq!(
+            Some("a".repeat(1_000).as_bytes().into()),
+            ro_blobs.get("a")?
+        );
+
+        // Writes after opening are not visible
+        tree.insert("d", "d")?;
+        tree.rotate_memtable_and_wait()?;
+        tree.major_compact()?;
+        assert!(!ro_tree.contains_key("d")?);
+        assert_eq!(3, ro_tree.len()?);
+
+        assert!(matches!(
+            ro_tree.insert("e", "e"),
+            Err(fjall::Error::ReadOnly)
+        ));
+        assert!(matches!(ro_tree.remove("a"), Err(fjall::Error::ReadOnly)));
+        assert!(matches!(ro_tree.clear(), Err(fjall::Error::ReadOnly)));
+        assert!(matches!(
+            ro_tree.rotate_memtable(),
+            Err(fjall::Error::ReadOnly)
+        ));
+
+        let mut batch = read_only.batch();
+        batch.insert(&ro_tree, "e", "e");
+        assert!(matches!(batch.commit(), Err(fjall::Error::ReadOnly)));
+
+        assert!(matches!(
+            read_only.keyspace("new", KeyspaceCreateOptions::default),
+            Err(fjall::Error::ReadOnly)
+        ));
+        assert!(matches!(
+            read_only.delete_keyspace(ro_tree),
+            Err(fjall::Error::ReadOnly)
+        ));
+    }
+
+    assert_eq!(0, read_only_view_count(folder.path())?);
+
+    // The writer is not affected
+    tree.insert("e", "e")?;
+    assert_eq!(5, tree.len()?);
+    drop(tree);
+    drop(blobs);
+    drop(db);
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(5, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn db_read_only_concurrent_flushes() -> fjall::Result<()> {
+    const ITEMS: usize = 5_000;
+
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+    std::thread::scope(|s| {
+        let writer = s.spawn(|| -> fjall::Result<()> {
+            for i in 0..ITEMS {
+                let key = format!("{i:0>10}");
+
+                let mut batch = db.batch();
+                batch.insert(&tree, &key, "a".repeat(100));
+                batch.insert(&tree2, &key, "a".repeat(100));
+                batch.commit()?;
+
+                if i % 500 == 0 {
+                    tree.rotate_memtable()?;
+                    tree2.rotate_memtable()?;
+                }
+            }
+
+            Ok(())
+        });
+
+        for _ in 0..5 {
+            let read_only = Database::builder(folder.path()).read_only(true).open()?;
+            let tree = read_only.keyspace("default", KeyspaceCreateOptions::default)?;
+            let tree2 = read_only.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+            // No batch is missing
+            for (idx, kv) in tree.iter().enumerate() {
+                assert_eq!(format!("{idx:0>10}").as_bytes(), &*kv.key()?);
+            }
+            for (idx, kv) in tree2.iter().enumerate() {
+                assert_eq!(format!("{idx:0>10}").as_bytes(), &*kv.key()?);
+            }
+        }
+
+        writer.join().expect("thread should not panic")
+    })?;
+
+    assert_eq!(ITEMS, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn db_read_only_not_exists() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let path = folder.path().join("db");
+
+    assert!(Database::builder(&path).read_only(true).open().is_err());
+    assert!(!path.try_exists()?);
+
+    Ok(())
+}
+
+#[test]
+fn db_read_only_remove_stale_views() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+    }
+
+    // NOTE: Left behind by a read-only database that crashed
+    let stale_view = folder.path().join(".read_only_stale");
+    std::fs::create_dir(&stale_view)?;
+    std::fs::write(stale_view.join("lock"), "")?;
+
+    let reader = Database::builder(&folder).read_only(true).open()?;
+    assert_eq!(2, read_only_view_count(folder.path())?);
+
+    {
+        let _db = Database::builder(&folder).open()?;
+
+        // The view of the open read-only database is kept
+        assert_eq!(1, read_only_view_count(folder.path())?);
+        assert!(!stale_view.try_exists()?);
+    }
+
+    let tree = reader.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert!(tree.contains_key("a")?);
+
+    drop(tree);
+    drop(reader);
+    assert_eq!(0, read_only_view_count(folder.path())?);
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use fjall::{Database, KeyspaceCreateOptions, KvSeparationOptions};
+use test_log::test;
+
+#[test]
+fn db_repair_quarantine_unreferenced() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+    }
+
+    let orphan = folder.path().join("keyspaces").join("99");
+    std::fs::create_dir(&orphan)?;
+    std::fs::write(orphan.join("file"), "abc")?;
+
+    // Kept when opening, if deletion is disabled
+    {
+        let db = Database::builder(&folder)
+            .delete_unreferenced_keyspaces(false)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        assert_eq!(1, tree.len()?);
+    }
+    assert!(orphan.try_exists()?);
+
+    {
+        let _db = Database::builder(&folder).open()?;
+        assert!(matches!(
+            Database::repair(&folder),
+            Err(fjall::Error::Locked)
+        ));
+    }
+
+    std::fs::create_dir(&orphan)?;
+    std::fs::write(orphan.join("file"), "abc")?;
+
+    let report = Database::repair(&folder)?;
+    assert!(!report.meta_keyspace_recreated);
+    assert!(report.rebuilt.is_empty());
+    assert_eq!(
+        vec![folder.path().join(".quarantine").join("99")],
+        report.quarantined,
+    );
+    assert!(!orphan.try_exists()?);
+    assert_eq!(
+        "abc",
+        std::fs::read_to_string(report.quarantined[0].join("file"))?,
+    );
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(1, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn db_repair_meta_keyspace_lost() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let blobs = db.keyspace("blobs", || {
+            KeyspaceCreateOptions::default()
+                .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+        })?;
+
+        for x in 0..100u32 {
+            tree.insert(x.to_be_bytes(), "a")?;
+        }
+        blobs.insert("a", "a".repeat(1_000))?;
+        tree.rotate_memtable_and_wait()?;
+        blobs.rotate_memtable_and_wait()?;
+
+        // Only in journal
+        tree.insert("b", "b")?;
+    }
+
+    std::fs::remove_dir_all(folder.path().join("keyspaces").join("0"))?;
+
+    let report = Database::repair(&folder)?;
+    assert!(report.meta_keyspace_recreated);
+    assert!(report.quarantined.is_empty());
+
+    let mut rebuilt = report.rebuilt;
+    rebuilt.sort();
+    assert_eq!(vec!["recovered_1", "recovered_2"], rebuilt);
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("recovered_1", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("recovered_2", KeyspaceCreateOptions::default)?;
+
+    assert_eq!(101, tree.len()?);
+    assert_eq!(Some("a".repeat(1_000).as_bytes().into()), blobs.get("a")?);
+
+    // Nothing left to repair
+    drop(tree);
+    drop(blobs);
+    drop(db);
+
+    let report = Database::repair(&folder)?;
+    assert!(!report.meta_keyspace_recreated);
+    assert!(report.quarantined.is_empty());
+    assert!(report.rebuilt.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use fjall::{Database, KeyspaceCreateOptions, Readable, SecondaryDatabase};
+use test_log::test;
+
+#[test]
+fn db_secondary_tail_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "a")?;
+
+    let secondary = SecondaryDatabase::builder(&folder).open()?;
+    let ro_tree = secondary.keyspace("default")?;
+    assert_eq!(1, ro_tree.len()?);
+
+    // Nothing to catch up
+    assert!(!secondary.try_catch_up()?);
+    assert_eq!(1, ro_tree.len()?);
+
+    tree.insert("b", "b")?;
+    tree.remove("a")?;
+    assert_eq!(1, ro_tree.len()?);
+
+    // Appended batches are visible through existing handles
+    assert!(!secondary.try_catch_up()?);
+    assert_eq!(1, ro_tree.len()?);
+    assert!(!ro_tree.contains_key("a")?);
+    assert!(ro_tree.contains_key("b")?);
+    assert_eq!(db.seqno(), secondary.database().seqno());
+
+    for x in 0..100u32 {
+        tree.insert(x.to_be_bytes(), "c")?;
+    }
+
+    let snapshot = secondary.snapshot();
+
+    assert!(!secondary.try_catch_up()?);
+    assert_eq!(101, ro_tree.len()?);
+
+    // Snapshots are not affected
+    assert_eq!(1, snapshot.len(&ro_tree)?);
+
+    assert!(matches!(
+        ro_tree.insert("a", "a"),
+        Err(fjall::Error::ReadOnly)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn db_secondary_reopen() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "a")?;
+
+    let secondary = SecondaryDatabase::builder(&folder).open()?;
+    assert_eq!(1, secondary.keyspace("default")?.len()?);
+
+    // Journal rotation
+    tree.insert("b", "b")?;
+    tree.rotate_memtable_and_wait()?;
+    tree.insert("c", "c")?;
+
+    assert!(secondary.try_catch_up()?);
+    assert_eq!(3, secondary.keyspace("default")?.len()?);
+
+    // New keyspace
+    let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+    assert!(secondary.keyspace("default2").is_err());
+    tree2.insert("a", "a")?;
+
+    assert!(secondary.try_catch_up()?);
+    assert_eq!(1, secondary.keyspace("default2")?.len()?);
+
+    // Compaction
+    tree.major_compact()?;
+
+    assert!(secondary.try_catch_up()?);
+    assert_eq!(3, secondary.keyspace("default")?.len()?);
+
+    assert!(!secondary.try_catch_up()?);
+
+    Ok(())
+}
+
+#[test]
+fn db_secondary_concurrent_writer() -> fjall::Result<()> {
+    const ITEMS: usize = 5_000;
+
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+    let secondary = SecondaryDatabase::builder(&folder).open()?;
+
+    std::thread::scope(|s| {
+        let writer = s.spawn(|| -> fjall::Result<()> {
+            for i in 0..ITEMS {
+                let key = format!("{i:0>10}");
+
+                let mut batch = db.batch();
+                batch.insert(&tree, &key, "a".repeat(100));
+                batch.insert(&tree2, &key, "a".repeat(100));
+                batch.commit()?;
+
+                if i % 1_000 == 0 {
+                    tree.rotate_memtable()?;
+                    tree2.rotate_memtable()?;
+                }
+            }
+
+            Ok(())
+        });
+
+        let mut prev_len = 0;
+
+        while !writer.is_finished() {
+            secondary.try_catch_up()?;
+
+            let snapshot = secondary.snapshot();
+            let tree = secondary.keyspace("default")?;
+            let tree2 = secondary.keyspace("default2")?;
+
+            // No batch is missing, or partially applied
+            let len = snapshot.len(&tree)?;
+            assert_eq!(len, snapshot.len(&tree2)?);
+            assert!(len >= prev_len);
+
+            for (idx, kv) in snapshot.iter(&tree).enumerate() {
+                assert_eq!(format!("{idx:0>10}").as_bytes(), &*kv.key()?);
+            }
+
+            prev_len = len;
+        }
+
+        writer.join().expect("thread should not panic")
+    })?;
+
+    secondary.try_catch_up()?;
+    assert_eq!(ITEMS, secondary.keyspace("default")?.len()?);
+    assert_eq!(ITEMS, secondary.keyspace("default2")?.len()?);
+
+    Ok(())
+}
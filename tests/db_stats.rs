@@ -0,0 +1,73 @@
+use fjall::{Database, KeyspaceCreateOptions, KvSeparationOptions};
+use test_log::test;
+
+#[test]
+#[expect(
+    deprecated,
+    reason = "checks the deprecated accessors agree with stats"
+)]
+fn db_stats() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("blobs", || {
+        KeyspaceCreateOptions::default()
+            .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    })?;
+
+    let stats = db.stats()?;
+    assert_eq!(2, stats.keyspaces.len());
+    assert_eq!(0, stats.write_buffer_size);
+    assert_eq!(1, stats.journal_count);
+    assert_eq!(0, stats.open_snapshots);
+    assert!(!stats.is_poisoned);
+    assert_eq!(0, stats.keyspaces["default"].table_count);
+
+    for x in 0..100u32 {
+        tree.insert(x.to_be_bytes(), "a")?;
+    }
+    blobs.insert("a", "a".repeat(1_000))?;
+
+    let stats = db.stats()?;
+    assert_eq!(db.write_buffer_size(), stats.write_buffer_size);
+    assert!(stats.write_buffer_size > 0);
+    assert!(stats.journal_disk_space > 0);
+    assert_eq!(100, stats.keyspaces["default"].approximate_len);
+    assert!(stats.keyspaces["default"].active_memtable_size > 0);
+
+    tree.rotate_memtable_and_wait()?;
+    blobs.rotate_memtable_and_wait()?;
+
+    let _snapshot = db.snapshot();
+
+    let stats = db.stats()?;
+    assert_eq!(1, stats.open_snapshots);
+
+    let tree_stats = &stats.keyspaces["default"];
+    assert_eq!(1, tree_stats.table_count);
+    assert_eq!(1, tree_stats.l0_table_count);
+    assert_eq!(1, tree_stats.l0_run_count);
+    assert_eq!(0, tree_stats.blob_file_count);
+    assert_eq!(0, tree_stats.sealed_memtable_count);
+    assert_eq!(0, tree_stats.active_memtable_size);
+    assert_eq!(tree.disk_space(), tree_stats.disk_space);
+
+    assert_eq!(1, stats.keyspaces["blobs"].blob_file_count);
+
+    tree.major_compact()?;
+    assert_eq!(
+        db.compactions_completed(),
+        db.stats()?.compactions_completed
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn db_stats_serialize() {
+    fn assert_serialize<T: serde::Serialize>() {}
+
+    assert_serialize::<fjall::DatabaseStats>();
+}
@@ -0,0 +1,204 @@
+use fjall::{
+    Database, KeyspaceCreateOptions, KvSeparationOptions, PersistMode, VerifyErrorKind,
+    VerifyOptions,
+};
+use std::io::{Seek, SeekFrom, Write};
+use test_log::test;
+
+fn flip_byte(path: &std::path::Path, pos: u64) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let byte = std::fs::read(path)?[pos as usize];
+    file.seek(SeekFrom::Start(pos))?;
+    file.write_all(&[!byte])?;
+    file.sync_all()
+}
+
+#[test]
+fn db_verify_ok() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let blobs = db.keyspace("blobs", || {
+        KeyspaceCreateOptions::default()
+            .with_kv_separation(Some(KvSeparationOptions::default().separation_threshold(1)))
+    })?;
+
+    for x in 0..1_000u32 {
+        tree.insert(x.to_be_bytes(), "a")?;
+        tree.insert(x.to_be_bytes(), "b")?;
+    }
+    tree.remove(0u32.to_be_bytes())?;
+    blobs.insert("a", "a".repeat(1_000))?;
+
+    tree.rotate_memtable_and_wait()?;
+    blobs.rotate_memtable_and_wait()?;
+
+    // Only in journal
+    tree.insert("b", "b")?;
+
+    let report = db.verify(&VerifyOptions::default())?;
+    assert!(report.is_ok(), "{:?}", report.errors);
+    assert!(report.table_count >= 2);
+    assert_eq!(1, report.blob_file_count);
+    assert!(report.journal_count >= 1);
+    assert!(report.bytes_read > 0);
+
+    Ok(())
+}
+
+#[test]
+fn db_verify_corrupt_table() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    for x in 0..100u32 {
+        tree.insert(x.to_be_bytes(), "a")?;
+    }
+    tree.rotate_memtable_and_wait()?;
+
+    let table_path = std::fs::read_dir(folder.path().join("keyspaces").join("1").join("tables"))?
+        .next()
+        .expect("should have table")?
+        .path();
+
+    flip_byte(&table_path, 10)?;
+
+    let report = db.verify(&VerifyOptions::default())?;
+    assert_eq!(1, report.errors.len());
+    assert_eq!(table_path, report.errors[0].path);
+    assert!(matches!(
+        report.errors[0].kind,
+        VerifyErrorKind::ChecksumMismatch { .. }
+    ));
+
+    std::fs::remove_file(&table_path)?;
+
+    let report = db.verify(&VerifyOptions::default())?;
+    assert_eq!(1, report.errors.len());
+    assert!(matches!(report.errors[0].kind, VerifyErrorKind::Missing));
+
+    Ok(())
+}
+
+#[test]
+fn db_verify_unreferenced_keyspace() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "a")?;
+
+    let orphan = folder.path().join("keyspaces").join("99");
+    std::fs::create_dir(&orphan)?;
+
+    let report = db.verify(&VerifyOptions::default())?;
+    assert_eq!(1, report.errors.len());
+    assert_eq!(orphan, report.errors[0].path);
+    assert!(matches!(
+        report.errors[0].kind,
+        VerifyErrorKind::UnreferencedKeyspace
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn db_verify_corrupt_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "verify-me")?;
+    tree.insert("b", "b")?;
+    db.persist(PersistMode::Buffer)?;
+
+    let journal_path = folder.path().join("0.jnl");
+    let journal = std::fs::read(&journal_path)?;
+    let pos = journal
+        .windows(9)
+        .position(|window| window == b"verify-me")
+        .expect("should find value");
+
+    flip_byte(&journal_path, pos as u64)?;
+
+    let report = db.verify(&VerifyOptions::default())?;
+    assert_eq!(1, report.errors.len());
+    assert_eq!(journal_path, report.errors[0].path);
+    assert!(matches!(report.errors[0].kind, VerifyErrorKind::Journal(_)));
+
+    let report = db.verify(&VerifyOptions::default().check_journals(false))?;
+    assert!(report.is_ok());
+    assert_eq!(0, report.journal_count);
+
+    Ok(())
+}
+
+#[test]
+fn db_verify_in_background() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    for x in 0..1_000u32 {
+        tree.insert(x.to_be_bytes(), "a".repeat(100))?;
+    }
+    tree.rotate_memtable_and_wait()?;
+
+    let start = std::time::Instant::now();
+
+    let handle =
+        db.verify_in_background(VerifyOptions::default().max_bytes_per_second(Some(1_000_000)))?;
+
+    // Writes can continue
+    for x in 0..1_000u32 {
+        tree.insert(x.to_be_bytes(), "b")?;
+    }
+
+    let report = handle.join().expect("thread should not panic")?;
+    assert!(report.is_ok(), "{:?}", report.errors);
+    assert!(report.table_count >= 1);
+
+    // Throttled
+    assert!(report.bytes_read > 100_000);
+    assert!(start.elapsed() >= std::time::Duration::from_millis(100));
+
+    Ok(())
+}
+
+#[test]
+fn db_verify_bytes_read() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    for x in 0..1_000u32 {
+        tree.insert(x.to_be_bytes(), "a")?;
+    }
+    tree.rotate_memtable_and_wait()?;
+
+    let mut table_bytes = 0;
+
+    for keyspace in std::fs::read_dir(folder.path().join("keyspaces"))? {
+        let tables_folder = keyspace?.path().join("tables");
+
+        if !tables_folder.try_exists()? {
+            continue;
+        }
+
+        for table in std::fs::read_dir(tables_folder)? {
+            table_bytes += table?.metadata()?.len();
+        }
+    }
+
+    // Every table is counted once, even though it is read twice (checksum, then items)
+    let report = db.verify(&VerifyOptions::default().check_journals(false))?;
+    assert!(report.is_ok(), "{:?}", report.errors);
+    assert_eq!(table_bytes, report.bytes_read);
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use fjall::{Database, DirectoryArchiver, KeyspaceCreateOptions};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+use test_log::test;
+
+fn wait_for_journal_eviction(db: &Database) {
+    for _ in 0..1_000 {
+        if db.journal_count() == 1 {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    panic!("journal was not evicted");
+}
+
+fn list_journals(path: &Path) -> std::io::Result<Vec<String>> {
+    let mut names = std::fs::read_dir(path)?
+        .map(|dirent| Ok(dirent?.file_name().to_string_lossy().to_string()))
+        .filter(|name| name.as_ref().map_or(true, |name| name.ends_with(".jnl")))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn journal_archive_directory() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+    let archive_path = folder.path().join("archive");
+
+    let db = Database::builder(&db_path)
+        .journal_archiver(DirectoryArchiver::new(&archive_path))
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    tree.insert("a", "a")?;
+    tree.rotate_memtable_and_wait()?;
+    wait_for_journal_eviction(&db);
+
+    assert_eq!(vec!["0.jnl"], list_journals(&archive_path)?);
+    assert_eq!(vec!["1.jnl"], list_journals(&db_path)?);
+
+    drop(tree);
+    drop(db);
+
+    // Archived journal is not replayed
+    let db = Database::builder(&db_path).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(1, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_archive_directory_retention() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let archive_path = folder.path().join("archive");
+
+    let db = Database::builder(folder.path().join("db"))
+        .journal_archiver(DirectoryArchiver::new(&archive_path).max_journal_count(2))
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    for key in ["a", "b", "c", "d"] {
+        tree.insert(key, key)?;
+        tree.rotate_memtable_and_wait()?;
+        wait_for_journal_eviction(&db);
+    }
+
+    assert_eq!(vec!["2.jnl", "3.jnl"], list_journals(&archive_path)?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_archive_callback() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+
+    let archived = Arc::new(Mutex::new(Vec::<PathBuf>::new()));
+
+    let db = Database::builder(&db_path)
+        .journal_archiver({
+            let archived = archived.clone();
+
+            // NOTE: Does not move the file, so the database needs to delete it
+            move |path: &Path| {
+                archived.lock().expect("lock is poisoned").push(path.into());
+                Ok(())
+            }
+        })
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    tree.insert("a", "a")?;
+    tree.rotate_memtable_and_wait()?;
+    wait_for_journal_eviction(&db);
+
+    let archived = archived.lock().expect("lock is poisoned");
+    assert_eq!(1, archived.len());
+    assert!(archived[0].ends_with("0.jnl"));
+    assert_eq!(vec!["1.jnl"], list_journals(&db_path)?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_archive_failure_keeps_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+
+    let should_fail = Arc::new(AtomicBool::new(true));
+
+    let db = Database::builder(&db_path)
+        .journal_archiver({
+            let should_fail = should_fail.clone();
+
+            move |_: &Path| {
+                if should_fail.load(Ordering::Acquire) {
+                    return Err(std::io::Error::other("archive is unavailable").into());
+                }
+                Ok(())
+            }
+        })
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    tree.insert("a", "a")?;
+    tree.rotate_memtable_and_wait()?;
+
+    assert_eq!(2, db.journal_count());
+    assert_eq!(vec!["0.jnl", "1.jnl"], list_journals(&db_path)?);
+
+    // Archiving is retried on the next maintenance
+    should_fail.store(false, Ordering::Release);
+
+    tree.insert("b", "b")?;
+    tree.rotate_memtable_and_wait()?;
+    wait_for_journal_eviction(&db);
+
+    assert_eq!(vec!["2.jnl"], list_journals(&db_path)?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_archive_directory_no_overwrite() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let archive_path = folder.path().join("archive");
+
+    let db = Database::builder(folder.path().join("db"))
+        .journal_archiver(DirectoryArchiver::new(&archive_path))
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    tree.insert("a", "a")?;
+    tree.rotate_memtable_and_wait()?;
+    wait_for_journal_eviction(&db);
+
+    let archived = std::fs::read(archive_path.join("0.jnl"))?;
+
+    // NOTE: Another database sharing the archive directory
+    let other_path = folder.path().join("other");
+    let other = Database::builder(&other_path)
+        .journal_archiver(DirectoryArchiver::new(&archive_path))
+        .open()?;
+    let other_tree = other.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    other_tree.insert("b", "b".repeat(100))?;
+    other_tree.rotate_memtable_and_wait()?;
+
+    // The archived journal is not overwritten, so the other journal is kept
+    assert_eq!(2, other.journal_count());
+    assert_eq!(vec!["0.jnl", "1.jnl"], list_journals(&other_path)?);
+    assert_eq!(archived, std::fs::read(archive_path.join("0.jnl"))?);
+    assert_eq!(vec!["0.jnl"], list_journals(&archive_path)?);
+
+    Ok(())
+}
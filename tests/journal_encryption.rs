@@ -0,0 +1,208 @@
+use fjall::{Database, EncryptionProvider, KeyspaceCreateOptions};
+use test_log::test;
+
+/// Toy cipher, which also prefixes a key ID, so the wrong key is detected
+struct XorProvider(u8);
+
+impl EncryptionProvider for XorProvider {
+    fn encrypt(&self, plaintext: &[u8], _aad: &[u8]) -> fjall::Result<Vec<u8>> {
+        let mut bytes = vec![self.0];
+        bytes.extend(plaintext.iter().map(|b| b ^ self.0));
+        Ok(bytes)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8], _aad: &[u8]) -> fjall::Result<Vec<u8>> {
+        match ciphertext.split_first() {
+            Some((&key_id, bytes)) if key_id == self.0 => {
+                Ok(bytes.iter().map(|b| b ^ self.0).collect())
+            }
+            _ => Err(fjall::Error::Decrypt),
+        }
+    }
+}
+
+#[test]
+fn journal_encryption() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder)
+            .journal_encryption(XorProvider(42))
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("secret-key", "secret-value")?;
+
+        let mut batch = db.batch();
+        batch.insert(&tree, "secret-key2", "secret-value2");
+        batch.remove(&tree, "secret-key3");
+        batch.commit()?;
+
+        let changes = db.changes_since(0)?.collect::<fjall::Result<Vec<_>>>()?;
+        assert_eq!(2, changes.len());
+    }
+
+    let journal = std::fs::read(folder.path().join("0.jnl"))?;
+    assert!(!journal.windows(6).any(|window| window == b"secret"));
+
+    {
+        let db = Database::builder(&folder)
+            .journal_encryption(XorProvider(42))
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(2, tree.len()?);
+        assert_eq!(
+            Some("secret-value".as_bytes().into()),
+            tree.get("secret-key")?
+        );
+    }
+
+    Ok(())
+}
+
+/// Fails to encrypt anything that contains "fail"
+struct FailingProvider;
+
+impl EncryptionProvider for FailingProvider {
+    fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> fjall::Result<Vec<u8>> {
+        if plaintext.windows(4).any(|window| window == b"fail") {
+            return Err(std::io::Error::other("encryption failed").into());
+        }
+        XorProvider(42).encrypt(plaintext, aad)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8], aad: &[u8]) -> fjall::Result<Vec<u8>> {
+        XorProvider(42).decrypt(ciphertext, aad)
+    }
+}
+
+#[test]
+fn journal_encryption_failure() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder)
+            .journal_encryption(FailingProvider)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        let mut batch = db.batch();
+        batch.insert(&tree, "a", "a");
+        batch.insert(&tree, "b", "fail");
+        assert!(matches!(batch.commit(), Err(fjall::Error::Io(_))));
+
+        assert!(matches!(tree.insert("c", "fail"), Err(fjall::Error::Io(_))));
+
+        // Nothing was written, so the database is not poisoned
+        tree.insert("d", "d")?;
+        assert_eq!(1, tree.len()?);
+    }
+
+    {
+        let db = Database::builder(&folder)
+            .journal_encryption(FailingProvider)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(1, tree.len()?);
+        assert!(tree.contains_key("d")?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn journal_encryption_after_rotation() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder)
+            .journal_encryption(XorProvider(42))
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("secret-key", "secret-value")?;
+        tree.rotate_memtable_and_wait()?;
+        tree.insert("secret-key2", "secret-value2")?;
+    }
+
+    for dirent in std::fs::read_dir(&folder)? {
+        let path = dirent?.path();
+
+        if path.extension().is_some_and(|ext| ext == "jnl") {
+            let journal = std::fs::read(path)?;
+            assert!(!journal.windows(6).any(|window| window == b"secret"));
+        }
+    }
+
+    let db = Database::builder(&folder)
+        .journal_encryption(XorProvider(42))
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(2, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_encryption_wrong_key() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder)
+            .journal_encryption(XorProvider(42))
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a")?;
+        tree.insert("b", "b")?;
+    }
+
+    assert!(matches!(
+        Database::builder(&folder)
+            .journal_encryption(XorProvider(1))
+            .open(),
+        Err(fjall::Error::Decrypt),
+    ));
+
+    assert!(matches!(
+        Database::builder(&folder).open(),
+        Err(fjall::Error::Decrypt),
+    ));
+
+    // Nothing was truncated
+    let db = Database::builder(&folder)
+        .journal_encryption(XorProvider(42))
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(2, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn journal_encryption_chacha20_poly1305() -> fjall::Result<()> {
+    use fjall::ChaCha20Poly1305Provider;
+
+    let folder = tempfile::tempdir()?;
+    let key = [7; 32];
+
+    {
+        let db = Database::builder(&folder)
+            .journal_encryption(ChaCha20Poly1305Provider::new(key))
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a".repeat(10_000))?;
+    }
+
+    let db = Database::builder(&folder)
+        .journal_encryption(ChaCha20Poly1305Provider::new(key))
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(Some("a".repeat(10_000).as_bytes().into()), tree.get("a")?);
+
+    Ok(())
+}
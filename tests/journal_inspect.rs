@@ -0,0 +1,127 @@
+use fjall::{
+    tools::journal::{self, BatchStatus},
+    Database, KeyspaceCreateOptions,
+};
+use std::io::{Seek, SeekFrom, Write};
+use test_log::test;
+
+#[test]
+fn journal_inspect() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "abc")?;
+        tree.remove("b")?;
+
+        let mut batch = db.batch();
+        batch.insert(&tree, "c", "c");
+        batch.insert(&tree, "d", "d");
+        batch.commit()?;
+    }
+
+    let mut dump = journal::inspect(folder.path().join("0.jnl"))?;
+    dump.resolve_keyspace_names(&folder)?;
+
+    assert_eq!(None, dump.first_corrupt_offset);
+    assert_eq!(
+        vec![1, 1, 2],
+        dump.batches
+            .iter()
+            .map(|batch| batch.items.len())
+            .collect::<Vec<_>>(),
+    );
+    assert!(dump
+        .batches
+        .iter()
+        .all(|batch| batch.status == BatchStatus::Valid));
+
+    let item = &dump.batches[0].items[0];
+    assert_eq!(Some("default"), item.keyspace_name.as_deref());
+    assert_eq!(fjall::ChangeType::Insert, item.change_type);
+    assert_eq!(1, item.key_size);
+    assert_eq!(3, item.value_size);
+
+    assert_eq!(
+        fjall::ChangeType::Remove,
+        dump.batches[1].items[0].change_type
+    );
+
+    assert!(dump.to_string().contains("\"default\""));
+
+    Ok(())
+}
+
+#[test]
+fn journal_inspect_locked() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "abc")?;
+
+    let mut dump = journal::inspect(folder.path().join("0.jnl"))?;
+    assert_eq!(1, dump.batches.len());
+
+    assert!(matches!(
+        dump.resolve_keyspace_names(&folder),
+        Err(fjall::Error::Locked),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn journal_inspect_corrupt_truncate() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let path = folder.path().join("0.jnl");
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "aaaaaaaaaa")?;
+        tree.insert("b", "bbbbbbbbbb")?;
+        tree.insert("c", "cccccccccc")?;
+    }
+
+    let dump = journal::inspect(&path)?;
+    assert_eq!(3, dump.batches.len());
+
+    // Flip a byte in the value of the second batch
+    let second = &dump.batches[1];
+    {
+        let mut file = std::fs::OpenOptions::new().write(true).open(&path)?;
+        file.seek(SeekFrom::Start(dump.batches[2].offset - 12))?;
+        file.write_all(b"x")?;
+        file.sync_all()?;
+    }
+
+    let dump = journal::inspect(&path)?;
+    assert_eq!(3, dump.batches.len());
+    assert!(matches!(
+        dump.batches[1].status,
+        BatchStatus::ChecksumMismatch { .. },
+    ));
+    assert_eq!(BatchStatus::Valid, dump.batches[2].status);
+    assert_eq!(Some(second.offset), dump.first_corrupt_offset);
+    assert_eq!(second.offset, dump.valid_len);
+
+    assert!(matches!(
+        Database::builder(&folder).open(),
+        Err(fjall::Error::JournalRecovery(
+            fjall::JournalRecoveryError::ChecksumMismatch
+        )),
+    ));
+
+    journal::truncate(&path, dump.valid_len)?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(1, tree.len()?);
+    assert!(tree.contains_key("a")?);
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use fjall::{Database, KeyspaceCreateOptions};
+use std::path::Path;
+use test_log::test;
+
+fn journal_count(path: &Path) -> std::io::Result<usize> {
+    Ok(std::fs::read_dir(path)?
+        .filter_map(Result::ok)
+        .filter(|dirent| dirent.file_name().to_string_lossy().ends_with(".jnl"))
+        .count())
+}
+
+#[test]
+fn journal_path_separate_folder() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+    let journal_path = folder.path().join("wal");
+
+    {
+        let db = Database::builder(&db_path)
+            .journal_path(&journal_path)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a")?;
+        tree2.insert("a", "a")?;
+
+        // Seal the journal, without flushing the first keyspace
+        tree2.rotate_memtable()?;
+
+        tree.insert("b", "b")?;
+
+        assert_eq!(0, journal_count(&db_path)?);
+        assert_eq!(2, journal_count(&journal_path)?);
+    }
+
+    let db = Database::builder(&db_path)
+        .journal_path(&journal_path)
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(2, tree.len()?);
+    assert_eq!(0, journal_count(&db_path)?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_path_mismatch() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+    let journal_path = folder.path().join("wal");
+
+    {
+        let db = Database::builder(&db_path)
+            .journal_path(&journal_path)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+    }
+
+    assert!(matches!(
+        Database::builder(&db_path).open(),
+        Err(fjall::Error::JournalPathMismatch(path)) if path == journal_path,
+    ));
+
+    assert!(matches!(
+        Database::builder(&db_path)
+            .journal_path(folder.path().join("other"))
+            .open(),
+        Err(fjall::Error::JournalPathMismatch(_)),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn journal_path_mismatch_default() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+
+    {
+        let db = Database::builder(&db_path).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+    }
+
+    assert!(matches!(
+        Database::builder(&db_path)
+            .journal_path(folder.path().join("wal"))
+            .open(),
+        Err(fjall::Error::JournalPathMismatch(path)) if path == db_path,
+    ));
+
+    // Explicitly using the database folder is the same as the default
+    let db = Database::builder(&db_path).journal_path(&db_path).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(1, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_path_temporary() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+    let journal_path = folder.path().join("wal");
+
+    let db = Database::builder(&db_path)
+        .journal_path(&journal_path)
+        .temporary(true)
+        .open()?;
+    assert!(journal_path.try_exists()?);
+
+    drop(db);
+    assert!(!db_path.try_exists()?);
+    assert!(!journal_path.try_exists()?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_path_temporary_keeps_other_files() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let db_path = folder.path().join("db");
+    let journal_path = folder.path().join("wal");
+
+    std::fs::create_dir_all(&journal_path)?;
+    std::fs::write(journal_path.join("other"), "hello")?;
+
+    let db = Database::builder(&db_path)
+        .journal_path(&journal_path)
+        .temporary(true)
+        .open()?;
+    assert_eq!(1, journal_count(&journal_path)?);
+
+    drop(db);
+    assert!(!db_path.try_exists()?);
+    assert_eq!(0, journal_count(&journal_path)?);
+    assert_eq!(b"hello", &*std::fs::read(journal_path.join("other"))?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_path_shared() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let journal_path = folder.path().join("wal");
+
+    // Journal of another database
+    std::fs::create_dir_all(&journal_path)?;
+    std::fs::write(journal_path.join("5.jnl"), "")?;
+
+    assert!(matches!(
+        Database::builder(folder.path().join("db"))
+            .journal_path(&journal_path)
+            .open(),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::AlreadyExists,
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use fjall::{Database, KeyspaceCreateOptions, RecoveryMode};
+use std::io::Write;
+use test_log::test;
+
+#[test]
+fn journal_recovery_mode_corrupt_tail() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    for _ in 0..2 {
+        let db = Database::builder(&folder)
+            .journal_recovery_mode(RecoveryMode::AbsoluteConsistency)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        tree.insert("a", "a")?;
+    }
+
+    {
+        let db = Database::builder(&folder)
+            .journal_recovery_mode(RecoveryMode::AbsoluteConsistency)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        assert_eq!(1, tree.len()?);
+    }
+
+    // Simulate a torn write at the end of the active journal
+    {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(folder.path().join("0.jnl"))?;
+        file.write_all(&[1, 1, 0, 0])?;
+        file.sync_all()?;
+    }
+
+    assert!(Database::builder(&folder)
+        .journal_recovery_mode(RecoveryMode::AbsoluteConsistency)
+        .open()
+        .is_err());
+
+    {
+        let db = Database::builder(&folder)
+            .journal_recovery_mode(RecoveryMode::TolerateCorruptTail)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        assert_eq!(1, tree.len()?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use fjall::{Database, KeyspaceCreateOptions};
+use std::path::Path;
+use test_log::test;
+
+fn wait_for_journal_eviction(db: &Database) {
+    for _ in 0..1_000 {
+        if db.journal_count() == 1 {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    panic!("journal was not evicted");
+}
+
+fn list_files(path: &Path, suffix: &str) -> std::io::Result<Vec<String>> {
+    let mut names = std::fs::read_dir(path)?
+        .map(|dirent| Ok(dirent?.file_name().to_string_lossy().to_string()))
+        .filter(|name| name.as_ref().map_or(true, |name| name.ends_with(suffix)))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn journal_preallocation_size() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder)
+        .journal_preallocation_size(1_024 * 1_024)
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    assert_eq!(
+        1_024 * 1_024,
+        std::fs::metadata(folder.path().join("0.jnl"))?.len(),
+    );
+
+    tree.insert("a", "a")?;
+    tree.rotate_memtable()?;
+
+    assert_eq!(
+        1_024 * 1_024,
+        std::fs::metadata(folder.path().join("1.jnl"))?.len(),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn journal_recycle() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).max_recycled_journals(1).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        for key in ["a", "b", "c", "d"] {
+            tree.insert(key, key)?;
+        }
+        tree.rotate_memtable_and_wait()?;
+        wait_for_journal_eviction(&db);
+
+        assert_eq!(
+            vec!["0.jnl.recycle"],
+            list_files(folder.path(), ".recycle")?
+        );
+        assert_eq!(vec!["1.jnl"], list_files(folder.path(), ".jnl")?);
+
+        tree.insert("e", "e")?;
+        tree.rotate_memtable_and_wait()?;
+        wait_for_journal_eviction(&db);
+
+        // The recycled file was reused as the new active journal
+        assert_eq!(
+            vec!["1.jnl.recycle"],
+            list_files(folder.path(), ".recycle")?
+        );
+        assert_eq!(vec!["2.jnl"], list_files(folder.path(), ".jnl")?);
+
+        tree.insert("f", "f")?;
+        tree.remove("a")?;
+    }
+
+    {
+        let db = Database::builder(&folder).max_recycled_journals(1).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        // Unused recycled journals are cleaned up on recovery
+        assert!(list_files(folder.path(), ".recycle")?.is_empty());
+
+        assert_eq!(5, tree.len()?);
+        assert!(!tree.contains_key("a")?);
+        assert!(tree.contains_key("f")?);
+
+        // Keep writing into the recovered recycled journal
+        tree.insert("g", "g")?;
+    }
+
+    {
+        let db = Database::builder(&folder).max_recycled_journals(1).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(6, tree.len()?);
+        assert!(tree.contains_key("g")?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn journal_recycle_read_only_view() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).max_recycled_journals(1).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "a")?;
+
+    // Links the active journal into its view
+    let read_only = Database::builder(&folder).read_only(true).open()?;
+
+    let view_journal = std::fs::read_dir(folder.path())?
+        .filter_map(Result::ok)
+        .find(|dirent| {
+            dirent
+                .file_name()
+                .to_string_lossy()
+                .starts_with(".read_only")
+        })
+        .expect("view should exist")
+        .path()
+        .join("journals")
+        .join("0.jnl");
+    let view_journal_bytes = std::fs::read(&view_journal)?;
+
+    tree.rotate_memtable_and_wait()?;
+    wait_for_journal_eviction(&db);
+    assert_eq!(
+        vec!["0.jnl.recycle"],
+        list_files(folder.path(), ".recycle")?
+    );
+
+    tree.insert("b", "b".repeat(1_000))?;
+    tree.rotate_memtable_and_wait()?;
+
+    // The recycled journal was not reused, because it is still linked by the view
+    assert_eq!(view_journal_bytes, std::fs::read(&view_journal)?);
+
+    drop(read_only);
+
+    Ok(())
+}
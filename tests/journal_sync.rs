@@ -0,0 +1,55 @@
+use fjall::{Database, KeyspaceCreateOptions};
+use std::time::Duration;
+use test_log::test;
+
+#[test]
+fn journal_sync_interval() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder)
+            .journal_sync_interval(Duration::from_millis(5))
+            .journal_bytes_per_sync(1_024)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        for i in 0..100u32 {
+            tree.insert(i.to_be_bytes(), "a".repeat(100))?;
+        }
+
+        std::thread::sleep(Duration::from_millis(20));
+
+        db.set_journal_sync_interval(None)?;
+        db.set_journal_bytes_per_sync(None)?;
+
+        tree.insert("b", "b")?;
+
+        db.set_journal_sync_interval(Some(Duration::from_millis(1)))?;
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(101, tree.len()?);
+
+    Ok(())
+}
+
+#[test]
+fn journal_bytes_per_sync_zero_disables() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder)
+        .journal_bytes_per_sync(0)
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    tree.insert("a", "a")?;
+
+    db.set_journal_bytes_per_sync(Some(0))?;
+    tree.insert("b", "b")?;
+
+    assert_eq!(2, tree.len()?);
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use fjall::{Database, KeyspaceCreateOptions, KvSeparationOptions};
+use test_log::test;
+
+#[test]
+fn keyspace_export_import() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(folder.path().join("a")).open()?;
+    let tree = db.keyspace("default", || {
+        KeyspaceCreateOptions::default()
+            .expect_point_read_hits(true)
+            .with_kv_separation(Some(
+                KvSeparationOptions::default().separation_threshold(100),
+            ))
+    })?;
+
+    for x in 0..100u32 {
+        tree.insert(x.to_be_bytes(), x.to_string())?;
+    }
+    tree.insert("big", "a".repeat(1_000))?;
+    tree.rotate_memtable_and_wait()?;
+    tree.remove(0u32.to_be_bytes())?;
+
+    let snapshot = db.snapshot();
+
+    // Not visible in snapshot
+    tree.insert("new", "new")?;
+
+    let mut export = vec![];
+    assert_eq!(100, tree.export(&snapshot, &mut export)?);
+
+    let other_db = Database::builder(folder.path().join("b")).open()?;
+    let imported = other_db.import(&*export)?;
+
+    assert_eq!("default", &**imported.name());
+    assert_eq!(100, imported.len()?);
+    assert!(!imported.contains_key(0u32.to_be_bytes())?);
+    assert!(!imported.contains_key("new")?);
+    assert_eq!(
+        Some("a".repeat(1_000).as_bytes().into()),
+        imported.get("big")?
+    );
+
+    // Options are restored as well
+    assert!(imported.config.expect_point_read_hits);
+    assert!(imported.config.kv_separation_opts.is_some());
+    assert_eq!(1, imported.blob_file_count());
+
+    assert!(other_db.keyspace_exists("default"));
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_import_existing() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    tree.insert("a", "a")?;
+
+    let mut export = vec![];
+    tree.export(&db.snapshot(), &mut export)?;
+
+    assert!(matches!(
+        db.import(&*export),
+        Err(fjall::Error::Io(e)) if e.kind() == std::io::ErrorKind::AlreadyExists,
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_import_corrupted() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(folder.path().join("a")).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    for x in 0..100u32 {
+        tree.insert(x.to_be_bytes(), x.to_string())?;
+    }
+
+    let mut export = vec![];
+    tree.export(&db.snapshot(), &mut export)?;
+
+    let other_db = Database::builder(folder.path().join("b")).open()?;
+
+    // Corrupt an item
+    {
+        let mut export = export.clone();
+        let idx = export.len() - 100;
+        export[idx] ^= 0xFF;
+
+        assert!(other_db.import(&*export).is_err());
+        assert!(!other_db.keyspace_exists("default"));
+    }
+
+    // Truncated
+    {
+        assert!(other_db.import(&export[..export.len() - 1]).is_err());
+        assert!(!other_db.keyspace_exists("default"));
+    }
+
+    other_db.import(&*export)?;
+    assert!(other_db.keyspace_exists("default"));
+
+    Ok(())
+}
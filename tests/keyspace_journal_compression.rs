@@ -0,0 +1,99 @@
+use fjall::{tools::journal, Database, JournalCompression, KeyspaceCreateOptions};
+use std::path::Path;
+use test_log::test;
+
+fn journal_compressions(path: &Path) -> fjall::Result<Vec<JournalCompression>> {
+    let dump = journal::inspect(path)?;
+
+    Ok(dump
+        .batches
+        .iter()
+        .flat_map(|batch| &batch.items)
+        .map(|item| item.compression)
+        .collect())
+}
+
+#[test]
+#[cfg(feature = "lz4")]
+fn keyspace_journal_compression_override() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let value = "a".repeat(1_000);
+
+    {
+        let db = Database::builder(&folder)
+            .journal_compression(JournalCompression::Lz4)
+            .journal_compression_threshold(100)
+            .open()?;
+
+        let compressed = db.keyspace("compressed", KeyspaceCreateOptions::default)?;
+        let uncompressed = db.keyspace("uncompressed", || {
+            KeyspaceCreateOptions::default().journal_compression(JournalCompression::None)
+        })?;
+
+        compressed.insert("a", &value)?;
+        uncompressed.insert("a", &value)?;
+
+        // Small values are not compressed
+        compressed.insert("b", "b")?;
+
+        let mut batch = db.batch();
+        batch.insert(&compressed, "c", &value);
+        batch.insert(&uncompressed, "c", &value);
+        batch.commit()?;
+    }
+
+    assert_eq!(
+        vec![
+            JournalCompression::Lz4,
+            JournalCompression::None,
+            JournalCompression::None,
+            JournalCompression::Lz4,
+            JournalCompression::None,
+        ],
+        journal_compressions(&folder.path().join("0.jnl"))?,
+    );
+
+    // The override is persisted
+    {
+        let db = Database::builder(&folder)
+            .journal_compression(JournalCompression::Lz4)
+            .journal_compression_threshold(100)
+            .open()?;
+
+        let uncompressed = db.keyspace("uncompressed", KeyspaceCreateOptions::default)?;
+        assert_eq!(2, uncompressed.len()?);
+
+        uncompressed.insert("d", &value)?;
+    }
+
+    let compressions = journal_compressions(&folder.path().join("0.jnl"))?;
+    assert_eq!(Some(&JournalCompression::None), compressions.last());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "lz4")]
+fn journal_compression_threshold_disabled() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder)
+            .journal_compression(JournalCompression::Lz4)
+            .journal_compression_threshold(0)
+            .open()?;
+
+        let tree = db.keyspace("default", || {
+            KeyspaceCreateOptions::default().journal_compression(JournalCompression::Lz4)
+        })?;
+
+        tree.insert("a", "a".repeat(10_000))?;
+    }
+
+    assert_eq!(
+        vec![JournalCompression::None],
+        journal_compressions(&folder.path().join("0.jnl"))?,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use fjall::{Database, KeyspaceCreateOptions};
+use test_log::test;
+
+#[test]
+fn keyspace_no_journal_recover() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let cache = db.keyspace("cache", || {
+            KeyspaceCreateOptions::default().journaling(false)
+        })?;
+
+        cache.insert("a", "a")?;
+        cache.rotate_memtable_and_wait()?;
+
+        cache.insert("b", "b")?;
+        cache.remove("a")?;
+
+        assert_eq!(1, cache.len()?);
+        assert_eq!(0, db.changes_since(0)?.count());
+    }
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let cache = db.keyspace("cache", KeyspaceCreateOptions::default)?;
+
+        // Unflushed writes are lost
+        assert_eq!(1, cache.len()?);
+        assert!(cache.contains_key("a")?);
+        assert!(!cache.contains_key("b")?);
+
+        // The option is persisted
+        cache.insert("c", "c")?;
+        assert_eq!(0, db.changes_since(0)?.count());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_no_journal_batch() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let cache = db.keyspace("cache", || {
+            KeyspaceCreateOptions::default().journaling(false)
+        })?;
+
+        // Batches can not mix keyspaces with and without journaling
+        let mut batch = db.batch();
+        batch.insert(&tree, "a", "a");
+        batch.insert(&cache, "a", "a");
+        assert!(matches!(batch.commit(), Err(fjall::Error::MixedJournaling)));
+        assert!(tree.is_empty()?);
+        assert!(cache.is_empty()?);
+
+        let mut batch = db.batch();
+        batch.insert(&tree, "a", "a");
+        batch.commit()?;
+
+        let mut batch = db.batch();
+        batch.insert(&cache, "a", "a");
+        batch.commit()?;
+
+        let mut batch = db.batch().durability(Some(fjall::PersistMode::SyncAll));
+        batch.insert(&cache, "b", "b");
+        batch.commit()?;
+
+        assert_eq!(2, cache.len()?);
+
+        let changes = db.changes_since(0)?.collect::<fjall::Result<Vec<_>>>()?;
+        assert_eq!(1, changes.len());
+        assert_eq!(1, changes[0].items.len());
+    }
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let cache = db.keyspace("cache", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(1, tree.len()?);
+        assert!(cache.is_empty()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_no_journal_does_not_hold_back_eviction() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let cache = db.keyspace("cache", || {
+        KeyspaceCreateOptions::default().journaling(false)
+    })?;
+
+    cache.insert("a", "a")?;
+
+    // Rotating a keyspace without journaling does not seal the journal
+    cache.rotate_memtable_and_wait()?;
+    assert_eq!(1, db.journal_count());
+
+    cache.insert("b", "b")?;
+    tree.insert("a", "a")?;
+    tree.rotate_memtable_and_wait()?;
+
+    for _ in 0..1_000 {
+        if db.journal_count() == 1 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    // The unflushed memtable of the cache does not keep the sealed journal alive
+    assert_eq!(1, db.journal_count());
+    assert_eq!(2, cache.len()?);
+
+    Ok(())
+}
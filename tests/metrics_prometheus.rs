@@ -0,0 +1,55 @@
+#![cfg(feature = "metrics")]
+
+use fjall::{Database, KeyspaceCreateOptions};
+use test_log::test;
+
+#[test]
+fn metrics_prometheus() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    let other = db.keyspace("other\"\\", KeyspaceCreateOptions::default)?;
+
+    for x in 0..100u32 {
+        tree.insert(x.to_be_bytes(), "a")?;
+    }
+    other.insert("a", "a")?;
+    tree.rotate_memtable_and_wait()?;
+
+    assert!(tree.get(0u32.to_be_bytes())?.is_some());
+
+    let mut buf = vec![];
+    fjall::metrics::encode_prometheus(&db, &mut buf)?;
+    let text = String::from_utf8(buf).expect("should be utf-8");
+
+    for line in text.lines() {
+        if let Some(comment) = line.strip_prefix("# ") {
+            assert!(comment.starts_with("HELP fjall_") || comment.starts_with("TYPE fjall_"));
+        } else {
+            assert!(line.starts_with("fjall_"), "{line}");
+            assert_eq!(2, line.rsplitn(2, ' ').count(), "{line}");
+        }
+    }
+
+    assert!(text.contains("# TYPE fjall_compactions_completed_total counter\n"));
+    assert!(text.contains(&format!("\nfjall_journals {}\n", db.journal_count())));
+    assert!(text.contains("\nfjall_poisoned 0\n"));
+    assert!(text.contains("\nfjall_keyspace_tables{keyspace=\"default\"} 1\n"));
+    assert!(text.contains("\nfjall_keyspace_approximate_items{keyspace=\"other\\\"\\\\\"} 1\n"));
+    assert!(text.contains(
+        "\nfjall_keyspace_block_loads_total{keyspace=\"default\",block_type=\"data\",source=\"io\"} 1\n"
+    ));
+
+    // Every family is only declared once
+    let mut families = text
+        .lines()
+        .filter(|line| line.starts_with("# TYPE"))
+        .collect::<Vec<_>>();
+    let count = families.len();
+    families.sort_unstable();
+    families.dedup();
+    assert_eq!(count, families.len());
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use fjall::{Database, KeyspaceCreateOptions};
+use test_log::test;
+
+fn batch_seqnos(db: &Database) -> fjall::Result<Vec<fjall::SeqNo>> {
+    db.changes_since(0)?
+        .map(|batch| batch.map(|batch| batch.seqno))
+        .collect()
+}
+
+#[test]
+fn recover_until_seqno_active_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let target = {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a")?;
+        tree.insert("b", "b")?;
+        tree.insert("c", "c")?;
+
+        batch_seqnos(&db)?[1]
+    };
+
+    {
+        let db = Database::builder(&folder)
+            .recover_until_seqno(target)
+            .open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        assert_eq!(2, tree.len()?);
+        assert!(!tree.contains_key("c")?);
+
+        tree.insert("d", "d")?;
+    }
+
+    // Discarded batches are gone for good
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    assert_eq!(3, tree.len()?);
+    assert!(!tree.contains_key("c")?);
+    assert!(tree.contains_key("d")?);
+
+    Ok(())
+}
+
+#[test]
+fn recover_until_seqno_sealed_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let target = {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+        let tree2 = db.keyspace("default2", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a")?;
+        tree.insert("b", "b")?;
+
+        // Rotate the journal without flushing the first keyspace, so its data stays journaled
+        tree2.rotate_memtable()?;
+
+        tree.insert("c", "c")?;
+
+        batch_seqnos(&db)?[0]
+    };
+
+    let db = Database::builder(&folder)
+        .recover_until_seqno(target)
+        .open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+    assert_eq!(1, tree.len()?);
+    assert!(tree.contains_key("a")?);
+
+    Ok(())
+}
+
+#[test]
+fn recover_until_seqno_unreachable() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let db = Database::builder(&folder).open()?;
+        let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+
+        tree.insert("a", "a")?;
+        tree.insert("b", "b")?;
+        tree.rotate_memtable_and_wait()?;
+    }
+
+    assert!(matches!(
+        Database::builder(&folder).recover_until_seqno(0).open(),
+        Err(fjall::Error::RecoveryTargetUnreachable(_)),
+    ));
+
+    // Nothing was discarded
+    let db = Database::builder(&folder).open()?;
+    let tree = db.keyspace("default", KeyspaceCreateOptions::default)?;
+    assert_eq!(2, tree.len()?);
+
+    Ok(())
+}